@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug, Default)]
@@ -9,6 +10,19 @@ pub struct Cli {
     /// Open an article with the given id
     pub article_id: Option<i32>,
 
+    #[structopt(long = "random")]
+    /// Open a random article
+    pub random: bool,
+
+    #[structopt(long = "print")]
+    /// Print the article of the search query, the article id or a random article as plain text
+    /// instead of opening it
+    pub print: bool,
+
+    #[structopt(long = "config", parse(from_os_str))]
+    /// Read the configuration from the given file instead of the default one
+    pub config: Option<PathBuf>,
+
     #[structopt(short = "l", long = "level")]
     /// Override the log level. Levels are:
     /// - Debug: 0
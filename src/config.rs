@@ -1,14 +1,21 @@
 use crate::cli::Cli;
+use crate::wiki::request::{DEFAULT_SCRIPT_PATH, DEFAULT_USER_AGENT};
 
 use anyhow::{bail, Context, Result};
 use cursive::{
+    direction::Orientation,
     event::{Event, Key},
     theme::{BaseColor, Color},
 };
 use lazy_static::*;
 use log::LevelFilter;
 use serde::Deserialize;
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 #[cfg(not(test))]
 use structopt::StructOpt;
 use toml::from_str;
@@ -16,6 +23,12 @@ use toml::from_str;
 const CONFIG_FILE: &str = "config.toml";
 const CONFIG_DIR: &str = ".config";
 const APP_DIR: &str = "wiki-tui";
+const SEARCH_HISTORY_FILE: &str = "search_history";
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+const READING_LOG_FILE: &str = "reading_log.json";
+const LOCALES_DIR: &str = "locales";
+const LAST_WIKI_FILE: &str = "last_wiki";
+const VISITED_LINKS_FILE: &str = "visited_links.json";
 
 lazy_static! {
     pub static ref CONFIG: Config = Config::new();
@@ -36,6 +49,9 @@ pub struct Theme {
 
     pub article_view: Option<ViewTheme>,
     pub toc_view: Option<ViewTheme>,
+
+    /// The themes of the article and toc views for the articles of a wiki, by its host
+    pub wikis: HashMap<String, ViewTheme>,
 }
 
 impl Theme {
@@ -57,6 +73,80 @@ impl Theme {
             ..Default::default()
         }
     }
+
+    /// The theme of the article view for an article from a given wiki. The theme of the wiki
+    /// replaces the one of the article view
+    pub fn article_view_for(&self, base_url: Option<&str>) -> Option<&ViewTheme> {
+        self.wiki_theme(base_url).or(self.article_view.as_ref())
+    }
+
+    /// The theme of the toc view for an article from a given wiki. The theme of the wiki
+    /// replaces the one of the toc view
+    pub fn toc_view_for(&self, base_url: Option<&str>) -> Option<&ViewTheme> {
+        self.wiki_theme(base_url).or(self.toc_view.as_ref())
+    }
+
+    /// Helper function that returns the theme configured for a given wiki
+    fn wiki_theme(&self, base_url: Option<&str>) -> Option<&ViewTheme> {
+        self.wikis.get(wiki_host(base_url?))
+    }
+
+    /// The color of code blocks in the article view
+    pub fn code_block(&self) -> Color {
+        self.article_view
+            .as_ref()
+            .map_or(DEFAULT_CODE_BLOCK_COLOR, |theme| theme.code_block)
+    }
+
+    /// The color of quotes in the article view
+    pub fn quote(&self) -> Color {
+        self.article_view
+            .as_ref()
+            .map_or(self.text, |theme| theme.quote)
+    }
+
+    /// The color of visited links in the article view for an article from a given wiki
+    pub fn visited_link_for(&self, base_url: Option<&str>) -> Color {
+        self.article_view_for(base_url)
+            .map_or(DEFAULT_VISITED_LINK_COLOR, |theme| theme.visited_link)
+    }
+
+    /// How the links of an article from a given wiki are decorated, together with their color
+    pub fn links_for(&self, base_url: Option<&str>) -> (LinkDecoration, Color) {
+        self.article_view_for(base_url)
+            .map_or((LinkDecoration::default(), DEFAULT_LINK_COLOR), |theme| {
+                (theme.link_decoration, theme.link)
+            })
+    }
+}
+
+/// The color of code blocks when the article view doesn't configure one
+const DEFAULT_CODE_BLOCK_COLOR: Color = Color::Dark(BaseColor::Blue);
+/// The color of links when the article view doesn't configure one
+const DEFAULT_LINK_COLOR: Color = Color::Dark(BaseColor::Blue);
+/// The color of visited links when the article view doesn't configure one
+const DEFAULT_VISITED_LINK_COLOR: Color = Color::Light(BaseColor::Black);
+
+/// How the links in the article view are told apart from the text. The decorations can be
+/// combined, by default links are underlined
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LinkDecoration {
+    /// Are links underlined?
+    pub underline: bool,
+    /// Are links surrounded by brackets?
+    pub brackets: bool,
+    /// Do links have their own color?
+    pub color: bool,
+}
+
+impl Default for LinkDecoration {
+    fn default() -> Self {
+        LinkDecoration {
+            underline: true,
+            brackets: false,
+            color: false,
+        }
+    }
 }
 
 pub struct ViewTheme {
@@ -68,6 +158,20 @@ pub struct ViewTheme {
     pub highlight: Color,
     pub highlight_text: Color,
     pub highlight_inactive: Color,
+    /// The color of the selected link, only used by the article view
+    pub current_link: Color,
+    /// The background color of selected text, only used by the article view
+    pub selection: Color,
+    /// The color of code blocks, only used by the article view
+    pub code_block: Color,
+    /// The color of quotes, only used by the article view
+    pub quote: Color,
+    /// The color of links with the color decoration, only used by the article view
+    pub link: Color,
+    /// The color of links to articles that have been opened, only used by the article view
+    pub visited_link: Color,
+    /// How links are decorated, only used by the article view
+    pub link_decoration: LinkDecoration,
 }
 
 impl ViewTheme {
@@ -94,17 +198,53 @@ impl ViewTheme {
 #[derive(Clone, Debug)]
 pub struct ApiConfig {
     pub base_url: String,
+    pub script_path: String,
+    pub timeout_ms: u64,
+    pub max_retries: u32,
+    pub user_agent: String,
+    pub wikis: Vec<Wiki>,
+    pub remember_wiki: bool,
+    pub last_wiki_path: Option<PathBuf>,
+}
+
+impl ApiConfig {
+    /// The wiki configured with base_url and script_path
+    pub fn default_wiki(&self) -> Wiki {
+        Wiki {
+            name: wiki_host(&self.base_url).to_string(),
+            base_url: self.base_url.clone(),
+            script_path: self.script_path.clone(),
+        }
+    }
+}
+
+/// A MediaWiki instance, like wikipedia or wiktionary
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wiki {
+    pub name: String,
+    pub base_url: String,
+    pub script_path: String,
 }
 
 pub struct Logging {
     pub enabled: bool,
     pub log_dir: PathBuf,
     pub log_level: LevelFilter,
+    pub buffer_size: usize,
 }
 
 pub struct Features {
     pub links: bool,
     pub toc: bool,
+    pub external_links: bool,
+    pub images: bool,
+    pub logo: bool,
+    pub offline: bool,
+    pub prefetch: bool,
+    pub vim_motions: bool,
+    pub status_bar: bool,
+    pub reading_log: bool,
+    pub visited_links: bool,
 }
 
 #[derive(Clone)]
@@ -116,10 +256,145 @@ pub struct Keybindings {
 
     pub focus_next: Event,
     pub focus_prev: Event,
+
+    pub history_back: Event,
+    pub history_forward: Event,
+
+    pub toggle_link_preview: Event,
+    pub link_hints: Event,
+
+    pub search_article: Event,
+    pub search_next: Event,
+    pub search_previous: Event,
+
+    pub toggle_bookmark: Event,
+    pub bookmarks: Event,
+    pub switch_wiki: Event,
+    pub article_info: Event,
+    pub copy_url: Event,
+    pub open_in_browser: Event,
+    pub scroll_top: Event,
+    pub scroll_bottom: Event,
+    pub references: Event,
+    pub toggle_toc_section: Event,
+    pub open_section: Event,
+    pub export_article: Event,
+    pub toggle_line_width: Event,
+    pub toggle_spacing: Event,
+    pub random_article: Event,
+    pub half_page_up: Event,
+    pub half_page_down: Event,
+    pub page_up: Event,
+    pub page_down: Event,
+    pub command_palette: Event,
+    pub open_in_new_tab: Event,
+    pub open_in_split: Event,
+    pub open_in_background: Event,
+    pub next_tab: Event,
+    pub previous_tab: Event,
+    pub close_tab: Event,
+    pub reading_log: Event,
+    pub search_mode: Event,
+    pub select_text: Event,
+    pub see_also: Event,
+    pub next_header: Event,
+    pub previous_header: Event,
+    pub copy_link: Event,
+    pub open_first_link: Event,
+    pub show_logs: Event,
+    pub search_selection: Event,
+    pub reload_article: Event,
+    pub cycle_toc_position: Event,
+}
+
+impl Keybindings {
+    /// Returns every keybinding together with its name in the configuration
+    pub fn named(&self) -> Vec<(&'static str, &Event)> {
+        vec![
+            ("down", &self.down),
+            ("up", &self.up),
+            ("left", &self.left),
+            ("right", &self.right),
+            ("focus_next", &self.focus_next),
+            ("focus_prev", &self.focus_prev),
+            ("history_back", &self.history_back),
+            ("history_forward", &self.history_forward),
+            ("toggle_link_preview", &self.toggle_link_preview),
+            ("link_hints", &self.link_hints),
+            ("search_article", &self.search_article),
+            ("search_next", &self.search_next),
+            ("search_previous", &self.search_previous),
+            ("toggle_bookmark", &self.toggle_bookmark),
+            ("bookmarks", &self.bookmarks),
+            ("switch_wiki", &self.switch_wiki),
+            ("article_info", &self.article_info),
+            ("copy_url", &self.copy_url),
+            ("open_in_browser", &self.open_in_browser),
+            ("scroll_top", &self.scroll_top),
+            ("scroll_bottom", &self.scroll_bottom),
+            ("references", &self.references),
+            ("toggle_toc_section", &self.toggle_toc_section),
+            ("open_section", &self.open_section),
+            ("export_article", &self.export_article),
+            ("toggle_line_width", &self.toggle_line_width),
+            ("toggle_spacing", &self.toggle_spacing),
+            ("random_article", &self.random_article),
+            ("half_page_up", &self.half_page_up),
+            ("half_page_down", &self.half_page_down),
+            ("page_up", &self.page_up),
+            ("page_down", &self.page_down),
+            ("command_palette", &self.command_palette),
+            ("open_in_new_tab", &self.open_in_new_tab),
+            ("open_in_split", &self.open_in_split),
+            ("open_in_background", &self.open_in_background),
+            ("next_tab", &self.next_tab),
+            ("previous_tab", &self.previous_tab),
+            ("close_tab", &self.close_tab),
+            ("reading_log", &self.reading_log),
+            ("search_mode", &self.search_mode),
+            ("select_text", &self.select_text),
+            ("see_also", &self.see_also),
+            ("next_header", &self.next_header),
+            ("previous_header", &self.previous_header),
+            ("copy_link", &self.copy_link),
+            ("open_first_link", &self.open_first_link),
+            ("show_logs", &self.show_logs),
+            ("search_selection", &self.search_selection),
+            ("reload_article", &self.reload_article),
+            ("cycle_toc_position", &self.cycle_toc_position),
+        ]
+    }
+
+    /// Returns the names of every pair of keybindings using the same key
+    pub fn conflicts(&self) -> Vec<(&'static str, &'static str)> {
+        let named = self.named();
+        let mut conflicts = Vec::new();
+        for (i, (name, event)) in named.iter().enumerate() {
+            for (other_name, other_event) in named[i + 1..].iter() {
+                if event == other_event {
+                    conflicts.push((*name, *other_name));
+                }
+            }
+        }
+        conflicts
+    }
 }
 
 pub struct Settings {
     pub toc: TocSettings,
+    pub cache: CacheSettings,
+    pub search: SearchSettings,
+    pub links: LinksSettings,
+    pub article: ArticleSettings,
+    pub bookmarks: BookmarksSettings,
+    pub reading_log: ReadingLogSettings,
+    pub export: ExportSettings,
+    pub start_screen: StartScreenSettings,
+    pub confirm_link_open: bool,
+    pub parser: ParserType,
+    pub locale_path: Option<PathBuf>,
+    pub scroll_lines: usize,
+    pub wheel_lines: usize,
 }
 
 #[derive(Clone)]
@@ -129,15 +404,126 @@ pub struct TocSettings {
     pub title_custom: Option<String>,
     pub min_width: usize,
     pub max_width: usize,
+    pub max_height: usize,
+    pub hide_below_width: usize,
+    pub hide_below_height: usize,
     pub scroll_x: bool,
     pub scroll_y: bool,
     pub item_format: String,
 }
 
 #[derive(Clone)]
+pub struct CacheSettings {
+    pub enabled: bool,
+    pub ttl: u64,
+    pub path: Option<PathBuf>,
+    pub prefetch_limit: usize,
+    pub max_entries: usize,
+    /// Where the cached articles are stored
+    pub storage: CacheStorage,
+}
+
+#[derive(Clone)]
+pub struct SearchSettings {
+    pub history_size: usize,
+    pub results_limit: usize,
+    pub infinite_scroll: bool,
+    pub auto_open_single_result: bool,
+    /// Can a query start with a language code, searching the wiki in that language?
+    pub language_prefix: bool,
+    pub live: bool,
+    pub live_delay_ms: u64,
+    pub preview_length: usize,
+    pub cache_ttl: u64,
+    pub history_path: Option<PathBuf>,
+    /// The metadata displayed next to the title of every search result
+    pub columns: Vec<SearchColumn>,
+}
+
+#[derive(Clone)]
+pub struct LinksSettings {
+    pub wrap: bool,
+    pub stay_on_line: bool,
+    pub double_click: bool,
+    pub step: usize,
+    /// Articles with more links than this only register the links in view, 0 registers every link
+    pub max_links: usize,
+    pub confirm_label: String,
+    pub cancel_label: String,
+    pub default_button: ConfirmButton,
+    pub open_mode: LinkOpenMode,
+    pub split: SplitOrientation,
+    pub namespaces: HashMap<String, NamespaceBehavior>,
+    /// For how long links are remembered as visited
+    pub visited: VisitedLinksScope,
+    /// The file the visited links are stored in when they're remembered between sessions
+    pub visited_path: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct ArticleSettings {
+    pub max_line_width: Option<usize>,
+    pub alignment: ArticleAlignment,
+    pub scroll_progress: bool,
+    pub spacing: LineSpacing,
+    /// How many minutes pass between refreshing the displayed article, 0 disables it
+    pub auto_refresh: u64,
+    /// Are infoboxes displayed as panels?
+    pub infobox: bool,
+    /// Is the redirect that led to an article mentioned at its top?
+    pub redirect_notice: bool,
+}
+
+#[derive(Clone)]
+pub struct BookmarksSettings {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct ReadingLogSettings {
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct StartScreenSettings {
+    /// The quick actions offered on the start screen, in the order they are listed in
+    pub actions: Vec<StartAction>,
+    /// How many of the articles read last are listed below the actions
+    pub recent_articles: usize,
+}
+
+#[derive(Clone)]
+pub struct ExportSettings {
+    pub path: Option<PathBuf>,
+    pub format: ExportFormat,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TocPosition {
     LEFT,
     RIGHT,
+    TOP,
+    BOTTOM,
+}
+
+impl TocPosition {
+    /// The orientation of the layout containing the article and the table of contents
+    pub fn orientation(&self) -> Orientation {
+        match self {
+            TocPosition::LEFT | TocPosition::RIGHT => Orientation::Horizontal,
+            TocPosition::TOP | TocPosition::BOTTOM => Orientation::Vertical,
+        }
+    }
+
+    /// The position on the other side of the article, with the same orientation
+    pub fn opposite(&self) -> TocPosition {
+        match self {
+            TocPosition::LEFT => TocPosition::RIGHT,
+            TocPosition::RIGHT => TocPosition::LEFT,
+            TocPosition::TOP => TocPosition::BOTTOM,
+            TocPosition::BOTTOM => TocPosition::TOP,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -147,6 +533,139 @@ pub enum TocTitle {
     ARTICLE,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ConfirmButton {
+    CONFIRM,
+    CANCEL,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LinkOpenMode {
+    REPLACE,
+    TAB,
+    SPLIT,
+    BACKGROUND,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VisitedLinksScope {
+    SESSION,
+    PERSISTENT,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NamespaceBehavior {
+    OPEN,
+    WARN,
+    BROWSER,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitOrientation {
+    HORIZONTAL,
+    VERTICAL,
+}
+
+impl SplitOrientation {
+    /// The orientation of the layout containing the split views
+    pub fn orientation(&self) -> Orientation {
+        match self {
+            SplitOrientation::HORIZONTAL => Orientation::Horizontal,
+            SplitOrientation::VERTICAL => Orientation::Vertical,
+        }
+    }
+}
+
+/// A quick action that can be offered on the start screen
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StartAction {
+    SEARCH,
+    RANDOM,
+    RECENT,
+    BOOKMARKS,
+}
+
+/// The metadata of a search result that can be displayed in a column of the results
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SearchColumn {
+    WORDCOUNT,
+    EDITED,
+    SIZE,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineSpacing {
+    COMPACT,
+    NORMAL,
+    SPACIOUS,
+}
+
+impl LineSpacing {
+    /// How many blank lines separate paragraphs, list items and other blocks
+    pub fn paragraph_lines(&self) -> usize {
+        match self {
+            LineSpacing::COMPACT => 0,
+            LineSpacing::NORMAL => 1,
+            LineSpacing::SPACIOUS => 2,
+        }
+    }
+
+    /// How many blank lines there are above a header at least
+    pub fn header_lines(&self) -> usize {
+        match self {
+            LineSpacing::COMPACT => 0,
+            LineSpacing::NORMAL => 1,
+            LineSpacing::SPACIOUS => 2,
+        }
+    }
+
+    /// Returns the spacing that follows this one, after the last one the first one follows
+    pub fn next(&self) -> Self {
+        match self {
+            LineSpacing::COMPACT => LineSpacing::NORMAL,
+            LineSpacing::NORMAL => LineSpacing::SPACIOUS,
+            LineSpacing::SPACIOUS => LineSpacing::COMPACT,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArticleAlignment {
+    LEFT,
+    CENTER,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportFormat {
+    TEXT,
+    MARKDOWN,
+}
+
+impl ExportFormat {
+    /// The extension of the files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::TEXT => "txt",
+            ExportFormat::MARKDOWN => "md",
+        }
+    }
+}
+
+/// Where the cached articles are stored
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CacheStorage {
+    /// In files in the cache directory, they are kept between sessions
+    DISK,
+    /// In memory, they are gone once wiki-tui is closed
+    MEMORY,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParserType {
+    HTML,
+    WIKITEXT,
+}
+
 pub struct Config {
     pub api_config: ApiConfig,
     pub theme: Theme,
@@ -156,6 +675,32 @@ pub struct Config {
     pub settings: Settings,
     config_path: PathBuf,
     args: Cli,
+    errors: Vec<ConfigError>,
+}
+
+/// A problem with the config file. The affected settings keep their default values
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /// The line of the config file the problem is in, if it's known
+    pub line: Option<usize>,
+    /// The key of the affected setting, if it's known
+    pub key: Option<String>,
+    /// What is wrong
+    pub message: String,
+    /// The value that can't be used, it's used for finding the line
+    value: Option<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        if let Some(ref key) = self.key {
+            write!(f, "{}: ", key)?;
+        }
+        write!(f, "{}", self.message)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -171,6 +716,93 @@ struct UserConfig {
 #[derive(Deserialize, Debug)]
 struct UserSettings {
     toc: Option<UserTocSettings>,
+    cache: Option<UserCacheSettings>,
+    search: Option<UserSearchSettings>,
+    links: Option<UserLinksSettings>,
+    article: Option<UserArticleSettings>,
+    bookmarks: Option<UserBookmarksSettings>,
+    reading_log: Option<UserReadingLogSettings>,
+    export: Option<UserExportSettings>,
+    start_screen: Option<UserStartScreenSettings>,
+    confirm_link_open: Option<bool>,
+    parser: Option<String>,
+    locale: Option<String>,
+    scroll_lines: Option<usize>,
+    wheel_lines: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserBookmarksSettings {
+    path: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserReadingLogSettings {
+    path: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserStartScreenSettings {
+    actions: Option<Vec<String>>,
+    recent_articles: Option<usize>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserExportSettings {
+    path: Option<String>,
+    format: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserLinksSettings {
+    wrap: Option<bool>,
+    stay_on_line: Option<bool>,
+    double_click: Option<bool>,
+    step: Option<usize>,
+    max_links: Option<usize>,
+    confirm_label: Option<String>,
+    cancel_label: Option<String>,
+    default_button: Option<String>,
+    open_mode: Option<String>,
+    split: Option<String>,
+    namespaces: Option<HashMap<String, String>>,
+    visited: Option<String>,
+    visited_path: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserArticleSettings {
+    max_line_width: Option<usize>,
+    alignment: Option<String>,
+    scroll_progress: Option<bool>,
+    spacing: Option<String>,
+    auto_refresh: Option<u64>,
+    infobox: Option<bool>,
+    redirect_notice: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserSearchSettings {
+    history_size: Option<usize>,
+    results_limit: Option<usize>,
+    infinite_scroll: Option<bool>,
+    auto_open_single_result: Option<bool>,
+    language_prefix: Option<bool>,
+    live: Option<bool>,
+    live_delay_ms: Option<u64>,
+    preview_length: Option<usize>,
+    cache_ttl: Option<u64>,
+    columns: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserCacheSettings {
+    enabled: Option<bool>,
+    ttl: Option<u64>,
+    path: Option<String>,
+    prefetch_limit: Option<usize>,
+    max_entries: Option<usize>,
+    storage: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -180,6 +812,9 @@ struct UserTocSettings {
     title_custom: Option<String>,
     min_width: Option<usize>,
     max_width: Option<usize>,
+    max_height: Option<usize>,
+    hide_below_width: Option<usize>,
+    hide_below_height: Option<usize>,
     scroll_x: Option<bool>,
     scroll_y: Option<bool>,
     item_format: Option<String>,
@@ -201,6 +836,8 @@ struct UserTheme {
 
     article_view: Option<UserViewTheme>,
     toc_view: Option<UserViewTheme>,
+
+    wikis: Option<HashMap<String, UserViewTheme>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -211,11 +848,32 @@ struct UserViewTheme {
     background: Option<String>,
     highlight_text: Option<String>,
     highlight_inactive: Option<String>,
+    current_link: Option<String>,
+    selection: Option<String>,
+    code_block: Option<String>,
+    quote: Option<String>,
+    link: Option<String>,
+    visited_link: Option<String>,
+    link_decoration: Option<Vec<String>>,
 }
 
 #[derive(Deserialize, Debug)]
 struct UserApiConfig {
     base_url: Option<String>,
+    script_path: Option<String>,
+    timeout_ms: Option<u64>,
+    max_retries: Option<u32>,
+    user_agent: Option<String>,
+    wikis: Option<Vec<UserWiki>>,
+    languages: Option<Vec<String>>,
+    remember_wiki: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+struct UserWiki {
+    name: String,
+    base_url: String,
+    script_path: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -223,12 +881,22 @@ struct UserLogging {
     enabled: Option<bool>,
     log_dir: Option<String>,
     log_level: Option<String>,
+    buffer_size: Option<usize>,
 }
 
 #[derive(Deserialize, Debug)]
 struct UserFeatures {
     links: Option<bool>,
     toc: Option<bool>,
+    external_links: Option<bool>,
+    images: Option<bool>,
+    logo: Option<bool>,
+    offline: Option<bool>,
+    prefetch: Option<bool>,
+    vim_motions: Option<bool>,
+    status_bar: Option<bool>,
+    reading_log: Option<bool>,
+    visited_links: Option<bool>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -240,6 +908,55 @@ struct UserKeybindings {
 
     focus_next: Option<UserKeybinding>,
     focus_prev: Option<UserKeybinding>,
+
+    history_back: Option<UserKeybinding>,
+    history_forward: Option<UserKeybinding>,
+
+    toggle_link_preview: Option<UserKeybinding>,
+    link_hints: Option<UserKeybinding>,
+
+    search_article: Option<UserKeybinding>,
+    search_next: Option<UserKeybinding>,
+    search_previous: Option<UserKeybinding>,
+
+    toggle_bookmark: Option<UserKeybinding>,
+    bookmarks: Option<UserKeybinding>,
+    switch_wiki: Option<UserKeybinding>,
+    article_info: Option<UserKeybinding>,
+    copy_url: Option<UserKeybinding>,
+    open_in_browser: Option<UserKeybinding>,
+    scroll_top: Option<UserKeybinding>,
+    scroll_bottom: Option<UserKeybinding>,
+    references: Option<UserKeybinding>,
+    toggle_toc_section: Option<UserKeybinding>,
+    open_section: Option<UserKeybinding>,
+    export_article: Option<UserKeybinding>,
+    toggle_line_width: Option<UserKeybinding>,
+    toggle_spacing: Option<UserKeybinding>,
+    random_article: Option<UserKeybinding>,
+    half_page_up: Option<UserKeybinding>,
+    half_page_down: Option<UserKeybinding>,
+    page_up: Option<UserKeybinding>,
+    page_down: Option<UserKeybinding>,
+    command_palette: Option<UserKeybinding>,
+    open_in_new_tab: Option<UserKeybinding>,
+    open_in_split: Option<UserKeybinding>,
+    open_in_background: Option<UserKeybinding>,
+    next_tab: Option<UserKeybinding>,
+    previous_tab: Option<UserKeybinding>,
+    close_tab: Option<UserKeybinding>,
+    reading_log: Option<UserKeybinding>,
+    search_mode: Option<UserKeybinding>,
+    select_text: Option<UserKeybinding>,
+    see_also: Option<UserKeybinding>,
+    next_header: Option<UserKeybinding>,
+    previous_header: Option<UserKeybinding>,
+    copy_link: Option<UserKeybinding>,
+    open_first_link: Option<UserKeybinding>,
+    show_logs: Option<UserKeybinding>,
+    search_selection: Option<UserKeybinding>,
+    reload_article: Option<UserKeybinding>,
+    cycle_toc_position: Option<UserKeybinding>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -254,6 +971,14 @@ impl Config {
         let mut config = Config {
             api_config: ApiConfig {
                 base_url: "https://en.wikipedia.org/".to_string(),
+                script_path: DEFAULT_SCRIPT_PATH.to_string(),
+                timeout_ms: 10000,
+                max_retries: 2,
+                user_agent: DEFAULT_USER_AGENT.to_string(),
+                wikis: Vec::new(),
+                remember_wiki: true,
+                last_wiki_path: dirs::home_dir()
+                    .map(|home_dir| home_dir.join(CONFIG_DIR).join(APP_DIR).join(LAST_WIKI_FILE)),
             },
             theme: Theme {
                 background: Color::Dark(BaseColor::White),
@@ -270,15 +995,27 @@ impl Config {
 
                 article_view: None,
                 toc_view: None,
+
+                wikis: HashMap::new(),
             },
             logging: Logging {
                 enabled: true,
                 log_dir: PathBuf::from("wiki_tui.log"),
                 log_level: LevelFilter::Info,
+                buffer_size: 500,
             },
             features: Features {
                 links: true,
                 toc: true,
+                external_links: true,
+                images: false,
+                logo: true,
+                offline: false,
+                prefetch: false,
+                vim_motions: false,
+                status_bar: true,
+                reading_log: false,
+                visited_links: false,
             },
             keybindings: Keybindings {
                 down: Event::Key(Key::Down),
@@ -288,6 +1025,55 @@ impl Config {
 
                 focus_next: Event::Key(Key::Tab),
                 focus_prev: Event::Shift(Key::Tab),
+
+                history_back: Event::Char('H'),
+                history_forward: Event::Char('L'),
+
+                toggle_link_preview: Event::Char('p'),
+                link_hints: Event::Char('f'),
+
+                search_article: Event::Char('/'),
+                search_next: Event::Char('n'),
+                search_previous: Event::Char('N'),
+
+                toggle_bookmark: Event::Char('b'),
+                bookmarks: Event::Char('B'),
+                switch_wiki: Event::Char('w'),
+                article_info: Event::Char('i'),
+                copy_url: Event::Char('c'),
+                open_in_browser: Event::Char('o'),
+                scroll_top: Event::Char('g'),
+                scroll_bottom: Event::Char('G'),
+                references: Event::Char('r'),
+                toggle_toc_section: Event::Char(' '),
+                open_section: Event::Char('S'),
+                export_article: Event::Char('e'),
+                toggle_line_width: Event::Char('W'),
+                toggle_spacing: Event::Char('D'),
+                random_article: Event::Char('R'),
+                half_page_up: Event::CtrlChar('u'),
+                half_page_down: Event::CtrlChar('d'),
+                page_up: Event::Key(Key::PageUp),
+                page_down: Event::Key(Key::PageDown),
+                command_palette: Event::Char(':'),
+                open_in_new_tab: Event::Char('t'),
+                open_in_split: Event::Char('v'),
+                open_in_background: Event::Char('T'),
+                next_tab: Event::Char(']'),
+                previous_tab: Event::Char('['),
+                close_tab: Event::Char('x'),
+                reading_log: Event::Char('Y'),
+                search_mode: Event::CtrlChar('t'),
+                select_text: Event::Char('s'),
+                see_also: Event::Char('a'),
+                next_header: Event::Char('}'),
+                previous_header: Event::Char('{'),
+                copy_link: Event::Char('C'),
+                open_first_link: Event::Char('F'),
+                show_logs: Event::Char('l'),
+                search_selection: Event::Char('K'),
+                reload_article: Event::CtrlChar('r'),
+                cycle_toc_position: Event::Char('P'),
             },
             settings: Settings {
                 toc: TocSettings {
@@ -296,10 +1082,104 @@ impl Config {
                     title_custom: None,
                     min_width: 20,
                     max_width: 60,
+                    max_height: 10,
+                    hide_below_width: 60,
+                    hide_below_height: 15,
                     scroll_x: true,
                     scroll_y: true,
                     item_format: "{NUMBER} {TEXT}".to_string(),
                 },
+                cache: CacheSettings {
+                    enabled: true,
+                    ttl: 1440,
+                    prefetch_limit: 2,
+                    max_entries: 1000,
+                    storage: CacheStorage::DISK,
+                    path: dirs::cache_dir().map(|cache_dir| cache_dir.join(APP_DIR)),
+                },
+                search: SearchSettings {
+                    history_size: 100,
+                    results_limit: 10,
+                    infinite_scroll: false,
+                    auto_open_single_result: false,
+                    language_prefix: true,
+                    live: false,
+                    live_delay_ms: 300,
+                    preview_length: 300,
+                    cache_ttl: 300,
+                    history_path: dirs::home_dir().map(|home_dir| {
+                        home_dir
+                            .join(CONFIG_DIR)
+                            .join(APP_DIR)
+                            .join(SEARCH_HISTORY_FILE)
+                    }),
+                    columns: Vec::new(),
+                },
+                links: LinksSettings {
+                    wrap: false,
+                    stay_on_line: false,
+                    double_click: false,
+                    step: 1,
+                    max_links: 0,
+                    confirm_label: "Yep".to_string(),
+                    cancel_label: "Nope".to_string(),
+                    default_button: ConfirmButton::CONFIRM,
+                    open_mode: LinkOpenMode::REPLACE,
+                    split: SplitOrientation::HORIZONTAL,
+                    namespaces: HashMap::from([
+                        ("category".to_string(), NamespaceBehavior::WARN),
+                        ("template".to_string(), NamespaceBehavior::WARN),
+                        ("file".to_string(), NamespaceBehavior::BROWSER),
+                        ("special".to_string(), NamespaceBehavior::BROWSER),
+                    ]),
+                    visited: VisitedLinksScope::SESSION,
+                    visited_path: dirs::home_dir().map(|home_dir| {
+                        home_dir
+                            .join(CONFIG_DIR)
+                            .join(APP_DIR)
+                            .join(VISITED_LINKS_FILE)
+                    }),
+                },
+                article: ArticleSettings {
+                    max_line_width: None,
+                    alignment: ArticleAlignment::LEFT,
+                    scroll_progress: true,
+                    spacing: LineSpacing::NORMAL,
+                    auto_refresh: 0,
+                    infobox: true,
+                    redirect_notice: true,
+                },
+                bookmarks: BookmarksSettings {
+                    path: dirs::home_dir().map(|home_dir| {
+                        home_dir.join(CONFIG_DIR).join(APP_DIR).join(BOOKMARKS_FILE)
+                    }),
+                },
+                reading_log: ReadingLogSettings {
+                    path: dirs::home_dir().map(|home_dir| {
+                        home_dir
+                            .join(CONFIG_DIR)
+                            .join(APP_DIR)
+                            .join(READING_LOG_FILE)
+                    }),
+                },
+                export: ExportSettings {
+                    path: dirs::home_dir(),
+                    format: ExportFormat::MARKDOWN,
+                },
+                start_screen: StartScreenSettings {
+                    actions: vec![
+                        StartAction::SEARCH,
+                        StartAction::RANDOM,
+                        StartAction::RECENT,
+                        StartAction::BOOKMARKS,
+                    ],
+                    recent_articles: 5,
+                },
+                confirm_link_open: true,
+                parser: ParserType::HTML,
+                locale_path: None,
+                scroll_lines: 1,
+                wheel_lines: 3,
             },
             config_path: PathBuf::new(),
             #[cfg(not(test))]
@@ -307,6 +1187,7 @@ impl Config {
 
             #[cfg(test)]
             args: Cli::default(),
+            errors: Vec::new(),
         };
 
         // load the configuration from the file
@@ -343,13 +1224,47 @@ impl Config {
             }
         };
 
-        let user_config = match from_str::<UserConfig>(&config_str).context("wrong format") {
+        self.load_config_str(&config_str);
+
+        // override the log level
+        if let Some(log_level) = self.args.level.as_ref() {
+            let level = match log_level {
+                0 => LevelFilter::Debug,
+                1 => LevelFilter::Info,
+                2 => LevelFilter::Warn,
+                3 => LevelFilter::Error,
+                _ => self.logging.log_level,
+            };
+            log::info!("overriding the configured log level to '{}'", level);
+            self.logging.log_level = level;
+        }
+    }
+
+    /// Loads the settings of a given config file. Every problem is reported in the errors, the
+    /// affected settings keep their defaults. A file that can't be read at all changes nothing
+    fn load_config_str(&mut self, config_str: &str) {
+        let user_config = match from_str::<UserConfig>(config_str) {
             Ok(config) => {
                 log::info!("successfully deserialized config");
                 config
             }
             Err(error) => {
                 log::warn!("deserializing the config file failed, {:?}", error);
+                let message = error.to_string();
+                let message = match error.line_col() {
+                    // the position is reported separately
+                    Some(_) => message
+                        .rsplit_once(" at line ")
+                        .map_or(message.as_str(), |(message, _)| message)
+                        .to_string(),
+                    None => message,
+                };
+                self.errors.push(ConfigError {
+                    line: error.line_col().map(|(line, _)| line + 1),
+                    key: None,
+                    message,
+                    value: None,
+                });
                 return;
             }
         };
@@ -378,21 +1293,42 @@ impl Config {
             self.load_settings(&user_settings);
         }
 
-        // override the log level
-        if let Some(log_level) = self.args.level.as_ref() {
-            let level = match log_level {
-                0 => LevelFilter::Debug,
-                1 => LevelFilter::Info,
-                2 => LevelFilter::Warn,
-                3 => LevelFilter::Error,
-                _ => self.logging.log_level,
-            };
-            log::info!("overriding the configured log level to '{}'", level);
-            self.logging.log_level = level;
+        // find the lines of the values that can't be used
+        for error in self.errors.iter_mut().filter(|error| error.line.is_none()) {
+            if let (Some(key), Some(value)) = (&error.key, &error.value) {
+                error.line = find_line(config_str, key, value);
+            }
         }
     }
 
+    /// Reports a value of the config file that can't be used, the setting keeps its default
+    fn report_error(&mut self, key: &str, value: &str, message: &str) {
+        log::warn!("{}: {}, got {}", key, message, value);
+        self.errors.push(ConfigError {
+            line: None,
+            key: Some(key.to_string()),
+            message: format!("{}, got '{}'", message, value),
+            value: Some(value.to_string()),
+        });
+    }
+
+    /// The problems found in the config file. Without any, the config file was loaded completely
+    pub fn errors(&self) -> &[ConfigError] {
+        &self.errors
+    }
+
     fn load_or_create_config_paths(&mut self) -> Result<bool> {
+        // a config file given as an argument replaces the default one
+        if let Some(ref config_file) = self.args.config {
+            check_config_file(config_file)?;
+            log::info!(
+                "using the config file '{}' from the arguments",
+                config_file.display()
+            );
+            self.config_path = config_file.clone();
+            return Ok(true);
+        }
+
         // get the platform specific config directory
         let config_dir = match dirs::home_dir() {
             Some(config_dir) => {
@@ -441,14 +1377,59 @@ impl Config {
         // define the macro for loading individual api settings
         macro_rules! to_api_setting {
             ($setting: ident) => {
-                if user_api_config.$setting.is_some() {
-                    self.api_config.$setting =
-                        user_api_config.$setting.as_ref().unwrap().to_string();
+                if let Some(setting) = &user_api_config.$setting {
+                    self.api_config.$setting = setting.to_string();
                 }
             };
         }
 
         to_api_setting!(base_url);
+        to_api_setting!(script_path);
+
+        self.api_config.base_url = normalize_base_url(&self.api_config.base_url);
+        self.api_config.script_path = normalize_script_path(&self.api_config.script_path);
+
+        if let Some(timeout_ms) = user_api_config.timeout_ms {
+            self.api_config.timeout_ms = timeout_ms;
+        }
+
+        if let Some(max_retries) = user_api_config.max_retries {
+            self.api_config.max_retries = max_retries;
+        }
+
+        if let Some(user_agent) = &user_api_config.user_agent {
+            self.api_config.user_agent = user_agent.clone();
+        }
+
+        if let Some(wikis) = &user_api_config.wikis {
+            self.api_config.wikis = wikis
+                .iter()
+                .map(|wiki| Wiki {
+                    name: wiki.name.clone(),
+                    base_url: normalize_base_url(&wiki.base_url),
+                    script_path: normalize_script_path(
+                        wiki.script_path
+                            .as_deref()
+                            .unwrap_or(&self.api_config.script_path),
+                    ),
+                })
+                .collect();
+        }
+
+        // every language is a shortcut for the wikipedia of that language
+        if let Some(languages) = &user_api_config.languages {
+            self.api_config
+                .wikis
+                .extend(languages.iter().map(|language| Wiki {
+                    name: format!("Wikipedia ({})", language),
+                    base_url: format!("https://{}.wikipedia.org/", language),
+                    script_path: DEFAULT_SCRIPT_PATH.to_string(),
+                }));
+        }
+
+        if let Some(remember_wiki) = user_api_config.remember_wiki {
+            self.api_config.remember_wiki = remember_wiki;
+        }
     }
 
     fn load_theme(&mut self, user_theme: &UserTheme) {
@@ -457,14 +1438,16 @@ impl Config {
         // define the macro for loading individual color settings
         macro_rules! to_theme_color {
             ($color: ident) => {
-                if user_theme.$color.is_some() {
-                    match parse_color(user_theme.$color.as_ref().unwrap().to_string()) {
+                if let Some(color) = &user_theme.$color {
+                    match parse_color(color.to_string()) {
                         Ok(color) => {
                             self.theme.$color = color;
                         }
-                        Err(error) => {
-                            log::warn!("{}", error);
-                        }
+                        Err(_) => self.report_error(
+                            concat!("theme.", stringify!($color)),
+                            color,
+                            "unknown color",
+                        ),
                     };
                 }
             };
@@ -482,7 +1465,7 @@ impl Config {
         if let Some(search_bar) = &user_theme.search_bar {
             let background_changed: bool = search_bar.background.is_some();
 
-            let mut search_bar_theme = self.load_view_theme(search_bar);
+            let mut search_bar_theme = self.load_view_theme("search_bar", search_bar);
             if background_changed {
                 search_bar_theme.secondary = search_bar_theme.background;
             }
@@ -491,35 +1474,49 @@ impl Config {
         }
 
         if let Some(search_results) = &user_theme.search_results {
-            self.theme.search_results = Some(self.load_view_theme(search_results));
+            self.theme.search_results =
+                Some(self.load_view_theme("search_results", search_results));
         }
 
         if let Some(search_preview) = &user_theme.search_preview {
-            self.theme.search_preview = Some(self.load_view_theme(search_preview));
+            self.theme.search_preview =
+                Some(self.load_view_theme("search_preview", search_preview));
         }
 
         if let Some(article_view) = &user_theme.article_view {
-            self.theme.article_view = Some(self.load_view_theme(article_view));
+            self.theme.article_view = Some(self.load_view_theme("article_view", article_view));
         }
 
         if let Some(toc_view) = &user_theme.toc_view {
-            self.theme.toc_view = Some(self.load_view_theme(toc_view));
+            self.theme.toc_view = Some(self.load_view_theme("toc_view", toc_view));
+        }
+
+        // the wikis can be given by their host or their url
+        if let Some(wikis) = &user_theme.wikis {
+            for (wiki, wiki_theme) in wikis {
+                let wiki_theme = self.load_view_theme(&format!("wikis.{}", wiki), wiki_theme);
+                self.theme
+                    .wikis
+                    .insert(wiki_host(wiki).to_string(), wiki_theme);
+            }
         }
     }
 
-    fn load_view_theme(&self, user_view_theme: &UserViewTheme) -> ViewTheme {
+    fn load_view_theme(&mut self, view: &str, user_view_theme: &UserViewTheme) -> ViewTheme {
         let mut view_theme = self.create_view_theme();
 
         macro_rules! to_view_theme {
             ($color: ident) => {
-                if user_view_theme.$color.is_some() {
-                    match parse_color(user_view_theme.$color.as_ref().unwrap().to_string()) {
+                if let Some(color) = &user_view_theme.$color {
+                    match parse_color(color.to_string()) {
                         Ok(color) => {
                             view_theme.$color = color;
                         }
-                        Err(error) => {
-                            log::warn!("{}", error);
-                        }
+                        Err(_) => self.report_error(
+                            &format!("theme.{}.{}", view, stringify!($color)),
+                            color,
+                            "unknown color",
+                        ),
                     };
                 }
             };
@@ -531,6 +1528,32 @@ impl Config {
         to_view_theme!(background);
         to_view_theme!(highlight_text);
         to_view_theme!(highlight_inactive);
+        to_view_theme!(current_link);
+        to_view_theme!(selection);
+        to_view_theme!(code_block);
+        to_view_theme!(quote);
+        to_view_theme!(link);
+        to_view_theme!(visited_link);
+
+        if let Some(decorations) = &user_view_theme.link_decoration {
+            view_theme.link_decoration = LinkDecoration {
+                underline: false,
+                brackets: false,
+                color: false,
+            };
+            for decoration in decorations {
+                match decoration.to_lowercase().as_str() {
+                    "underline" => view_theme.link_decoration.underline = true,
+                    "brackets" => view_theme.link_decoration.brackets = true,
+                    "color" => view_theme.link_decoration.color = true,
+                    decoration => self.report_error(
+                        &format!("theme.{}.link_decoration", view),
+                        decoration,
+                        "unknown link decoration",
+                    ),
+                }
+            }
+        }
 
         view_theme
     }
@@ -544,6 +1567,13 @@ impl Config {
             highlight: self.theme.highlight,
             highlight_text: self.theme.highlight_text,
             highlight_inactive: self.theme.highlight_inactive,
+            current_link: self.theme.highlight,
+            selection: self.theme.highlight_inactive,
+            code_block: DEFAULT_CODE_BLOCK_COLOR,
+            quote: self.theme.text,
+            link: DEFAULT_LINK_COLOR,
+            visited_link: DEFAULT_VISITED_LINK_COLOR,
+            link_decoration: LinkDecoration::default(),
         }
     }
 
@@ -565,6 +1595,10 @@ impl Config {
                 self.logging.log_level = level;
             }
         }
+
+        if let Some(buffer_size) = user_logging.buffer_size {
+            self.logging.buffer_size = buffer_size;
+        }
     }
 
     fn load_features(&mut self, user_features: &UserFeatures) {
@@ -577,96 +1611,526 @@ impl Config {
         if let Some(toc) = user_features.toc {
             self.features.toc = toc;
         }
+
+        if let Some(external_links) = user_features.external_links {
+            self.features.external_links = external_links;
+        }
+
+        if let Some(images) = user_features.images {
+            self.features.images = images;
+        }
+
+        if let Some(logo) = user_features.logo {
+            self.features.logo = logo;
+        }
+
+        if let Some(offline) = user_features.offline {
+            self.features.offline = offline;
+        }
+
+        if let Some(prefetch) = user_features.prefetch {
+            self.features.prefetch = prefetch;
+        }
+
+        if let Some(vim_motions) = user_features.vim_motions {
+            self.features.vim_motions = vim_motions;
+        }
+
+        if let Some(status_bar) = user_features.status_bar {
+            self.features.status_bar = status_bar;
+        }
+
+        if let Some(reading_log) = user_features.reading_log {
+            self.features.reading_log = reading_log;
+        }
+
+        if let Some(visited_links) = user_features.visited_links {
+            self.features.visited_links = visited_links;
+        }
     }
 
     fn load_keybindings(&mut self, user_keybindings: &UserKeybindings) {
         log::info!("loading the keybindings");
 
-        if let Some(keybinding) = &user_keybindings.down {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.down = event_key;
-                }
-                Err(error) => {
-                    log::warn!("{:?}", error)
+        // define the macro for loading individual keybindings
+        macro_rules! to_keybinding {
+            ($action: ident) => {
+                if let Some(keybinding) = &user_keybindings.$action {
+                    match parse_keybinding(
+                        &keybinding.key,
+                        keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
+                    ) {
+                        Ok(event_key) => {
+                            self.keybindings.$action = event_key;
+                        }
+                        Err(error) => self.report_error(
+                            concat!("keybindings.", stringify!($action)),
+                            &keybinding.key,
+                            &error.to_string(),
+                        ),
+                    }
                 }
+            };
+        }
+
+        to_keybinding!(down);
+        to_keybinding!(up);
+        to_keybinding!(left);
+        to_keybinding!(right);
+
+        to_keybinding!(focus_next);
+        to_keybinding!(focus_prev);
+
+        to_keybinding!(history_back);
+        to_keybinding!(history_forward);
+
+        to_keybinding!(toggle_link_preview);
+        to_keybinding!(link_hints);
+
+        to_keybinding!(search_article);
+        to_keybinding!(search_next);
+        to_keybinding!(search_previous);
+
+        to_keybinding!(toggle_bookmark);
+        to_keybinding!(bookmarks);
+        to_keybinding!(switch_wiki);
+        to_keybinding!(article_info);
+        to_keybinding!(copy_url);
+        to_keybinding!(open_in_browser);
+        to_keybinding!(scroll_top);
+        to_keybinding!(scroll_bottom);
+        to_keybinding!(references);
+        to_keybinding!(toggle_toc_section);
+        to_keybinding!(open_section);
+        to_keybinding!(export_article);
+        to_keybinding!(toggle_line_width);
+        to_keybinding!(toggle_spacing);
+        to_keybinding!(random_article);
+        to_keybinding!(half_page_up);
+        to_keybinding!(half_page_down);
+        to_keybinding!(page_up);
+        to_keybinding!(page_down);
+        to_keybinding!(command_palette);
+        to_keybinding!(open_in_new_tab);
+        to_keybinding!(open_in_split);
+        to_keybinding!(open_in_background);
+        to_keybinding!(next_tab);
+        to_keybinding!(previous_tab);
+        to_keybinding!(close_tab);
+        to_keybinding!(reading_log);
+        to_keybinding!(search_mode);
+        to_keybinding!(select_text);
+        to_keybinding!(see_also);
+        to_keybinding!(next_header);
+        to_keybinding!(previous_header);
+        to_keybinding!(copy_link);
+        to_keybinding!(open_first_link);
+        to_keybinding!(show_logs);
+        to_keybinding!(search_selection);
+        to_keybinding!(reload_article);
+        to_keybinding!(cycle_toc_position);
+
+        for (name, other_name) in self.keybindings.conflicts() {
+            log::warn!(
+                "the keybindings '{}' and '{}' use the same key, only one of them will work",
+                name,
+                other_name
+            );
+        }
+    }
+
+    fn load_settings(&mut self, user_settings: &UserSettings) {
+        log::info!("loading settings");
+
+        if let Some(user_toc_settings) = &user_settings.toc {
+            self.load_toc_settings(user_toc_settings);
+        }
+
+        if let Some(user_cache_settings) = &user_settings.cache {
+            self.load_cache_settings(user_cache_settings);
+        }
+
+        if let Some(user_search_settings) = &user_settings.search {
+            self.load_search_settings(user_search_settings);
+        }
+
+        if let Some(user_links_settings) = &user_settings.links {
+            self.load_links_settings(user_links_settings);
+        }
+
+        if let Some(user_article_settings) = &user_settings.article {
+            self.load_article_settings(user_article_settings);
+        }
+
+        if let Some(user_bookmarks_settings) = &user_settings.bookmarks {
+            self.load_bookmarks_settings(user_bookmarks_settings);
+        }
+
+        if let Some(user_reading_log_settings) = &user_settings.reading_log {
+            self.load_reading_log_settings(user_reading_log_settings);
+        }
+
+        if let Some(user_export_settings) = &user_settings.export {
+            self.load_export_settings(user_export_settings);
+        }
+
+        if let Some(user_start_screen_settings) = &user_settings.start_screen {
+            self.load_start_screen_settings(user_start_screen_settings);
+        }
+
+        if let Some(confirm_link_open) = user_settings.confirm_link_open {
+            self.settings.confirm_link_open = confirm_link_open;
+        }
+
+        if let Some(parser) = &user_settings.parser {
+            match parser.to_lowercase().as_str() {
+                "html" => self.settings.parser = ParserType::HTML,
+                "wikitext" => self.settings.parser = ParserType::WIKITEXT,
+                parser => self.report_error("settings.parser", parser, "unknown parser"),
             }
         }
-        if let Some(keybinding) = &user_keybindings.up {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.up = event_key;
-                }
-                Err(error) => {
-                    log::warn!("{:?}", error)
+
+        // scrolling by zero lines would make the keys useless
+        if let Some(scroll_lines) = user_settings.scroll_lines {
+            self.settings.scroll_lines = scroll_lines.max(1);
+        }
+
+        if let Some(wheel_lines) = user_settings.wheel_lines {
+            self.settings.wheel_lines = wheel_lines.max(1);
+        }
+
+        // the messages of a locale are stored in the locales directory of the config
+        if let Some(locale) = &user_settings.locale {
+            self.settings.locale_path = dirs::home_dir().map(|home_dir| {
+                home_dir
+                    .join(CONFIG_DIR)
+                    .join(APP_DIR)
+                    .join(LOCALES_DIR)
+                    .join(format!("{}.toml", locale))
+            });
+        }
+    }
+
+    fn load_bookmarks_settings(&mut self, user_bookmarks_settings: &UserBookmarksSettings) {
+        log::info!("loading bookmarks settings");
+
+        if let Some(path) = &user_bookmarks_settings.path {
+            self.settings.bookmarks.path = Some(PathBuf::from(path));
+        }
+    }
+
+    fn load_reading_log_settings(&mut self, user_reading_log_settings: &UserReadingLogSettings) {
+        log::info!("loading reading log settings");
+
+        if let Some(path) = &user_reading_log_settings.path {
+            self.settings.reading_log.path = Some(PathBuf::from(path));
+        }
+    }
+
+    fn load_start_screen_settings(&mut self, user_start_screen_settings: &UserStartScreenSettings) {
+        log::info!("loading start screen settings");
+
+        if let Some(actions) = &user_start_screen_settings.actions {
+            self.settings.start_screen.actions.clear();
+            for action in actions {
+                let action = match action.to_lowercase().as_str() {
+                    "search" => StartAction::SEARCH,
+                    "random" => StartAction::RANDOM,
+                    "recent" => StartAction::RECENT,
+                    "bookmarks" => StartAction::BOOKMARKS,
+                    _ => {
+                        self.report_error(
+                            "settings.start_screen.actions",
+                            action,
+                            "unknown action",
+                        );
+                        continue;
+                    }
+                };
+                if !self.settings.start_screen.actions.contains(&action) {
+                    self.settings.start_screen.actions.push(action);
                 }
             }
         }
-        if let Some(keybinding) = &user_keybindings.left {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.left = event_key;
-                }
-                Err(error) => {
-                    log::warn!("{:?}", error)
+
+        if let Some(recent_articles) = user_start_screen_settings.recent_articles {
+            self.settings.start_screen.recent_articles = recent_articles;
+        }
+    }
+
+    fn load_export_settings(&mut self, user_export_settings: &UserExportSettings) {
+        log::info!("loading export settings");
+
+        if let Some(path) = &user_export_settings.path {
+            self.settings.export.path = Some(PathBuf::from(path));
+        }
+
+        if let Some(format) = &user_export_settings.format {
+            match format.to_lowercase().as_str() {
+                "text" => self.settings.export.format = ExportFormat::TEXT,
+                "markdown" => self.settings.export.format = ExportFormat::MARKDOWN,
+                format => {
+                    self.report_error("settings.export.format", format, "unknown export format")
                 }
             }
         }
-        if let Some(keybinding) = &user_keybindings.right {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.right = event_key;
-                }
-                Err(error) => {
-                    log::warn!("{:?}", error)
-                }
+    }
+
+    fn load_links_settings(&mut self, user_links_settings: &UserLinksSettings) {
+        log::info!("loading links settings");
+
+        if let Some(wrap) = user_links_settings.wrap {
+            self.settings.links.wrap = wrap;
+        }
+
+        if let Some(stay_on_line) = user_links_settings.stay_on_line {
+            self.settings.links.stay_on_line = stay_on_line;
+        }
+
+        if let Some(double_click) = user_links_settings.double_click {
+            self.settings.links.double_click = double_click;
+        }
+
+        if let Some(step) = user_links_settings.step {
+            self.settings.links.step = step.max(1);
+        }
+
+        if let Some(max_links) = user_links_settings.max_links {
+            self.settings.links.max_links = max_links;
+        }
+
+        if let Some(confirm_label) = &user_links_settings.confirm_label {
+            self.settings.links.confirm_label = confirm_label.to_string();
+        }
+
+        if let Some(cancel_label) = &user_links_settings.cancel_label {
+            self.settings.links.cancel_label = cancel_label.to_string();
+        }
+
+        if let Some(default_button) = &user_links_settings.default_button {
+            match default_button.to_lowercase().as_str() {
+                "confirm" => self.settings.links.default_button = ConfirmButton::CONFIRM,
+                "cancel" => self.settings.links.default_button = ConfirmButton::CANCEL,
+                default_button => self.report_error(
+                    "settings.links.default_button",
+                    default_button,
+                    "unknown default button",
+                ),
             }
         }
-        if let Some(keybinding) = &user_keybindings.focus_next {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.focus_next = event_key;
-                }
-                Err(error) => {
-                    log::warn!("{:?}", error)
+
+        if let Some(open_mode) = &user_links_settings.open_mode {
+            match open_mode.to_lowercase().as_str() {
+                "replace" => self.settings.links.open_mode = LinkOpenMode::REPLACE,
+                "tab" => self.settings.links.open_mode = LinkOpenMode::TAB,
+                "split" => self.settings.links.open_mode = LinkOpenMode::SPLIT,
+                "background" => self.settings.links.open_mode = LinkOpenMode::BACKGROUND,
+                open_mode => self.report_error(
+                    "settings.links.open_mode",
+                    open_mode,
+                    "unknown link open mode",
+                ),
+            }
+        }
+
+        if let Some(split) = &user_links_settings.split {
+            match split.to_lowercase().as_str() {
+                "horizontal" => self.settings.links.split = SplitOrientation::HORIZONTAL,
+                "vertical" => self.settings.links.split = SplitOrientation::VERTICAL,
+                split => {
+                    self.report_error("settings.links.split", split, "unknown split orientation")
                 }
             }
         }
-        if let Some(keybinding) = &user_keybindings.focus_prev {
-            match parse_keybinding(
-                &keybinding.key,
-                keybinding.mode.as_ref().unwrap_or(&"normal".to_string()),
-            ) {
-                Ok(event_key) => {
-                    self.keybindings.focus_prev = event_key;
+
+        // the namespaces are added to the default ones, so only the changed ones have to be set
+        if let Some(namespaces) = &user_links_settings.namespaces {
+            for (namespace, behavior) in namespaces {
+                let behavior = match behavior.to_lowercase().as_str() {
+                    "open" => NamespaceBehavior::OPEN,
+                    "warn" => NamespaceBehavior::WARN,
+                    "browser" => NamespaceBehavior::BROWSER,
+                    behavior => {
+                        self.report_error(
+                            &format!("settings.links.namespaces.{}", namespace),
+                            behavior,
+                            "unknown namespace behavior",
+                        );
+                        continue;
+                    }
+                };
+                self.settings
+                    .links
+                    .namespaces
+                    .insert(namespace.to_lowercase().replace('_', " "), behavior);
+            }
+        }
+        if let Some(visited) = &user_links_settings.visited {
+            match visited.to_lowercase().as_str() {
+                "session" => self.settings.links.visited = VisitedLinksScope::SESSION,
+                "persistent" => self.settings.links.visited = VisitedLinksScope::PERSISTENT,
+                visited => self.report_error("settings.links.visited", visited, "unknown scope"),
+            }
+        }
+
+        if let Some(visited_path) = &user_links_settings.visited_path {
+            self.settings.links.visited_path = Some(PathBuf::from(visited_path));
+        }
+    }
+
+    fn load_article_settings(&mut self, user_article_settings: &UserArticleSettings) {
+        log::info!("loading article settings");
+
+        // a width of zero means the lines aren't limited
+        if let Some(max_line_width) = user_article_settings.max_line_width {
+            self.settings.article.max_line_width = Some(max_line_width).filter(|width| *width > 0);
+        }
+
+        if let Some(alignment) = &user_article_settings.alignment {
+            match alignment.to_lowercase().as_str() {
+                "left" => self.settings.article.alignment = ArticleAlignment::LEFT,
+                "center" => self.settings.article.alignment = ArticleAlignment::CENTER,
+                alignment => self.report_error(
+                    "settings.article.alignment",
+                    alignment,
+                    "unknown article alignment",
+                ),
+            }
+        }
+
+        if let Some(scroll_progress) = user_article_settings.scroll_progress {
+            self.settings.article.scroll_progress = scroll_progress;
+        }
+
+        if let Some(auto_refresh) = user_article_settings.auto_refresh {
+            self.settings.article.auto_refresh = auto_refresh;
+        }
+
+        if let Some(infobox) = user_article_settings.infobox {
+            self.settings.article.infobox = infobox;
+        }
+
+        if let Some(redirect_notice) = user_article_settings.redirect_notice {
+            self.settings.article.redirect_notice = redirect_notice;
+        }
+
+        if let Some(spacing) = &user_article_settings.spacing {
+            match spacing.to_lowercase().as_str() {
+                "compact" => self.settings.article.spacing = LineSpacing::COMPACT,
+                "normal" => self.settings.article.spacing = LineSpacing::NORMAL,
+                "spacious" => self.settings.article.spacing = LineSpacing::SPACIOUS,
+                spacing => {
+                    self.report_error("settings.article.spacing", spacing, "unknown line spacing")
                 }
-                Err(error) => {
-                    log::warn!("{:?}", error)
+            }
+        }
+    }
+
+    fn load_search_settings(&mut self, user_search_settings: &UserSearchSettings) {
+        log::info!("loading search settings");
+
+        if let Some(history_size) = user_search_settings.history_size {
+            self.settings.search.history_size = history_size;
+        }
+
+        if let Some(results_limit) = user_search_settings.results_limit {
+            // the api only accepts limits between 1 and 500
+            if (1..=500).contains(&results_limit) {
+                self.settings.search.results_limit = results_limit;
+            } else {
+                self.report_error(
+                    "settings.search.results_limit",
+                    &results_limit.to_string(),
+                    "the limit has to be between 1 and 500",
+                );
+            }
+        }
+
+        if let Some(infinite_scroll) = user_search_settings.infinite_scroll {
+            self.settings.search.infinite_scroll = infinite_scroll;
+        }
+
+        if let Some(auto_open_single_result) = user_search_settings.auto_open_single_result {
+            self.settings.search.auto_open_single_result = auto_open_single_result;
+        }
+
+        if let Some(language_prefix) = user_search_settings.language_prefix {
+            self.settings.search.language_prefix = language_prefix;
+        }
+
+        if let Some(live) = user_search_settings.live {
+            self.settings.search.live = live;
+        }
+
+        if let Some(live_delay_ms) = user_search_settings.live_delay_ms {
+            self.settings.search.live_delay_ms = live_delay_ms;
+        }
+
+        if let Some(preview_length) = user_search_settings.preview_length {
+            self.settings.search.preview_length = preview_length;
+        }
+
+        if let Some(cache_ttl) = user_search_settings.cache_ttl {
+            self.settings.search.cache_ttl = cache_ttl;
+        }
+
+        if let Some(columns) = &user_search_settings.columns {
+            self.settings.search.columns.clear();
+            for column in columns {
+                let column = match column.to_lowercase().as_str() {
+                    "wordcount" => SearchColumn::WORDCOUNT,
+                    "edited" => SearchColumn::EDITED,
+                    "size" => SearchColumn::SIZE,
+                    column => {
+                        self.report_error("settings.search.columns", column, "unknown column");
+                        continue;
+                    }
+                };
+                if !self.settings.search.columns.contains(&column) {
+                    self.settings.search.columns.push(column);
                 }
             }
         }
     }
 
-    fn load_settings(&mut self, user_settings: &UserSettings) {
-        log::info!("loading settings");
+    fn load_cache_settings(&mut self, user_cache_settings: &UserCacheSettings) {
+        log::info!("loading cache settings");
 
-        if let Some(user_toc_settings) = &user_settings.toc {
-            self.load_toc_settings(user_toc_settings);
+        if let Some(enabled) = user_cache_settings.enabled {
+            self.settings.cache.enabled = enabled;
+        }
+
+        if let Some(ttl) = user_cache_settings.ttl {
+            self.settings.cache.ttl = ttl;
+        }
+
+        if let Some(prefetch_limit) = user_cache_settings.prefetch_limit {
+            self.settings.cache.prefetch_limit = prefetch_limit.max(1);
+        }
+
+        if let Some(max_entries) = user_cache_settings.max_entries {
+            self.settings.cache.max_entries = max_entries;
+        }
+
+        if let Some(storage) = &user_cache_settings.storage {
+            match storage.to_lowercase().as_str() {
+                "disk" => self.settings.cache.storage = CacheStorage::DISK,
+                "memory" => self.settings.cache.storage = CacheStorage::MEMORY,
+                _ => self.report_error("settings.cache.storage", storage, "unknown storage"),
+            }
+        }
+
+        // only use the custom path if we can create it, otherwise keep the default one
+        if let Some(path) = &user_cache_settings.path {
+            let path = PathBuf::from(path);
+            match std::fs::create_dir_all(&path) {
+                Ok(_) => self.settings.cache.path = Some(path),
+                Err(error) => self.report_error(
+                    "settings.cache.path",
+                    &path.display().to_string(),
+                    &format!("couldn't create the cache directory ({})", error),
+                ),
+            }
         }
     }
 
@@ -677,7 +2141,9 @@ impl Config {
             match position.to_lowercase().as_str() {
                 "left" => self.settings.toc.position = TocPosition::LEFT,
                 "right" => self.settings.toc.position = TocPosition::RIGHT,
-                pos => log::warn!("unknown toc position, got {}", pos),
+                "top" => self.settings.toc.position = TocPosition::TOP,
+                "bottom" => self.settings.toc.position = TocPosition::BOTTOM,
+                pos => self.report_error("settings.toc.position", pos, "unknown toc position"),
             }
         }
 
@@ -702,6 +2168,18 @@ impl Config {
             self.settings.toc.max_width = max_width.to_owned();
         }
 
+        if let Some(max_height) = &user_toc_settings.max_height {
+            self.settings.toc.max_height = max_height.to_owned();
+        }
+
+        if let Some(hide_below_width) = &user_toc_settings.hide_below_width {
+            self.settings.toc.hide_below_width = hide_below_width.to_owned();
+        }
+
+        if let Some(hide_below_height) = &user_toc_settings.hide_below_height {
+            self.settings.toc.hide_below_height = hide_below_height.to_owned();
+        }
+
         if let Some(scroll_x) = &user_toc_settings.scroll_x {
             self.settings.toc.scroll_x = scroll_x.to_owned();
         }
@@ -720,10 +2198,74 @@ impl Config {
     }
 }
 
+/// Returns the number of the first line of a config file that contains the last part of a given
+/// key and a given value. Keys can be written in many ways, so this is only a good guess
+fn find_line(config_str: &str, key: &str, value: &str) -> Option<usize> {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    config_str
+        .lines()
+        .position(|line| line.contains(name) && line.contains(value))
+        .map(|index| index + 1)
+}
+
+/// Returns the host of a given wiki url
+pub fn wiki_host(base_url: &str) -> &str {
+    base_url
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/')
+}
+
+/// Checks that a given config file exists and can be read, the error says which of it isn't the
+/// case
+pub fn check_config_file(path: &Path) -> Result<()> {
+    if !path.exists() {
+        bail!("the config file '{}' doesn't exist", path.display());
+    }
+    if !path.is_file() {
+        bail!("the config file '{}' isn't a file", path.display());
+    }
+    std::fs::File::open(path)
+        .with_context(|| format!("the config file '{}' can't be read", path.display()))?;
+    Ok(())
+}
+
+/// Makes sure a wiki url ends with a slash, so paths can be appended to it
+fn normalize_base_url(base_url: &str) -> String {
+    format!("{}/", base_url.trim_end_matches('/'))
+}
+
+/// Makes sure a script path is relative to the wiki url and ends with a slash, unless it's empty
+fn normalize_script_path(script_path: &str) -> String {
+    let script_path = script_path.trim_matches('/');
+    if script_path.is_empty() {
+        return String::new();
+    }
+    format!("{}/", script_path)
+}
+
 fn parse_color(color: String) -> Result<Color> {
     Color::parse(&color.to_lowercase()).context("Failed loading the color")
 }
 
+/// Returns a human readable name of the key of a keybinding, like 'Ctrl+u'
+pub fn keybinding_name(event: &Event) -> String {
+    match event {
+        Event::Char(' ') => "Space".to_string(),
+        Event::Char(character) => character.to_string(),
+        Event::CtrlChar(character) => format!("Ctrl+{}", character),
+        Event::AltChar(character) => format!("Alt+{}", character),
+        Event::Key(key) => format!("{:?}", key),
+        Event::Shift(key) => format!("Shift+{:?}", key),
+        Event::Alt(key) => format!("Alt+{:?}", key),
+        Event::AltShift(key) => format!("Alt+Shift+{:?}", key),
+        Event::Ctrl(key) => format!("Ctrl+{:?}", key),
+        Event::CtrlShift(key) => format!("Ctrl+Shift+{:?}", key),
+        Event::CtrlAlt(key) => format!("Ctrl+Alt+{:?}", key),
+        event => format!("{:?}", event),
+    }
+}
+
 fn parse_keybinding(key: &str, mode: &str) -> Result<Event> {
     // check if the key is a character
     if let Ok(character) = char::from_str(key) {
@@ -788,3 +2330,127 @@ impl Default for Config {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{check_config_file, keybinding_name, Config, LinkDecoration, CONFIG};
+    use cursive::event::{Event, Key};
+    use cursive::theme::{BaseColor, Color};
+
+    #[test]
+    fn keybinding_names() {
+        assert_eq!(keybinding_name(&Event::Char('H')), "H");
+        assert_eq!(keybinding_name(&Event::Char(' ')), "Space");
+        assert_eq!(keybinding_name(&Event::CtrlChar('u')), "Ctrl+u");
+        assert_eq!(keybinding_name(&Event::Key(Key::PageUp)), "PageUp");
+        assert_eq!(keybinding_name(&Event::Shift(Key::Tab)), "Shift+Tab");
+    }
+
+    #[test]
+    fn keybinding_conflicts() {
+        let mut keybindings = CONFIG.keybindings.clone();
+        keybindings.up = Event::Char('x');
+        keybindings.history_back = Event::Char('x');
+        assert!(keybindings.conflicts().contains(&("up", "history_back")));
+    }
+
+    #[test]
+    fn config_errors() {
+        let errors = |config_str| {
+            let mut config = Config::new();
+            config.errors.clear();
+            config.load_config_str(config_str);
+            config
+                .errors()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            errors("[theme]\ntext = \"nocolor\"\n\n[settings.toc]\nposition = \"middle\"\n"),
+            vec![
+                "line 2: theme.text: unknown color, got 'nocolor'",
+                "line 5: settings.toc.position: unknown toc position, got 'middle'"
+            ]
+        );
+        assert_eq!(
+            errors("[features]\nlinks = \"yes\"\n"),
+            vec![
+                "line 2: invalid type: string \"yes\", expected a boolean for key `features.links`"
+            ]
+        );
+        assert!(errors("[features]\nlinks = true\n").is_empty());
+        assert_eq!(
+            errors("[settings.search]\nresults_limit = 0\n"),
+            vec!["line 2: settings.search.results_limit: the limit has to be between 1 and 500, got '0'"]
+        );
+        let cache_errors = errors("[settings.cache]\npath = \"/dev/null/cache\"\n");
+        assert_eq!(cache_errors.len(), 1);
+        assert!(cache_errors[0]
+            .starts_with("line 2: settings.cache.path: couldn't create the cache directory"));
+    }
+
+    #[test]
+    fn link_decorations() {
+        let mut config = Config::new();
+        config.load_config_str(
+            "[theme.article_view]\nlink = \"red\"\nlink_decoration = [\"brackets\", \"color\", \"blink\"]\n",
+        );
+
+        assert_eq!(
+            config.theme.links_for(None),
+            (
+                LinkDecoration {
+                    underline: false,
+                    brackets: true,
+                    color: true,
+                },
+                Color::Dark(BaseColor::Red)
+            )
+        );
+        assert_eq!(config.errors().len(), 1);
+        assert_eq!(
+            Config::new().theme.links_for(None).0,
+            LinkDecoration::default()
+        );
+    }
+
+    #[test]
+    fn wiki_themes() {
+        let mut config = Config::new();
+        config.load_config_str(
+            "[theme.article_view]\ntext = \"blue\"\n\n[theme.wikis.\"https://en.wiktionary.org/\"]\ntext = \"red\"\n",
+        );
+
+        let text = |base_url| {
+            config
+                .theme
+                .article_view_for(base_url)
+                .map(|theme| theme.text)
+        };
+        assert_eq!(
+            text(Some("https://en.wiktionary.org/")),
+            Some(Color::Dark(BaseColor::Red))
+        );
+        assert_eq!(
+            text(Some("https://en.wikipedia.org/")),
+            Some(Color::Dark(BaseColor::Blue))
+        );
+        assert_eq!(text(None), Some(Color::Dark(BaseColor::Blue)));
+    }
+
+    #[test]
+    fn config_files() {
+        let dir = std::env::temp_dir().join(format!("wiki-tui-test-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("config.toml");
+        std::fs::write(&file, "").unwrap();
+
+        assert!(check_config_file(&file).is_ok());
+        assert!(check_config_file(&dir).is_err());
+        assert!(check_config_file(&dir.join("missing.toml")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,7 +1,86 @@
 use crate::config::CONFIG;
 
+use lazy_static::lazy_static;
+use log::{Level, LevelFilter, Record};
+use log4rs::append::Append;
 use log4rs::config::{Appender, Config, Root};
 use log4rs::Handle;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The most recent log entries, so they can be displayed without leaving wiki-tui
+    static ref LOG_BUFFER: Mutex<LogBuffer> = Mutex::new(LogBuffer::new(0));
+}
+
+/// Returns the buffered log entries that are at least as severe as a given level, the oldest one
+/// first
+pub fn recent_entries(level: LevelFilter) -> Vec<LogEntry> {
+    LOG_BUFFER.lock().unwrap().entries(level)
+}
+
+/// A single log entry kept in memory
+#[derive(Clone, Debug)]
+pub struct LogEntry {
+    pub level: Level,
+    pub message: String,
+}
+
+/// Keeps up to capacity log entries, dropping the oldest ones when it's full
+struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        LogBuffer {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    fn entries(&self, level: LevelFilter) -> Vec<LogEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.level <= level)
+            .cloned()
+            .collect()
+    }
+}
+
+/// An appender that writes the log entries into the in-memory buffer
+#[derive(Debug)]
+struct MemoryAppender;
+
+impl Append for MemoryAppender {
+    fn append(&self, record: &Record) -> anyhow::Result<()> {
+        LOG_BUFFER.lock().unwrap().push(LogEntry {
+            level: record.level(),
+            message: record.args().to_string(),
+        });
+        Ok(())
+    }
+
+    fn flush(&self) {}
+}
 
 pub struct Logger {
     handle: Handle,
@@ -38,11 +117,18 @@ impl Logger {
             .build(CONFIG.logging.log_dir.as_path())
             .unwrap();
 
+        LOG_BUFFER
+            .lock()
+            .unwrap()
+            .set_capacity(CONFIG.logging.buffer_size);
+
         let default_config = Config::builder()
             .appender(Appender::builder().build("wiki_tui", Box::new(wiki_tui)))
+            .appender(Appender::builder().build("memory", Box::new(MemoryAppender)))
             .build(
                 Root::builder()
                     .appender("wiki_tui")
+                    .appender("memory")
                     .build(CONFIG.logging.log_level),
             )
             .unwrap();
@@ -57,3 +143,41 @@ impl Default for Logger {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{LogBuffer, LogEntry};
+    use log::{Level, LevelFilter};
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry {
+            level,
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn log_buffer() {
+        let mut buffer = LogBuffer::new(2);
+        buffer.push(entry(Level::Info, "first"));
+        buffer.push(entry(Level::Error, "second"));
+        buffer.push(entry(Level::Warn, "third"));
+
+        let messages = |level| {
+            buffer
+                .entries(level)
+                .into_iter()
+                .map(|entry| entry.message)
+                .collect::<Vec<String>>()
+        };
+        assert_eq!(messages(LevelFilter::Trace), vec!["second", "third"]);
+        assert_eq!(messages(LevelFilter::Error), vec!["second"]);
+
+        buffer.set_capacity(1);
+        assert_eq!(buffer.entries(LevelFilter::Trace).len(), 1);
+
+        let mut buffer = LogBuffer::new(0);
+        buffer.push(entry(Level::Error, "dropped"));
+        assert!(buffer.entries(LevelFilter::Trace).is_empty());
+    }
+}
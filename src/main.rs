@@ -6,9 +6,9 @@ extern crate log;
 extern crate cursive;
 
 use crate::config::CONFIG;
-use cursive::align::HAlign;
 use cursive::backends;
 use cursive::direction::Orientation;
+use cursive::event;
 use cursive::theme::*;
 use cursive::traits::*;
 use cursive::view::Resizable;
@@ -18,7 +18,11 @@ use cursive_buffered_backend::BufferedBackend;
 use std::fs;
 use std::io::Write;
 
-use crate::ui::RootLayout;
+use crate::ui::{
+    messages::{t, t_args},
+    status::{StatusBar, StatusContext, StatusTracker},
+    tabs::TabView,
+};
 use crate::wiki::search::SearchResult;
 
 pub mod cli;
@@ -79,21 +83,116 @@ fn main() {
         };
     });
 
+    // a config file given as an argument has to be usable, falling back to the default one
+    // would hide the mistake
+    if let Some(ref config_file) = CONFIG.get_args().config {
+        if let Err(error) = config::check_config_file(config_file) {
+            eprintln!("Error: {:#}", error);
+            std::process::exit(1);
+        }
+    }
+
     initialize();
+    if CONFIG.get_args().print {
+        print_article();
+        return;
+    }
     start_application();
 }
 
 fn initialize() {
+    // create and initialize the logger
+    let logger = logging::Logger::new();
+
+    // the logo would end up in the printed article
     #[cfg(debug_assertions)]
-    println!("{}", LOGO);
+    if !CONFIG.get_args().print {
+        println!("{}", LOGO);
+    }
 
-    // create and initialize the logger
-    logging::Logger::new().initialize();
+    logger.initialize();
+}
+
+/// Prints the article given by the arguments as plain text to stdout, without starting the tui.
+/// If the article can't be printed, the error is printed to stderr and wiki-tui exits with 1
+fn print_article() {
+    for error in CONFIG.errors() {
+        eprintln!("Config error: {}", error);
+    }
+    if let Err(error) = fetch_article_text().map(|text| println!("{}", text)) {
+        log::error!("{:?}", error);
+        eprintln!("Error: {:#}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Helper function that fetches the text of the article given by the arguments
+fn fetch_article_text() -> anyhow::Result<String> {
+    let args = CONFIG.get_args();
+    let wiki = ui::wikis::current_wiki();
+
+    let (page_id, title) = if let Some(search_query) = args.search_query.as_ref() {
+        let search_result = ui::search::find_article(search_query)?;
+        (*search_result.page_id(), search_result.title().to_string())
+    } else if let Some(article_id) = args.article_id {
+        (article_id, article_id.to_string())
+    } else if args.random {
+        wiki::random::random_article(
+            &wiki.base_url,
+            &wiki.script_path,
+            std::time::Duration::from_millis(CONFIG.api_config.timeout_ms),
+            CONFIG.api_config.max_retries,
+            &CONFIG.api_config.user_agent,
+        )?
+    } else {
+        anyhow::bail!("--print needs a search query, an article id or --random");
+    };
+
+    ui::article::article_text(&wiki.base_url, page_id, &title)
 }
 
 fn start_application() {
     let mut siv = Cursive::new();
     siv.add_global_callback('q', Cursive::quit);
+    siv.add_global_callback(
+        CONFIG.keybindings.bookmarks.clone(),
+        ui::bookmarks::on_bookmarks_show,
+    );
+    siv.add_global_callback(
+        CONFIG.keybindings.switch_wiki.clone(),
+        ui::wikis::on_wiki_select,
+    );
+    siv.add_global_callback(
+        CONFIG.keybindings.random_article.clone(),
+        ui::article::on_random_article,
+    );
+    siv.add_global_callback(
+        CONFIG.keybindings.command_palette.clone(),
+        ui::palette::on_palette_show,
+    );
+    siv.add_global_callback(CONFIG.keybindings.next_tab.clone(), ui::tabs::on_next_tab);
+    siv.add_global_callback(
+        CONFIG.keybindings.previous_tab.clone(),
+        ui::tabs::on_previous_tab,
+    );
+    siv.add_global_callback(CONFIG.keybindings.close_tab.clone(), ui::tabs::on_tab_close);
+    siv.add_global_callback(
+        CONFIG.keybindings.reading_log.clone(),
+        ui::reading_log::on_reading_log_show,
+    );
+    siv.add_global_callback(CONFIG.keybindings.show_logs.clone(), ui::logs::on_logs_show);
+    siv.add_global_callback(
+        CONFIG.keybindings.reload_article.clone(),
+        ui::article::on_article_reload,
+    );
+    siv.add_global_callback(
+        CONFIG.keybindings.cycle_toc_position.clone(),
+        ui::toc::on_toc_position_cycle,
+    );
+    siv.add_global_callback(
+        CONFIG.keybindings.search_mode.clone(),
+        ui::search::on_search_mode_switch,
+    );
 
     // get and apply the color theme
     let theme = Theme {
@@ -105,6 +204,7 @@ fn start_application() {
     // Create the views
     let search_bar = EditView::new()
         .on_submit(|s, q| ui::search::on_search(s, q.to_string()))
+        .on_edit(|s, q, _| ui::search::on_search_edit(s, q))
         .style({
             if let Some(search_theme) = &config::CONFIG.theme.search_bar {
                 if search_theme.background == search_theme.secondary {
@@ -116,37 +216,72 @@ fn start_application() {
                 ColorStyle::secondary()
             }
         })
-        .with_name("search_bar")
-        .full_width();
+        .with_name("search_bar");
+
+    // let the user cycle through the previous queries
+    let search_bar = OnEventView::new(search_bar)
+        .on_pre_event_inner(event::Key::Up, |search_bar, _| {
+            ui::search::on_search_history_previous(&mut search_bar.get_mut())
+        })
+        .on_pre_event_inner(event::Key::Down, |search_bar, _| {
+            ui::search::on_search_history_next(&mut search_bar.get_mut())
+        });
+    let search_bar = StatusTracker::new(StatusContext::SearchBar, search_bar).full_width();
 
     let search_layout = view_with_theme!(
         config::CONFIG.theme.search_bar,
-        Dialog::around(LinearLayout::horizontal().child(search_bar))
-            .title("Search")
-            .title_position(cursive::align::HAlign::Left)
+        Dialog::around(
+            LinearLayout::vertical()
+                .child(search_bar)
+                .child(ui::search::live_search_view())
+        )
+        .title(ui::wikis::search_title())
+        .title_position(cursive::align::HAlign::Left)
+        .with_name("search_dialog")
     );
 
-    let logo_view = TextView::new(LOGO)
-        .h_align(HAlign::Center)
-        .with_name("logo_view")
-        .full_screen();
-
-    let article_layout = RootLayout::new(Orientation::Horizontal, CONFIG.keybindings.clone())
-        .child(Dialog::around(logo_view))
-        .with_name("article_layout");
+    // the start screen is only shown until the first article is opened
+    let mut article_layout = ui::toc::article_layout();
+    if let Some(start_view) = ui::start::start_view() {
+        article_layout.add_child(start_view);
+    }
+    let article_tabs = TabView::new(article_layout.with_name("article_layout"));
 
-    // Add a fullscreen layer, containing the search bar and the article view
+    // Add a fullscreen layer, containing the search bar, the article view and the status bar
+    let mut main_layout = LinearLayout::vertical()
+        .child(search_layout)
+        .child(article_tabs.with_name("article_tabs"));
+    if CONFIG.features.status_bar {
+        main_layout.add_child(StatusBar::new());
+    }
     siv.add_fullscreen_layer(
-        Dialog::around(
-            LinearLayout::vertical()
-                .child(search_layout)
-                .child(article_layout),
-        )
-        .title("wiki-tui")
-        .button("Quit", Cursive::quit)
-        .full_screen(),
+        Dialog::around(main_layout)
+            .title("wiki-tui")
+            .button(t("bookmarks.title"), ui::bookmarks::on_bookmarks_show)
+            .button(t("dialog.quit"), Cursive::quit)
+            .full_screen(),
     );
 
+    // the problems with the config file are shown before anything else
+    ui::utils::show_config_errors(&mut siv);
+
+    // tell the user when the wiki limits the requests, they are retried after a while
+    let cb_sink = siv.cb_sink().clone();
+    wiki::request::on_rate_limit(move |delay| {
+        let message = t_args("error.rate_limited", &[&delay.as_secs().to_string()]);
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            ui::status::display_message(s, message.clone())
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+
+    // keep the displayed article up to date, if it's enabled
+    ui::article::start_auto_refresh(&siv);
+
+    // make sure the wiki can be used before anything is searched for
+    ui::wikis::check_wiki(&mut siv, ui::wikis::current_wiki());
+
     // Start the application
     let argument_callback = handle_arguments();
     if let Err(error) = siv.cb_sink().send(argument_callback) {
@@ -158,6 +293,9 @@ fn start_application() {
     if std::panic::catch_unwind(|| siv_box.lock().unwrap().run_with(|| backend())).is_err() {
         error::print_panic();
     }
+
+    // the article read last is only logged when wiki-tui is closed
+    ui::reading_log::finish_reading();
 }
 
 fn handle_arguments() -> Box<dyn FnOnce(&mut Cursive) + Send> {
@@ -191,6 +329,11 @@ fn handle_arguments() -> Box<dyn FnOnce(&mut Cursive) + Send> {
         });
     }
 
+    if config::CONFIG.get_args().random {
+        log::info!("opening a random article");
+        return Box::new(ui::article::on_random_article);
+    }
+
     Box::new(|_: &mut Cursive| {})
 }
 
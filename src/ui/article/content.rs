@@ -1,15 +1,28 @@
-use cursive::{direction::Absolute, Vec2};
+use cursive::{
+    direction::Absolute,
+    theme::{Effect, Style},
+    Vec2,
+};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 use std::rc::Rc;
 
-use crate::wiki::article::{Article, ArticleElement};
+use crate::wiki::article::{Article, ArticleElement, ArticleImage};
 use crate::{
-    config::CONFIG,
-    ui::article::{
-        lines::{Line, LinesWrapper},
-        links::LinkHandler,
+    config::{ArticleAlignment, ExportFormat, LineSpacing, CONFIG},
+    ui::{
+        self,
+        article::{
+            lines::{line_length, selected_text, Line, LinesWrapper, Selection},
+            links::LinkHandler,
+        },
+        messages::t_args,
     },
 };
 
+/// The width the lines are wrapped at when limiting them is toggled on without a configured width
+const DEFAULT_MAX_LINE_WIDTH: usize = 80;
+
 /// The content of an ArticleView. Handles text formatting
 pub struct ArticleContent {
     /// The article
@@ -17,12 +30,18 @@ pub struct ArticleContent {
     /// Wrapped lines, ready for drawing
     rendered_lines: Vec<Line>,
 
-    /// The y-coordinates of the headers, only created and used when it's enabled in the
-    /// configuration
-    header_y_coords: Option<Vec<usize>>,
+    /// The y-coordinates of the headers by their id, only created and used when it's enabled in
+    /// the configuration
+    header_y_coords: Option<HashMap<i32, usize>>,
 
     /// The LinkHandler, only created and used when it's enabled in the configuration
     link_handler: Option<LinkHandler>,
+
+    /// The width the lines are wrapped at, if they are limited
+    max_line_width: Option<usize>,
+
+    /// How many blank lines separate paragraphs and headers
+    spacing: LineSpacing,
 }
 
 impl ArticleContent {
@@ -34,6 +53,72 @@ impl ArticleContent {
             rendered_lines: Vec::new(),
             header_y_coords: None,
             link_handler: None,
+            max_line_width: CONFIG.settings.article.max_line_width,
+            spacing: CONFIG.settings.article.spacing,
+        }
+    }
+
+    /// The displayed article
+    pub fn article(&self) -> &Article {
+        &self.article
+    }
+
+    /// Adds downloaded images to the article. The lines have to be computed again afterwards
+    pub fn add_images(&mut self, images: &[(i32, ArticleImage)]) {
+        for (id, image) in images {
+            self.article.set_image(*id, image.clone());
+        }
+    }
+
+    /// The optional url of the wikipedia the article was fetched from
+    pub fn base_url(&self) -> Option<&str> {
+        self.article.base_url()
+    }
+
+    /// The optional title of the article
+    pub fn title(&self) -> Option<&str> {
+        self.article.title()
+    }
+
+    /// The optional id of the article
+    pub fn page_id(&self) -> Option<i32> {
+        self.article.page_id()
+    }
+
+    /// Limits the width of the lines to the configured width, or stops limiting it. The lines have
+    /// to be computed again afterwards
+    pub fn toggle_max_line_width(&mut self) {
+        self.max_line_width = match self.max_line_width {
+            Some(_) => None,
+            None => Some(
+                CONFIG
+                    .settings
+                    .article
+                    .max_line_width
+                    .unwrap_or(DEFAULT_MAX_LINE_WIDTH),
+            ),
+        };
+        log::info!("the maximum line width is now '{:?}'", self.max_line_width);
+    }
+
+    /// Switches to the next line spacing, from compact over normal to spacious. The lines have to
+    /// be computed again afterwards
+    pub fn toggle_spacing(&mut self) {
+        self.spacing = self.spacing.next();
+        log::info!("the line spacing is now '{:?}'", self.spacing);
+    }
+
+    /// Returns the width the lines are wrapped at, for a view with the given width
+    fn line_width(&self, width: usize) -> usize {
+        self.max_line_width.map_or(width, |max| width.min(max))
+    }
+
+    /// Returns how far the lines are moved to the right in a view with the given width, so that
+    /// they are aligned as configured
+    pub fn line_offset(&self, width: usize) -> usize {
+        match CONFIG.settings.article.alignment {
+            ArticleAlignment::LEFT => 0,
+            ArticleAlignment::CENTER => (width - self.line_width(width)) / 2,
         }
     }
 
@@ -47,6 +132,11 @@ impl ArticleContent {
         None
     }
 
+    /// Returns the text of the current link. References have no text worth using
+    pub fn current_link_text(&self) -> Option<String> {
+        link_text(self.article.elements(), self.current_link()?)
+    }
+
     /// Returns the id of the current link
     pub fn current_link(&self) -> Option<i32> {
         if let Some(ref link_handler) = self.link_handler {
@@ -70,16 +160,94 @@ impl ArticleContent {
         None
     }
 
-    /// Returns the y-position of a given header
-    pub fn header_y_pos(&self, index: usize) -> Option<usize> {
-        if let Some(ref header_y_coords) = self.header_y_coords {
-            if header_y_coords.len() <= index {
-                log::warn!("couldn't retrieve the header y-position, headers_len: '{}' <= header_index '{}'", header_y_coords.len(), index);
-                return None;
+    /// Overrides the current link with the link at the given index
+    pub fn set_current_link_by_index(&mut self, index: usize) {
+        if let Some(ref mut link_handler) = self.link_handler {
+            link_handler.set_current_link_by_index(index);
+        }
+    }
+
+    /// Registers only the links in the given lines, if the article has more links than configured.
+    /// Otherwise every link stays registered
+    pub fn register_links_in(&mut self, lines: Range<usize>) {
+        let max_links = CONFIG.settings.links.max_links;
+        if let Some(ref mut link_handler) = self.link_handler {
+            if max_links != 0 && link_handler.total_links() > max_links {
+                link_handler.register_lines(lines);
             }
-            return Some(header_y_coords[index]);
         }
-        None
+    }
+
+    /// Selects the first link of the article leading to another article, references are skipped.
+    /// Every link is registered for it, even if only the visible ones were before. Returns false
+    /// if the article has no such link
+    pub fn select_first_link(&mut self) -> bool {
+        let link_handler = match self.link_handler {
+            Some(ref mut link_handler) => link_handler,
+            None => return false,
+        };
+        if link_handler.registered_links() < link_handler.total_links() {
+            link_handler.register_lines(0..usize::MAX);
+        }
+
+        let article = &self.article;
+        let index = link_handler.link_ids().position(|id| {
+            article
+                .elements()
+                .find(|element| *element.id() == id)
+                .and_then(|element| element.get_attribute("type"))
+                == Some("link")
+        });
+        match index {
+            Some(index) => {
+                link_handler.set_current_link_by_index(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the line the selection continues in, when it moves past the last or the first of
+    /// the links registered for the given lines
+    pub fn next_unregistered_line(&self, lines: &Range<usize>, forward: bool) -> Option<usize> {
+        self.link_handler
+            .as_ref()?
+            .next_unregistered_line(lines, forward)
+    }
+
+    /// Returns the positions of every registered link, in the order they are numbered in
+    pub fn link_positions(&self) -> Vec<Vec2> {
+        match self.link_handler {
+            Some(ref link_handler) => link_handler.link_positions().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the y-position of the header with a given id
+    pub fn header_y_pos(&self, id: i32) -> Option<usize> {
+        let header_y_pos = self.header_y_coords.as_ref()?.get(&id).copied();
+        if header_y_pos.is_none() {
+            log::warn!("couldn't retrieve the y-position of the header '{}'", id);
+        }
+        header_y_pos
+    }
+
+    /// Returns the y-position of the closest header below or above a given line
+    pub fn next_header_y(&self, y: usize, forward: bool) -> Option<usize> {
+        let header_y = self.header_y_coords.as_ref()?.values().copied();
+        if forward {
+            header_y.filter(|header_y| *header_y > y).min()
+        } else {
+            header_y.filter(|header_y| *header_y < y).max()
+        }
+    }
+
+    /// Selects the first link in the given lines. Returns false if there is no link in them
+    pub fn select_first_link_in(&mut self, lines: Range<usize>) -> bool {
+        match self.link_handler {
+            Some(ref mut link_handler) => link_handler.select_first_link_in(lines),
+            None => false,
+        }
     }
 
     /// Calculates and returns the required size
@@ -91,8 +259,9 @@ impl ArticleContent {
         );
 
         // get the required width from a LinesWrapper
+        let (link_decoration, link_color) = CONFIG.theme.links_for(self.base_url());
         let required_width = LinesWrapper::new(
-            size.x,
+            self.line_width(size.x),
             // we have to clone all of the elements
             Rc::new(
                 self.article
@@ -101,6 +270,7 @@ impl ArticleContent {
                     .collect::<Vec<ArticleElement>>(),
             ),
         )
+        .links(link_decoration, link_color)
         .required_width();
 
         // if the rendered lines are empty, render them
@@ -109,8 +279,8 @@ impl ArticleContent {
         }
 
         // the required width is 0, when any of the lines are wrapped. When this happens we
-        // require the full width
-        if required_width == 0 {
+        // require the full width. Limited lines are aligned inside of the full width too
+        if required_width == 0 || self.max_line_width.is_some() {
             log::debug!("lines are wrapped, requiring the full width");
             log::debug!("required_size finished successfully");
             return Vec2::new(size.x, self.rendered_lines.len());
@@ -132,27 +302,34 @@ impl ArticleContent {
             size.y
         );
 
+        // we have to clone all the elements
+        let mut elements = self
+            .article
+            .elements()
+            .cloned()
+            .collect::<Vec<ArticleElement>>();
+        if CONFIG.settings.article.redirect_notice {
+            if let Some(redirected_from) = self.article.redirected_from() {
+                insert_redirect_notice(&mut elements, redirected_from);
+            }
+        }
+
         // render the lines
-        let lines_wrapper = LinesWrapper::new(
-            size.x,
-            // we have to clone all the elements
-            Rc::new(
-                self.article
-                    .elements()
-                    .cloned()
-                    .collect::<Vec<ArticleElement>>(),
-            ),
-        )
-        .wrap_lines();
+        let (link_decoration, link_color) = CONFIG.theme.links_for(self.base_url());
+        let lines_wrapper = LinesWrapper::new(self.line_width(size.x), Rc::new(elements))
+            .images(self.article.images().clone())
+            .spacing(self.spacing)
+            .links(link_decoration, link_color)
+            .visited_links(
+                self.visited_links(),
+                CONFIG.theme.visited_link_for(self.base_url()),
+            )
+            .wrap_lines();
 
         self.link_handler = lines_wrapper.link_handler;
         self.rendered_lines = lines_wrapper.rendered_lines;
 
-        if let Some(ref header_y) = lines_wrapper.header_y {
-            let mut header_y_coords = header_y.clone().into_values().collect::<Vec<usize>>();
-            header_y_coords.sort_unstable();
-            self.header_y_coords = Some(header_y_coords);
-        }
+        self.header_y_coords = lines_wrapper.header_y;
 
         log::debug!(
             "compute_lines finished successfully, rendering '{}' lines",
@@ -160,11 +337,45 @@ impl ArticleContent {
         );
     }
 
+    /// Helper function that returns the ids of the links to articles that have been opened
+    fn visited_links(&self) -> HashSet<i32> {
+        let base_url = match self.base_url() {
+            Some(base_url) => base_url.to_string(),
+            None => ui::wikis::current_wiki().base_url,
+        };
+        self.article
+            .elements()
+            .filter(|element| element.get_attribute("type") == Some("link"))
+            .filter(|element| {
+                element
+                    .get_attribute("target")
+                    .is_some_and(|target| ui::visited::is_visited(&base_url, target))
+            })
+            .map(|element| *element.id())
+            .collect()
+    }
+
     /// Returns an iterator over the rendered lines
     pub fn get_rendered_lines(&self) -> impl Iterator<Item = &Line> {
         self.rendered_lines.iter()
     }
 
+    /// Returns the number of characters in the rendered line at a given y position
+    pub fn line_length(&self, y: usize) -> usize {
+        self.rendered_lines.get(y).map_or(0, line_length)
+    }
+
+    /// Returns the text inside a selection of the rendered lines
+    pub fn selected_text(&self, selection: &Selection) -> String {
+        selected_text(&self.rendered_lines, selection)
+    }
+
+    /// Returns how far the article has been scrolled, in percent, when the viewport with a given
+    /// height starts at the given line
+    pub fn scroll_progress(&self, viewport_offset: usize, viewport_height: usize) -> usize {
+        scroll_percentage(viewport_offset, viewport_height, self.rendered_lines.len())
+    }
+
     /// Moves the selected link by in a direction by a given amount
     pub fn move_selected_link(&mut self, direction: Absolute, amount: usize) {
         if !CONFIG.features.links {
@@ -173,8 +384,14 @@ impl ArticleContent {
 
         if let Some(ref mut link_handler) = self.link_handler {
             match direction {
+                Absolute::Left if CONFIG.settings.links.stay_on_line => {
+                    link_handler.move_left_on_line(amount)
+                }
                 Absolute::Left => link_handler.move_left(amount),
                 Absolute::Up => link_handler.move_up(amount),
+                Absolute::Right if CONFIG.settings.links.stay_on_line => {
+                    link_handler.move_right_on_line(amount)
+                }
                 Absolute::Right => link_handler.move_right(amount),
                 Absolute::Down => link_handler.move_down(amount),
                 Absolute::None => {}
@@ -182,6 +399,36 @@ impl ArticleContent {
         }
     }
 
+    /// Moves the selected link up or down by a given amount, without wrapping around. It's used
+    /// for keeping the selected link inside of the viewport while scrolling
+    pub fn move_selected_link_without_wrap(&mut self, direction: Absolute, amount: usize) {
+        if !CONFIG.features.links {
+            return;
+        }
+
+        if let Some(ref mut link_handler) = self.link_handler {
+            match direction {
+                Absolute::Up => link_handler.move_up_without_wrap(amount),
+                Absolute::Down => link_handler.move_down_without_wrap(amount),
+                _ => {}
+            }
+        }
+    }
+
+    /// Finds every occurrence of a query in the rendered lines, ignoring the case. Returns the
+    /// position and the text of every match
+    pub fn find(&self, query: &str) -> Vec<(Vec2, String)> {
+        self.rendered_lines
+            .iter()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                find_in_line(line, query)
+                    .into_iter()
+                    .map(move |(x, text)| (Vec2::new(x, y), text))
+            })
+            .collect()
+    }
+
     /// Retrieves the element at the given position. If no element could be found at that position,
     /// none is returned
     pub fn get_element_at_position(&self, position: Vec2) -> Option<&ArticleElement> {
@@ -209,4 +456,334 @@ impl ArticleContent {
         // no element could be found at that position
         None
     }
+
+    /// Converts the article into a given format for saving it in a file
+    pub fn export(&self, format: ExportFormat) -> String {
+        export_elements(self.article.elements(), self.base_url(), format)
+    }
+}
+
+/// Helper function that converts the elements of an article into a given format. In markdown, the
+/// headers, links and bold or italic text keep their formatting and every line is its own
+/// paragraph
+fn export_elements<'a>(
+    elements: impl Iterator<Item = &'a ArticleElement>,
+    base_url: Option<&str>,
+    format: ExportFormat,
+) -> String {
+    let mut text = String::new();
+    let mut is_title = true;
+    for element in elements {
+        let content = element.content();
+        if format == ExportFormat::TEXT {
+            match element.get_attribute("type") {
+                Some("newline") => text.push('\n'),
+                Some("code" | "infobox_title" | "infobox_header") => {
+                    text.push_str(&format!("{}\n", content))
+                }
+                Some("infobox") => text.push_str(&format!(
+                    "{}: {}\n",
+                    element.get_attribute("label").unwrap_or_default(),
+                    content
+                )),
+                _ => text.push_str(content),
+            }
+            continue;
+        }
+
+        match element.get_attribute("type") {
+            Some("newline") => text.push_str("\n\n"),
+            // indented lines are code blocks in markdown
+            Some("code") => text.push_str(&format!("    {}\n", content)),
+            // the rows of infoboxes become a list
+            Some("infobox_title" | "infobox_header") => {
+                text.push_str(&format!("**{}**\n\n", content))
+            }
+            Some("infobox") => text.push_str(&format!(
+                "- **{}**: {}\n",
+                element.get_attribute("label").unwrap_or_default(),
+                content
+            )),
+            Some("header") => {
+                text.push_str(if is_title { "# " } else { "## " });
+                text.push_str(content);
+                is_title = false;
+            }
+            Some("link") => {
+                let target = element.get_attribute("target").unwrap_or_default();
+                let url = match base_url {
+                    Some(base_url) if target.starts_with('/') => {
+                        format!("{}{}", base_url.trim_end_matches('/'), target)
+                    }
+                    _ => target.to_string(),
+                };
+                text.push_str(&format!("[{}]({})", content, url));
+            }
+            _ if content.trim().is_empty() => text.push_str(content),
+            _ if element.style().effects.contains(Effect::Bold) => {
+                text.push_str(&format!("**{}**", content))
+            }
+            _ if element.style().effects.contains(Effect::Italic) => {
+                text.push_str(&format!("*{}*", content))
+            }
+            // the list items are indented with a tab, which markdown reads as code
+            _ => text.push_str(content.strip_prefix('\t').unwrap_or(content)),
+        }
+    }
+
+    // remove the empty lines between paragraphs
+    let mut exported = String::new();
+    let mut empty_lines = 0;
+    for line in text.lines().map(str::trim_end) {
+        if line.is_empty() {
+            empty_lines += 1;
+            continue;
+        }
+        let max_empty_lines = if format == ExportFormat::MARKDOWN {
+            1
+        } else {
+            0
+        };
+        if !exported.is_empty() {
+            exported.push_str(&"\n".repeat(1 + empty_lines.min(max_empty_lines)));
+        }
+        exported.push_str(line);
+        empty_lines = 0;
+    }
+    exported.push('\n');
+    exported
+}
+
+/// Helper function that finds every occurrence of a query in a line, ignoring the case. Returns the
+/// x position and the text of every match
+fn find_in_line(line: &Line, query: &str) -> Vec<(usize, String)> {
+    let query = query.chars().map(lowercase).collect::<Vec<char>>();
+    let chars = line
+        .iter()
+        .flat_map(|element| element.content.chars())
+        .collect::<Vec<char>>();
+
+    let mut matches = Vec::new();
+    if query.is_empty() {
+        return matches;
+    }
+
+    let mut x = 0;
+    while x + query.len() <= chars.len() {
+        let text = &chars[x..x + query.len()];
+        if text
+            .iter()
+            .copied()
+            .map(lowercase)
+            .eq(query.iter().copied())
+        {
+            matches.push((x, text.iter().collect()));
+            x += query.len();
+        } else {
+            x += 1;
+        }
+    }
+    matches
+}
+
+/// Helper function that inserts the notice about the redirect that led to an article below its
+/// title. The notice gets ids no other element has
+fn insert_redirect_notice(elements: &mut Vec<ArticleElement>, redirected_from: &str) {
+    let id = elements
+        .iter()
+        .map(|element| *element.id())
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let notice = t_args("article.redirected_from", &[&redirected_from]);
+    let index = match elements.first() {
+        Some(title) if title.get_attribute("type") == Some("header") => elements
+            .iter()
+            .position(|element| element.get_attribute("type") == Some("newline"))
+            .map_or(elements.len(), |index| index + 1),
+        _ => 0,
+    };
+    elements.splice(
+        index..index,
+        [
+            ArticleElement::new(
+                id,
+                notice.chars().count(),
+                Style::from(CONFIG.theme.text).combine(Effect::Italic),
+                notice,
+            ),
+            ArticleElement::newline(id + 1),
+        ],
+    );
+}
+
+/// Helper function that calculates how far a viewport has been scrolled, in percent. When every
+/// line fits into the viewport, everything has been read
+fn scroll_percentage(viewport_offset: usize, viewport_height: usize, lines_count: usize) -> usize {
+    let scrollable_lines = lines_count.saturating_sub(viewport_height);
+    if scrollable_lines == 0 {
+        return 100;
+    }
+    viewport_offset.min(scrollable_lines) * 100 / scrollable_lines
+}
+
+/// Helper function that converts a character into its lowercase equivalent
+fn lowercase(char: char) -> char {
+    char.to_lowercase().next().unwrap_or(char)
+}
+
+/// Helper function that joins the contents of the elements of a link, with the whitespace
+/// collapsed
+fn link_text<'a>(elements: impl Iterator<Item = &'a ArticleElement>, id: i32) -> Option<String> {
+    let text = elements
+        .filter(|element| element.id() == &id)
+        .filter(|element| element.get_attribute("type") != Some("reference"))
+        .map(|element| element.content())
+        .collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        export_elements, find_in_line, insert_redirect_notice, link_text, scroll_percentage,
+    };
+    use crate::config::ExportFormat;
+    use crate::ui::article::lines::{Line, RenderedElement};
+    use crate::wiki::article::ArticleElement;
+    use cursive::theme::{Effect, Style};
+
+    fn line(contents: &[&str]) -> Line {
+        contents
+            .iter()
+            .enumerate()
+            .map(|(id, content)| RenderedElement {
+                id: id as i32,
+                content: content.to_string(),
+                style: Style::none(),
+                width: content.chars().count(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn find_ignoring_case() {
+        assert_eq!(
+            find_in_line(&line(&["Rust is a ", "language, rust"]), "rust"),
+            vec![(0, "Rust".to_string()), (20, "rust".to_string())]
+        );
+    }
+
+    #[test]
+    fn find_across_elements() {
+        assert_eq!(
+            find_in_line(&line(&["progr", "amming"]), "RAM"),
+            vec![(4, "ram".to_string())]
+        );
+    }
+
+    #[test]
+    fn find_nothing() {
+        assert_eq!(find_in_line(&line(&["wikipedia"]), ""), vec![]);
+        assert_eq!(find_in_line(&line(&["wiki"]), "wikipedia"), vec![]);
+    }
+
+    #[test]
+    fn link_texts() {
+        let elements = [
+            ArticleElement::new(0, 5, Style::none(), "Rust ".to_string()),
+            ArticleElement::new(1, 12, Style::none(), "programming\n".to_string()),
+            ArticleElement::new(1, 8, Style::none(), "language".to_string()),
+            ArticleElement::new(2, 3, Style::none(), "[1]".to_string())
+                .attribute("type", "reference"),
+        ];
+        assert_eq!(
+            link_text(elements.iter(), 1).as_deref(),
+            Some("programming language")
+        );
+        assert_eq!(link_text(elements.iter(), 2), None);
+        assert_eq!(link_text(elements.iter(), 3), None);
+    }
+
+    #[test]
+    fn redirect_notice() {
+        let mut elements = vec![
+            ArticleElement::new(0, 13, Style::none(), "United States".to_string())
+                .attribute("type", "header"),
+            ArticleElement::newline(1),
+            ArticleElement::new(2, 4, Style::none(), "text".to_string()),
+        ];
+        insert_redirect_notice(&mut elements, "USA");
+        assert_eq!(
+            elements
+                .iter()
+                .map(|element| (*element.id(), element.content()))
+                .collect::<Vec<(i32, &str)>>(),
+            vec![
+                (0, "United States"),
+                (1, ""),
+                (3, "Redirected from USA"),
+                (4, ""),
+                (2, "text")
+            ]
+        );
+
+        let mut elements = vec![ArticleElement::new(0, 4, Style::none(), "text".to_string())];
+        insert_redirect_notice(&mut elements, "USA");
+        assert_eq!(elements[0].content(), "Redirected from USA");
+    }
+
+    #[test]
+    fn scroll_percentages() {
+        assert_eq!(scroll_percentage(0, 10, 110), 0);
+        assert_eq!(scroll_percentage(42, 10, 110), 42);
+        assert_eq!(scroll_percentage(100, 10, 110), 100);
+        assert_eq!(scroll_percentage(120, 10, 110), 100);
+        assert_eq!(scroll_percentage(0, 10, 5), 100);
+    }
+
+    #[test]
+    fn export() {
+        let text = |id, content: &str| {
+            ArticleElement::new(id, content.len(), Style::none(), content.to_string())
+        };
+        let elements = vec![
+            text(0, "Rust").attribute("type", "header"),
+            ArticleElement::newline(1),
+            text(11, "Rust").attribute("type", "infobox_title"),
+            text(12, "2015")
+                .attribute("type", "infobox")
+                .attribute("label", "First appeared"),
+            ArticleElement::newline(13),
+            text(2, "Rust")
+                .attribute("type", "link")
+                .attribute("target", "/wiki/Rust"),
+            text(3, " is "),
+            ArticleElement::new(4, 4, Style::from(Effect::Bold), "fast".to_string()),
+            ArticleElement::newline(5),
+            text(6, "History").attribute("type", "header"),
+            ArticleElement::newline(7),
+            ArticleElement::newline(8),
+            text(9, "\t- "),
+            text(10, "2010"),
+        ];
+
+        assert_eq!(
+            export_elements(
+                elements.iter(),
+                Some("https://en.wikipedia.org/"),
+                ExportFormat::TEXT
+            ),
+            "Rust\nRust\nFirst appeared: 2015\nRust is fast\nHistory\n\t- 2010\n"
+        );
+        assert_eq!(
+            export_elements(
+                elements.iter(),
+                Some("https://en.wikipedia.org/"),
+                ExportFormat::MARKDOWN
+            ),
+            "# Rust\n\n**Rust**\n\n- **First appeared**: 2015\n\n[Rust](https://en.wikipedia.org/wiki/Rust) is **fast**\n\n## History\n\n- 2010\n"
+        );
+    }
 }
@@ -1,9 +1,10 @@
-use crate::config::CONFIG;
+use crate::config::{LineSpacing, LinkDecoration, CONFIG};
 use crate::ui::article::links::LinkHandler;
-use crate::wiki::article::ArticleElement;
+use crate::wiki::article::{ArticleElement, ArticleImage};
 
-use cursive::theme::Style;
-use std::collections::HashMap;
+use cursive::theme::{Color, ColorStyle, Effect, Style};
+use cursive::Vec2;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::rc::Rc;
 
@@ -37,6 +38,92 @@ impl RenderedElement {
 
 pub type Line = Vec<RenderedElement>;
 
+/// A range of selected text in the rendered lines. Positions are measured in characters. The
+/// anchor is where the selection was started and the cursor is the end that is moved
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Selection {
+    /// The position the selection was started at
+    anchor: Vec2,
+    /// The position of the moving end of the selection
+    cursor: Vec2,
+}
+
+impl Selection {
+    /// Creates a new Selection containing only the character at a given position
+    pub fn new(position: Vec2) -> Self {
+        Selection {
+            anchor: position,
+            cursor: position,
+        }
+    }
+
+    /// Returns the position of the moving end of the selection
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    /// Moves the moving end of the selection to a given position
+    pub fn set_cursor(&mut self, cursor: Vec2) {
+        self.cursor = cursor;
+    }
+
+    /// Returns the first and the last selected position, in reading order
+    pub fn bounds(&self) -> (Vec2, Vec2) {
+        if (self.anchor.y, self.anchor.x) <= (self.cursor.y, self.cursor.x) {
+            (self.anchor, self.cursor)
+        } else {
+            (self.cursor, self.anchor)
+        }
+    }
+
+    /// Checks if the character at a given position is selected
+    pub fn contains(&self, position: Vec2) -> bool {
+        let (start, end) = self.bounds();
+        (start.y, start.x) <= (position.y, position.x) && (position.y, position.x) <= (end.y, end.x)
+    }
+}
+
+/// Returns the selected text of the rendered lines. Trailing whitespace is removed from every line
+/// and the lines are joined with newlines
+pub fn selected_text(lines: &[Line], selection: &Selection) -> String {
+    let (start, end) = selection.bounds();
+    let mut text = Vec::new();
+    for (y, line) in lines
+        .iter()
+        .enumerate()
+        .skip(start.y)
+        .take(end.y + 1 - start.y)
+    {
+        let chars = line
+            .iter()
+            .flat_map(|element| element.content.chars())
+            .collect::<Vec<char>>();
+        let from = if y == start.y { start.x } else { 0 }.min(chars.len());
+        let to = if y == end.y { end.x + 1 } else { chars.len() }.min(chars.len());
+        let line_text = chars[from..to.max(from)].iter().collect::<String>();
+        text.push(line_text.trim_end().to_string());
+    }
+    text.join("\n")
+}
+
+/// Returns the number of characters in a rendered line
+pub fn line_length(line: &Line) -> usize {
+    line.iter()
+        .map(|element| element.content.chars().count())
+        .sum()
+}
+
+/// The maximal width of a rendered image, measured in characters
+const MAX_IMAGE_WIDTH: usize = 60;
+/// How far code blocks are indented, measured in characters
+const CODE_INDENT: usize = 4;
+/// The beginning of every line of a quote
+const QUOTE_PREFIX: &str = "│ ";
+/// The maximal width of an infobox, measured in characters
+const MAX_INFOBOX_WIDTH: usize = 60;
+/// The minimal width of an infobox. In narrower lines, infoboxes are left out
+const MIN_INFOBOX_WIDTH: usize = 12;
+
 /// Generates lines of elements in constrained width
 pub struct LinesWrapper {
     /// The line that is currently being rendered
@@ -62,6 +149,26 @@ pub struct LinesWrapper {
 
     /// The y coordinates of the headers, it is only created and used when enabled in the config
     pub header_y: Option<HashMap<i32, usize>>,
+
+    /// The downloaded images, by the id of their element
+    images: HashMap<i32, ArticleImage>,
+
+    /// How many blank lines separate paragraphs and headers
+    spacing: LineSpacing,
+    /// How many blank lines the rendered lines end with
+    blank_lines: usize,
+
+    /// The width of the label column of the infobox that is being rendered, if there is one
+    infobox_label_width: Option<usize>,
+
+    /// How links are decorated
+    link_decoration: LinkDecoration,
+    /// The color of links with the color decoration
+    link_color: Color,
+    /// The ids of the links to articles that have been opened
+    visited_links: HashSet<i32>,
+    /// The color of the visited links
+    visited_link_color: Color,
 }
 
 impl LinesWrapper {
@@ -86,7 +193,7 @@ impl LinesWrapper {
 
             link_handler: {
                 if CONFIG.features.links {
-                    Some(LinkHandler::new())
+                    Some(LinkHandler::new(CONFIG.settings.links.wrap))
                 } else {
                     None
                 }
@@ -99,9 +206,52 @@ impl LinesWrapper {
                     None
                 }
             },
+
+            images: HashMap::new(),
+
+            spacing: CONFIG.settings.article.spacing,
+            blank_lines: 0,
+
+            infobox_label_width: None,
+
+            link_decoration: CONFIG.theme.links_for(None).0,
+            link_color: CONFIG.theme.links_for(None).1,
+
+            visited_links: HashSet::new(),
+            visited_link_color: CONFIG.theme.visited_link_for(None),
         }
     }
 
+    /// Renders the given images above the captions of their elements
+    #[must_use]
+    pub fn images(mut self, images: HashMap<i32, ArticleImage>) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Separates paragraphs and headers with the blank lines of a given spacing
+    #[must_use]
+    pub fn spacing(mut self, spacing: LineSpacing) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Decorates the links with a given decoration. The color decoration uses the given color
+    #[must_use]
+    pub fn links(mut self, decoration: LinkDecoration, color: Color) -> Self {
+        self.link_decoration = decoration;
+        self.link_color = color;
+        self
+    }
+
+    /// Colors the links with the given ids in a given color, marking them as visited
+    #[must_use]
+    pub fn visited_links(mut self, ids: HashSet<i32>, color: Color) -> Self {
+        self.visited_links = ids;
+        self.visited_link_color = color;
+        self
+    }
+
     /// Wraps the lines and returns the required width. This method is way cheaper than wrap_lines
     /// because it only calculates the required width and nothing else
     pub fn required_width(mut self) -> usize {
@@ -121,15 +271,34 @@ impl LinesWrapper {
                 }
             }
 
+            // every line of code is on its own line
+            if element.get_attribute("type") == Some("code") {
+                self.current_width = CODE_INDENT;
+            }
+
+            // infoboxes are drawn as panels that use the whole width
+            if is_infobox_element(element) {
+                log::debug!("required_width finished successfully with a width of '0'");
+                return 0;
+            }
+
+            // links can be surrounded by brackets
+            let width =
+                if self.link_decoration.brackets && element.get_attribute("type") == Some("link") {
+                    element.width() + 2
+                } else {
+                    *element.width()
+                };
+
             // does it fit into the current line?
-            if element.width() + self.current_width < self.width {
+            if width + self.current_width < self.width {
                 // yay, it fits
                 // add its width to the current line
-                self.current_width += element.width();
+                self.current_width += width;
 
                 // store the width of the element if it is the biggest one yet
-                if element.width() > &self.max_width {
-                    self.max_width = *element.width();
+                if width > self.max_width {
+                    self.max_width = width;
                     continue;
                 }
             }
@@ -152,12 +321,32 @@ impl LinesWrapper {
         log::debug!("wrap_lines was called");
 
         // go through every element
-        for element in self.elements.clone().iter() {
+        for (index, element) in self.elements.clone().iter().enumerate() {
             // get the type of the element
             let element_type = element.get_attribute("type").unwrap_or("text");
 
-            // is this a link?
-            let is_link = element_type == "link" && element.get_attribute("target").is_some();
+            // infoboxes are drawn as panels, they are closed by the first element after them
+            if is_infobox_element(element) {
+                self.push_infobox_element(index, element);
+                continue;
+            }
+            if self.infobox_label_width.is_some() {
+                self.close_infobox();
+            }
+
+            // is this a link? References are selected like links
+            let is_link = (element_type == "link" || element_type == "reference")
+                && element.get_attribute("target").is_some();
+
+            // is this part of a quote?
+            let is_quote = element.get_attribute("quote") == Some("true");
+
+            // links are decorated as configured
+            let (content, style) = if element_type == "link" {
+                self.decorate_link(element)
+            } else {
+                (element.content().to_string(), *element.style())
+            };
 
             // is this a toc header?
             let is_toc_header = element_type == "header"
@@ -165,16 +354,46 @@ impl LinesWrapper {
 
             // does this element go onto a new line?
             if element_type == "newline" {
-                // fill the current line and make the next one blank
+                // fill the current line and add the blank lines separating the paragraphs
                 self.fill_line();
                 self.newline();
 
-                self.fill_line();
-                self.newline();
+                for _ in 0..self.spacing.paragraph_lines() {
+                    self.fill_line();
+                    self.newline();
+                }
+
+                continue;
+            }
+
+            // headers at the beginning of a line get some room above them
+            if element_type == "header" && self.current_line.is_empty() {
+                while !self.rendered_lines.is_empty()
+                    && self.blank_lines < self.spacing.header_lines()
+                {
+                    self.fill_line();
+                    self.newline();
+                }
+            }
 
+            // code blocks keep their whitespace, every line of code is on its own line
+            if element_type == "code" {
+                self.push_code_line(element);
                 continue;
             }
 
+            // images are drawn above their caption, each on their own lines
+            if element_type == "image" {
+                let width = self.width.saturating_sub(1).min(MAX_IMAGE_WIDTH);
+                if let Some(rows) = self
+                    .images
+                    .get(element.id())
+                    .map(|image| render_image(image, *element.id(), width))
+                {
+                    self.push_image(rows);
+                }
+            }
+
             // what we do here is fairly simple:
             // First, we split the content into words and then we merge these words together until the
             // line is full. Then we create a new one and do the same thing over and over again until
@@ -182,7 +401,7 @@ impl LinesWrapper {
 
             let mut merged_element = RenderedElement {
                 id: *element.id(),
-                style: *element.style(),
+                style,
                 content: String::new(),
                 width: 0,
             };
@@ -192,12 +411,19 @@ impl LinesWrapper {
 
             // if the element does not have a leading special character and we are not at the beginning
             // of a line, add a leading whitespace
-            if !element.content().starts_with([',', '.', ';', ':']) && !self.current_line.is_empty()
-            {
+            let needs_whitespace =
+                !content.starts_with([',', '.', ';', ':']) && !self.current_line.is_empty();
+
+            // quotes are marked at the beginning of every line
+            if is_quote && self.current_line.is_empty() {
+                self.push_quote_prefix();
+            }
+
+            if needs_whitespace {
                 self.push_whitespace();
             }
 
-            for span in element.content().split_whitespace() {
+            for span in content.split_whitespace() {
                 // does the span fit onto the current line?
                 if span.chars().count() + merged_element.width + self.current_width < self.width {
                     // only add a leading whitespace if the merged element is not empty
@@ -229,9 +455,13 @@ impl LinesWrapper {
                 self.fill_line();
                 self.newline();
 
+                if is_quote {
+                    self.push_quote_prefix();
+                }
+
                 merged_element = RenderedElement {
                     id: *element.id(),
-                    style: *element.style(),
+                    style,
                     content: String::new(),
                     width: 0,
                 };
@@ -264,6 +494,10 @@ impl LinesWrapper {
             }
         }
 
+        if self.infobox_label_width.is_some() {
+            self.close_infobox();
+        }
+
         if let Some(ref header_y) = self.header_y {
             log::debug!("total headers registered: '{}'", header_y.len());
         }
@@ -280,6 +514,26 @@ impl LinesWrapper {
         self
     }
 
+    /// Returns the content and the style of a link with its decoration
+    fn decorate_link(&self, element: &ArticleElement) -> (String, Style) {
+        let mut style = *element.style();
+        if !self.link_decoration.underline {
+            style.effects.remove(Effect::Underline);
+        }
+        if self.visited_links.contains(element.id()) {
+            style = style.combine(self.visited_link_color);
+        } else if self.link_decoration.color {
+            style = style.combine(self.link_color);
+        }
+
+        let content = if self.link_decoration.brackets {
+            format!("[{}]", element.content().trim())
+        } else {
+            element.content().to_string()
+        };
+        (content, style)
+    }
+
     // Registers a new header. If the headers is already registered, it won't be registered again
     fn register_header(&mut self, id: i32, y_pos: usize) {
         if let Some(ref mut header_y) = self.header_y {
@@ -290,13 +544,17 @@ impl LinesWrapper {
         }
     }
 
-    /// Registers a new link with the given id
+    /// Registers a new link with the given id. The link has to be the last element of the current
+    /// line
     fn register_link(&mut self, id: i32) {
         if let Some(ref mut link_handler) = self.link_handler {
+            // the x-coordinate is where the last element of the current line begins and the
+            // y-coordinate is the index the current line will have once it's rendered
+            let link_width = self.current_line.last().map_or(0, |element| element.width);
             link_handler.push_link(
                 id,
-                self.current_line.len().saturating_sub(1),
-                self.rendered_lines.len().saturating_sub(1),
+                self.current_width.saturating_sub(link_width),
+                self.rendered_lines.len(),
             );
         }
     }
@@ -322,8 +580,216 @@ impl LinesWrapper {
         });
     }
 
+    /// Adds the beginning of a line of a quote to the current line, if it fits
+    fn push_quote_prefix(&mut self) {
+        let width = QUOTE_PREFIX.chars().count();
+        if self.current_width + width >= self.width {
+            return;
+        }
+
+        self.push_element(RenderedElement {
+            id: -1,
+            content: QUOTE_PREFIX.to_string(),
+            style: Style::from(CONFIG.theme.quote()),
+            width,
+        });
+    }
+
+    /// Adds a line of a code block as a new, indented line. Lines that are too long are cut off
+    /// and end with an ellipsis
+    fn push_code_line(&mut self, element: &ArticleElement) {
+        if !self.current_line.is_empty() {
+            self.fill_line();
+            self.newline();
+        }
+
+        // a line can't fill the last column
+        let indent = CODE_INDENT.min(self.width.saturating_sub(1));
+        let available = self.width.saturating_sub(1 + indent);
+        let mut content = element
+            .content()
+            .chars()
+            .take(available)
+            .collect::<String>();
+        if element.content().chars().count() > available && content.pop().is_some() {
+            content.push('…');
+        }
+
+        self.push_element(RenderedElement {
+            id: -1,
+            content: " ".repeat(indent),
+            style: Style::none(),
+            width: indent,
+        });
+        self.push_element(RenderedElement {
+            id: *element.id(),
+            width: content.chars().count(),
+            content,
+            style: *element.style(),
+        });
+        self.fill_line();
+        self.newline();
+    }
+
+    /// Adds an element of an infobox as new lines. The first element of an infobox opens its panel,
+    /// with the infobox's title in the top border if it has one
+    fn push_infobox_element(&mut self, index: usize, element: &ArticleElement) {
+        if self.width < MIN_INFOBOX_WIDTH + 1 {
+            return;
+        }
+        let width = self.width.saturating_sub(1).min(MAX_INFOBOX_WIDTH);
+        let border_style = Style::from(CONFIG.theme.text);
+
+        let element_type = element.get_attribute("type").unwrap_or_default();
+        let label_width = match self.infobox_label_width {
+            Some(label_width) => label_width,
+            None => {
+                if !self.current_line.is_empty() {
+                    self.fill_line();
+                    self.newline();
+                }
+
+                // the labels take up at most a third of the panel
+                let label_width = self.elements[index..]
+                    .iter()
+                    .take_while(|element| is_infobox_element(element))
+                    .filter_map(|element| element.get_attribute("label"))
+                    .map(|label| label.chars().count())
+                    .max()
+                    .unwrap_or_default()
+                    .clamp(1, (width - 4) / 3);
+                self.infobox_label_width = Some(label_width);
+
+                let title = (element_type == "infobox_title").then_some(element);
+                self.push_infobox_border('┌', title, '┐');
+                if title.is_some() {
+                    return;
+                }
+                label_width
+            }
+        };
+
+        if element_type != "infobox" {
+            self.push_infobox_border('├', Some(element), '┤');
+            return;
+        }
+
+        let value_width = width - 5 - label_width;
+        let labels = wrap_text(
+            element.get_attribute("label").unwrap_or_default(),
+            label_width,
+        );
+        let values = wrap_text(element.content(), value_width);
+        for row in 0..labels.len().max(values.len()) {
+            let label = labels.get(row).map_or("", String::as_str);
+            let value = values.get(row).map_or("", String::as_str);
+            self.push_element(RenderedElement {
+                id: -1,
+                content: "│ ".to_string(),
+                style: border_style,
+                width: 2,
+            });
+            self.push_element(RenderedElement {
+                id: -1,
+                content: format!("{:<1$} ", label, label_width),
+                style: Style::from(CONFIG.theme.title).combine(Effect::Bold),
+                width: label_width + 1,
+            });
+            self.push_element(RenderedElement {
+                id: *element.id(),
+                content: format!("{:<1$}", value, value_width),
+                style: *element.style(),
+                width: value_width,
+            });
+            self.push_element(RenderedElement {
+                id: -1,
+                content: " │".to_string(),
+                style: border_style,
+                width: 2,
+            });
+            self.fill_line();
+            self.newline();
+        }
+    }
+
+    /// Adds the bottom border of the infobox that is being rendered as a new line
+    fn close_infobox(&mut self) {
+        self.push_infobox_border('└', None, '┘');
+        self.infobox_label_width = None;
+    }
+
+    /// Adds a horizontal border of an infobox as a new line. The content of an element, like the
+    /// title of the infobox, can be displayed in the border
+    fn push_infobox_border(&mut self, start: char, element: Option<&ArticleElement>, end: char) {
+        let width = self.width.saturating_sub(1).min(MAX_INFOBOX_WIDTH);
+        let border_style = Style::from(CONFIG.theme.text);
+
+        let mut start = start.to_string();
+        if let Some(element) = element {
+            start.push_str("─ ");
+            self.push_element(RenderedElement {
+                id: -1,
+                width: start.chars().count(),
+                content: start,
+                style: border_style,
+            });
+
+            // the title is cut off when it's too long
+            let available = width - 6;
+            let mut content = element
+                .content()
+                .chars()
+                .take(available)
+                .collect::<String>();
+            if element.content().chars().count() > available && content.pop().is_some() {
+                content.push('…');
+            }
+            self.push_element(RenderedElement {
+                id: *element.id(),
+                width: content.chars().count(),
+                content,
+                style: *element.style(),
+            });
+            start = " ".to_string();
+        }
+
+        let remaining = width - self.current_width - start.chars().count() - 1;
+        let border = format!("{}{}{}", start, "─".repeat(remaining), end);
+        self.push_element(RenderedElement {
+            id: -1,
+            width: border.chars().count(),
+            content: border,
+            style: border_style,
+        });
+        self.fill_line();
+        self.newline();
+    }
+
+    /// Adds the rows of an image as new lines
+    fn push_image(&mut self, rows: Vec<Line>) {
+        if !self.current_line.is_empty() {
+            self.fill_line();
+            self.newline();
+        }
+
+        for row in rows {
+            for element in row {
+                self.push_element(element);
+            }
+            self.fill_line();
+            self.newline();
+        }
+    }
+
     /// Adds the current line to the rendered lines and replaces it with a new, empty one
     fn newline(&mut self) {
+        // count the blank lines at the end, so headers know how much room there is above them
+        let is_blank = self
+            .current_line
+            .iter()
+            .all(|element| element.id == -1 && element.content.trim().is_empty());
+        self.blank_lines = if is_blank { self.blank_lines + 1 } else { 0 };
+
         // add the current line to the rendered lines
         self.rendered_lines.push(mem::take(&mut self.current_line));
 
@@ -369,3 +835,305 @@ impl LinesWrapper {
         });
     }
 }
+
+/// Helper function that checks if an element belongs to an infobox
+fn is_infobox_element(element: &ArticleElement) -> bool {
+    matches!(
+        element.get_attribute("type"),
+        Some("infobox" | "infobox_title" | "infobox_header")
+    )
+}
+
+/// Helper function that wraps a text into lines of a given width. Words that are too long for a
+/// line are split
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word = word.chars().collect::<Vec<char>>();
+        let line_width = line.chars().count();
+        if line_width > 0 && line_width + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(word);
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line.extend(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Helper function that renders an image with half blocks, every character displays two pixels
+/// on top of each other. The image is scaled down to the given width, keeping its aspect ratio
+fn render_image(image: &ArticleImage, id: i32, width: usize) -> Vec<Line> {
+    let columns = image.width().min(width);
+    if columns == 0 {
+        return Vec::new();
+    }
+
+    // a character is about twice as high as it's wide, so every row contains two pixels
+    let pixel_rows = image.height() * columns / image.width();
+    let rows = pixel_rows.div_ceil(2);
+    let source = |position: usize| position * image.width() / columns;
+    let color = |(r, g, b): (u8, u8, u8)| Color::Rgb(r, g, b);
+
+    (0..rows)
+        .map(|row| {
+            (0..columns)
+                .map(|column| {
+                    let top = image.pixel(source(column), source(row * 2));
+                    let bottom = image.pixel(source(column), source(row * 2 + 1));
+                    RenderedElement {
+                        id,
+                        content: "▀".to_string(),
+                        style: Style::from(ColorStyle::new(color(top), color(bottom))),
+                        width: 1,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        render_image, selected_text, wrap_text, Line, LinesWrapper, RenderedElement, Selection,
+    };
+    use crate::config::{LineSpacing, LinkDecoration};
+    use crate::wiki::article::{ArticleElement, ArticleImage};
+    use cursive::theme::{BaseColor, Color, ColorType, Effect, Style};
+    use cursive::Vec2;
+    use std::collections::HashSet;
+    use std::rc::Rc;
+
+    fn line(content: &str) -> Line {
+        vec![RenderedElement {
+            id: 0,
+            content: content.to_string(),
+            style: Style::none(),
+            width: content.chars().count(),
+        }]
+    }
+
+    #[test]
+    fn spacing() {
+        let elements = Rc::new(vec![
+            ArticleElement::new(0, 1, Style::none(), "a".to_string()),
+            ArticleElement::newline(1),
+            ArticleElement::new(2, 1, Style::none(), "H".to_string()).attribute("type", "header"),
+            ArticleElement::newline(3),
+        ]);
+        let lines = |spacing| {
+            LinesWrapper::new(10, elements.clone())
+                .spacing(spacing)
+                .wrap_lines()
+                .rendered_lines
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .map(|element| element.content.as_str())
+                        .collect::<String>()
+                        .trim()
+                        .to_string()
+                })
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(lines(LineSpacing::COMPACT), vec!["a", "H"]);
+        assert_eq!(lines(LineSpacing::NORMAL), vec!["a", "", "H", ""]);
+        assert_eq!(lines(LineSpacing::SPACIOUS), vec!["a", "", "", "H", "", ""]);
+
+        // the more spacious the spacing, the more room there is above a header
+        let blank_lines = |spacing| {
+            lines(spacing)
+                .iter()
+                .take_while(|line| line.as_str() != "H")
+                .filter(|line| line.is_empty())
+                .count()
+        };
+        assert!(blank_lines(LineSpacing::COMPACT) <= blank_lines(LineSpacing::NORMAL));
+        assert!(blank_lines(LineSpacing::NORMAL) <= blank_lines(LineSpacing::SPACIOUS));
+        assert!(LineSpacing::COMPACT.header_lines() <= LineSpacing::NORMAL.header_lines());
+        assert!(LineSpacing::NORMAL.header_lines() <= LineSpacing::SPACIOUS.header_lines());
+    }
+
+    /// Helper function that renders some elements and returns the text of every line
+    fn rendered_text(width: usize, elements: Vec<ArticleElement>) -> Vec<String> {
+        LinesWrapper::new(width, Rc::new(elements))
+            .wrap_lines()
+            .rendered_lines
+            .iter()
+            .map(|line| {
+                line.iter()
+                    .map(|element| element.content.as_str())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn code_blocks() {
+        let code = |id, content: &str| {
+            ArticleElement::new(
+                id,
+                content.chars().count(),
+                Style::none(),
+                content.to_string(),
+            )
+            .attribute("type", "code")
+        };
+        assert_eq!(
+            rendered_text(
+                12,
+                vec![code(0, "fn main() {"), code(1, "  x"), code(2, "}")]
+            ),
+            vec!["    fn mai…", "      x", "    }"]
+        );
+    }
+
+    #[test]
+    fn quotes() {
+        let elements = vec![
+            ArticleElement::new(0, 9, Style::none(), "to be or not".to_string())
+                .attribute("quote", "true"),
+            ArticleElement::newline(1),
+        ];
+        assert_eq!(rendered_text(10, elements), vec!["│ to be or", "│ not", ""]);
+    }
+
+    #[test]
+    fn infoboxes() {
+        let infobox = |id, content: &str, element_type| {
+            ArticleElement::new(
+                id,
+                content.chars().count(),
+                Style::none(),
+                content.to_string(),
+            )
+            .attribute("type", element_type)
+        };
+        let elements = vec![
+            infobox(0, "GitHub", "infobox_title"),
+            infobox(1, "February 8, 2008", "infobox").attribute("label", "Founded"),
+            infobox(2, "Ownership", "infobox_header"),
+            infobox(3, "Microsoft", "infobox").attribute("label", "Parent"),
+            ArticleElement::newline(4),
+        ];
+
+        assert_eq!(
+            rendered_text(25, elements),
+            vec![
+                "┌─ GitHub ─────────────┐",
+                "│ Founde February 8,   │",
+                "│ d      2008          │",
+                "├─ Ownership ──────────┤",
+                "│ Parent Microsoft     │",
+                "└──────────────────────┘",
+                "",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn link_decorations() {
+        let elements = vec![
+            ArticleElement::new(0, 4, Style::none(), "Rust".to_string()),
+            ArticleElement::new(1, 8, Style::from(Effect::Underline), "compiler".to_string())
+                .attribute("type", "link")
+                .attribute("target", "/wiki/Compiler"),
+            ArticleElement::newline(2),
+        ];
+        let wrapper = LinesWrapper::new(20, Rc::new(elements))
+            .links(
+                LinkDecoration {
+                    underline: false,
+                    brackets: true,
+                    color: true,
+                },
+                Color::Dark(BaseColor::Red),
+            )
+            .wrap_lines();
+
+        let link = &wrapper.rendered_lines[0][2];
+        assert_eq!(link.content, "[compiler]");
+        assert!(!link.style.effects.contains(Effect::Underline));
+        assert_eq!(
+            link.style.color.front,
+            ColorType::Color(Color::Dark(BaseColor::Red))
+        );
+    }
+
+    #[test]
+    fn visited_links() {
+        let elements = vec![
+            ArticleElement::new(0, 4, Style::from(Effect::Underline), "Rust".to_string())
+                .attribute("type", "link")
+                .attribute("target", "/wiki/Rust"),
+            ArticleElement::new(1, 8, Style::from(Effect::Underline), "compiler".to_string())
+                .attribute("type", "link")
+                .attribute("target", "/wiki/Compiler"),
+            ArticleElement::newline(2),
+        ];
+        let wrapper = LinesWrapper::new(20, Rc::new(elements))
+            .visited_links(HashSet::from([1]), Color::Light(BaseColor::Black))
+            .wrap_lines();
+
+        let line = &wrapper.rendered_lines[0];
+        assert_eq!(line[0].style.color.front, ColorType::InheritParent);
+        assert_eq!(line[2].content, "compiler");
+        assert_eq!(
+            line[2].style.color.front,
+            ColorType::Color(Color::Light(BaseColor::Black))
+        );
+        assert!(line[2].style.effects.contains(Effect::Underline));
+    }
+
+    #[test]
+    fn wrapped_text() {
+        assert_eq!(wrap_text("to be or not", 6), vec!["to be", "or not"]);
+        assert_eq!(wrap_text("infobox", 3), vec!["inf", "obo", "x"]);
+        assert!(wrap_text(" ", 3).is_empty());
+    }
+
+    #[test]
+    fn selections() {
+        let lines = vec![line("Rust is a "), line("programming language  "), line("")];
+
+        // the cursor can be before the anchor
+        let mut selection = Selection::new(Vec2::new(10, 1));
+        selection.set_cursor(Vec2::new(5, 0));
+        assert_eq!(selection.bounds(), (Vec2::new(5, 0), Vec2::new(10, 1)));
+        assert!(selection.contains(Vec2::new(0, 1)));
+        assert!(!selection.contains(Vec2::new(11, 1)));
+        assert_eq!(selected_text(&lines, &selection), "is a\nprogramming");
+
+        selection.set_cursor(Vec2::new(0, 2));
+        assert_eq!(selected_text(&lines, &selection), "g language\n");
+    }
+
+    #[test]
+    fn render_scaled_image() {
+        let image = ArticleImage::new(4, 4, vec![(0, 0, 0); 16]).unwrap();
+
+        let rows = render_image(&image, 1, 2);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 2);
+
+        let rows = render_image(&image, 1, 10);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].len(), 4);
+    }
+}
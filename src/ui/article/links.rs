@@ -1,4 +1,5 @@
 use cursive::Vec2;
+use std::ops::Range;
 
 /// A struct handling link selection
 pub struct LinkHandler {
@@ -7,15 +8,24 @@ pub struct LinkHandler {
 
     /// The index of the current link
     current_link: usize,
+
+    /// Does the selection wrap around when moving past the first or the last link?
+    wrap: bool,
+
+    /// Every link of the article, once only the links of some lines are registered
+    article_links: Option<Vec<Link>>,
 }
 
 impl LinkHandler {
-    /// Creates a new link handler
-    pub fn new() -> Self {
+    /// Creates a new link handler. If wrap is true, moving past the first or the last link
+    /// continues at the other end
+    pub fn new(wrap: bool) -> Self {
         log::debug!("creating a new instance of LinkHandler");
         Self {
             links: Vec::new(),
             current_link: 0,
+            wrap,
+            article_links: None,
         }
     }
 
@@ -24,6 +34,63 @@ impl LinkHandler {
         self.links.len()
     }
 
+    /// Returns the number of links in the article, including the ones that aren't registered
+    pub fn total_links(&self) -> usize {
+        self.article_links
+            .as_ref()
+            .map_or(self.links.len(), Vec::len)
+    }
+
+    /// Registers only the links in the given lines, the other ones are kept so they can be
+    /// registered later. The current link stays selected if it's in the lines, otherwise the first
+    /// link in them is selected
+    pub fn register_lines(&mut self, lines: Range<usize>) {
+        let current_link = self.get_current_link();
+        if self.article_links.is_none() {
+            self.article_links = Some(std::mem::take(&mut self.links));
+        }
+
+        self.links = self
+            .article_links
+            .iter()
+            .flatten()
+            .filter(|link| lines.contains(&link.y))
+            .cloned()
+            .collect();
+        self.current_link = current_link
+            .and_then(|id| self.links.iter().position(|link| link.id == id))
+            .unwrap_or(0);
+    }
+
+    /// Returns the line of the closest link below or above the given lines, if only the links in
+    /// them are registered and the current link is the last or the first of them. Moving the
+    /// selection further has to continue in that line
+    pub fn next_unregistered_line(&self, lines: &Range<usize>, forward: bool) -> Option<usize> {
+        let article_links = self.article_links.as_ref()?;
+        let is_at_edge = self.links.is_empty()
+            || if forward {
+                self.current_link + 1 >= self.links.len()
+            } else {
+                self.current_link == 0
+            };
+        if !is_at_edge {
+            return None;
+        }
+
+        if forward {
+            article_links
+                .iter()
+                .map(|link| link.y)
+                .find(|y| *y >= lines.end)
+        } else {
+            article_links
+                .iter()
+                .rev()
+                .map(|link| link.y)
+                .find(|y| *y < lines.start)
+        }
+    }
+
     /// Adds a new link with the given id and position
     /// It is required to add the links from left to right and top to bottom in order for the
     /// selection to work
@@ -48,21 +115,66 @@ impl LinkHandler {
         Some(Vec2::new(link.x, link.y))
     }
 
-    /// Moves the selection up by a given amount
+    /// Iterate over the ids of every registered link in the order they were added
+    pub fn link_ids(&self) -> impl Iterator<Item = i32> + '_ {
+        self.links.iter().map(|link| link.id)
+    }
+
+    /// Iterate over the positions of every registered link in the order they were added
+    pub fn link_positions(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.links.iter().map(|link| Vec2::new(link.x, link.y))
+    }
+
+    /// Moves the selection up by a given amount. On the new line, the link closest to the
+    /// x-coordinate of the current link is selected
     pub fn move_up(&mut self, amount: usize) {
+        self.move_up_with(amount, self.wrap)
+    }
+
+    /// Moves the selection up by a given amount without wrapping around
+    pub fn move_up_without_wrap(&mut self, amount: usize) {
+        self.move_up_with(amount, false)
+    }
+
+    /// Moves the selection down by a given amount. On the new line, the link closest to the
+    /// x-coordinate of the current link is selected
+    pub fn move_down(&mut self, amount: usize) {
+        self.move_down_with(amount, self.wrap)
+    }
+
+    /// Moves the selection down by a given amount without wrapping around
+    pub fn move_down_without_wrap(&mut self, amount: usize) {
+        self.move_down_with(amount, false)
+    }
+
+    /// Moves the selection up by a given amount. When wrap is true and the current link is on the
+    /// first line, the closest link on the last line is selected
+    fn move_up_with(&mut self, amount: usize, wrap: bool) {
         if self.links.is_empty() {
             log::warn!("no links are registered, aborting...");
             return;
         }
 
-        // save the minimum y-position
-        let min_y = self.links[self.current_link].y.saturating_sub(amount);
+        if wrap && self.links[self.current_link].y == self.links[0].y {
+            log::debug!("wrapping around to the last line");
+            self.current_link = self.closest_on_line(self.links.len() - 1);
+            return;
+        }
+
+        // save the minimum y-position, if there is none we're already on the first line
+        let min_y = match self.links[self.current_link].y.checked_sub(amount) {
+            Some(min_y) => min_y,
+            None => {
+                self.current_link = 0;
+                return;
+            }
+        };
 
         // go through every link above the current one
         for i in (0..self.current_link).rev() {
-            // if the link has the right y-position, save it as the new current link and return
+            // if the link has the right y-position, select the closest link on its line and return
             if self.links[i].y <= min_y {
-                self.current_link = i;
+                self.current_link = self.closest_on_line(i);
                 return;
             }
         }
@@ -71,21 +183,29 @@ impl LinkHandler {
         self.current_link = 0;
     }
 
-    /// Moves the selection down by a given amount
-    pub fn move_down(&mut self, amount: usize) {
+    /// Moves the selection down by a given amount. When wrap is true and the current link is on
+    /// the last line, the closest link on the first line is selected
+    fn move_down_with(&mut self, amount: usize, wrap: bool) {
         if self.links.is_empty() {
             log::warn!("no links are registered, aborting...");
             return;
         }
 
+        let last_link = self.links.len() - 1;
+        if wrap && self.links[self.current_link].y == self.links[last_link].y {
+            log::debug!("wrapping around to the first line");
+            self.current_link = self.closest_on_line(0);
+            return;
+        }
+
         // save the minimum y-position
         let min_y = self.links[self.current_link].y.saturating_add(amount);
 
         // go through every link below the current one
         for i in self.current_link..self.links.len() {
-            // if the link has the right y-position, save it as the new current link and return
+            // if the link has the right y-position, select the closest link on its line and return
             if self.links[i].y >= min_y {
-                self.current_link = i;
+                self.current_link = self.closest_on_line(i);
                 return;
             }
         }
@@ -94,6 +214,58 @@ impl LinkHandler {
         self.current_link = self.links.len().saturating_sub(1);
     }
 
+    /// Returns the index of the link that is on the same line as the given one and closest to the
+    /// x-coordinate of the current link
+    fn closest_on_line(&self, index: usize) -> usize {
+        let x = self.links[self.current_link].x;
+        let (start, end) = self.line_bounds(index);
+
+        (start..end)
+            .min_by_key(|&i| (self.links[i].x as isize - x as isize).abs())
+            .unwrap_or(index)
+    }
+
+    /// Returns the range of indices of the links that are on the same line as the given one. The
+    /// end is exclusive
+    fn line_bounds(&self, index: usize) -> (usize, usize) {
+        let y = self.links[index].y;
+
+        // links are sorted by their position, so every link on that line is next to each other
+        let start = self.links[..index]
+            .iter()
+            .rposition(|link| link.y != y)
+            .map_or(0, |i| i + 1);
+        let end = self.links[index..]
+            .iter()
+            .position(|link| link.y != y)
+            .map_or(self.links.len(), |i| i + index);
+        (start, end)
+    }
+
+    /// Moves the selection left by a given amount, staying on the line of the current link. When
+    /// there are no more links on the left, the first link of the line is selected
+    pub fn move_left_on_line(&mut self, amount: usize) {
+        if self.links.is_empty() {
+            log::warn!("no links are registered, aborting...");
+            return;
+        }
+
+        let (start, _) = self.line_bounds(self.current_link);
+        self.current_link = self.current_link.saturating_sub(amount).max(start);
+    }
+
+    /// Moves the selection right by a given amount, staying on the line of the current link. When
+    /// there are no more links on the right, the last link of the line is selected
+    pub fn move_right_on_line(&mut self, amount: usize) {
+        if self.links.is_empty() {
+            log::warn!("no links are registered, aborting...");
+            return;
+        }
+
+        let (_, end) = self.line_bounds(self.current_link);
+        self.current_link = self.current_link.saturating_add(amount).min(end - 1);
+    }
+
     /// Moves the selection left by a given amount
     pub fn move_left(&mut self, amount: usize) {
         if self.links.is_empty() {
@@ -101,6 +273,12 @@ impl LinkHandler {
             return;
         }
 
+        if self.wrap {
+            let links_len = self.links.len();
+            self.current_link = (self.current_link + links_len - amount % links_len) % links_len;
+            return;
+        }
+
         self.current_link = self.current_link.saturating_sub(amount);
     }
 
@@ -111,6 +289,11 @@ impl LinkHandler {
             return;
         }
 
+        if self.wrap {
+            self.current_link = (self.current_link + amount) % self.links.len();
+            return;
+        }
+
         // if we don't have enough links on the right, just select the last one
         if self.current_link + amount >= self.links.len() {
             self.current_link = self.links.len().saturating_sub(1);
@@ -139,10 +322,43 @@ impl LinkHandler {
         );
         self.current_link = new_selection as usize;
     }
+
+    /// Overrides the current link with the link at the given index. Links are indexed in the order
+    /// they were added
+    pub fn set_current_link_by_index(&mut self, index: usize) {
+        if index >= self.links.len() {
+            log::warn!(
+                "the link index '{}' is out of bounds, links_len: '{}'",
+                index,
+                self.links.len()
+            );
+            return;
+        }
+
+        log::info!(
+            "replacing the current link '{}', with '{}'",
+            self.current_link,
+            index
+        );
+        self.current_link = index;
+    }
+
+    /// Selects the first link in the given lines. If there is no link in them, the selection stays
+    /// the same and false is returned
+    pub fn select_first_link_in(&mut self, lines: Range<usize>) -> bool {
+        match self.links.iter().position(|link| lines.contains(&link.y)) {
+            Some(index) => {
+                self.current_link = index;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 /// A struct representing a Link. It contains an id to reference it to an ArticleElement and
 /// relative x and y coordinates
+#[derive(Clone)]
 struct Link {
     /// The id of the Link. This is also used to reference it to an ArticleElement
     id: i32,
@@ -152,3 +368,213 @@ struct Link {
     /// The relative y coordinate of the Link
     y: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::LinkHandler;
+
+    /// Creates a link handler with the following links (id: x)
+    /// 0: 0   1: 20   2: 40
+    /// 3: 5            4: 38
+    ///            5: 30
+    fn link_handler(wrap: bool) -> LinkHandler {
+        let mut link_handler = LinkHandler::new(wrap);
+        link_handler.push_link(0, 0, 0);
+        link_handler.push_link(1, 20, 0);
+        link_handler.push_link(2, 40, 0);
+        link_handler.push_link(3, 5, 1);
+        link_handler.push_link(4, 38, 1);
+        link_handler.push_link(5, 30, 2);
+        link_handler
+    }
+
+    #[test]
+    fn move_down_closest() {
+        let mut link_handler = link_handler(false);
+
+        link_handler.set_current_link(2);
+        link_handler.move_down(1);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+
+        link_handler.set_current_link(1);
+        link_handler.move_down(1);
+        assert_eq!(link_handler.get_current_link(), Some(3));
+
+        link_handler.move_down(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+
+        link_handler.move_down(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+    }
+
+    #[test]
+    fn move_up_closest() {
+        let mut link_handler = link_handler(false);
+
+        link_handler.set_current_link(4);
+        link_handler.move_up(1);
+        assert_eq!(link_handler.get_current_link(), Some(2));
+
+        link_handler.set_current_link(5);
+        link_handler.move_up(1);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+
+        link_handler.set_current_link(5);
+        link_handler.move_up(2);
+        assert_eq!(link_handler.get_current_link(), Some(1));
+
+        link_handler.move_up(1);
+        assert_eq!(link_handler.get_current_link(), Some(0));
+    }
+
+    #[test]
+    fn set_current_link_by_index() {
+        let mut link_handler = link_handler(false);
+
+        link_handler.set_current_link_by_index(4);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+
+        link_handler.set_current_link_by_index(6);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+    }
+
+    #[test]
+    fn move_left_right_wrap() {
+        let mut link_handler = link_handler(true);
+
+        link_handler.move_left(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+
+        link_handler.move_right(1);
+        assert_eq!(link_handler.get_current_link(), Some(0));
+
+        link_handler.move_right(8);
+        assert_eq!(link_handler.get_current_link(), Some(2));
+    }
+
+    #[test]
+    fn move_up_down_wrap() {
+        let mut link_handler = link_handler(true);
+
+        link_handler.set_current_link(2);
+        link_handler.move_up(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+
+        link_handler.move_down(1);
+        assert_eq!(link_handler.get_current_link(), Some(1));
+
+        link_handler.set_current_link(5);
+        link_handler.move_down_without_wrap(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+    }
+
+    #[test]
+    fn select_first_link_in() {
+        let mut link_handler = link_handler(false);
+
+        assert!(link_handler.select_first_link_in(1..3));
+        assert_eq!(link_handler.get_current_link(), Some(3));
+
+        assert!(!link_handler.select_first_link_in(3..5));
+        assert_eq!(link_handler.get_current_link(), Some(3));
+    }
+
+    #[test]
+    fn move_without_wrap() {
+        let mut link_handler = link_handler(false);
+
+        link_handler.move_left(1);
+        assert_eq!(link_handler.get_current_link(), Some(0));
+
+        link_handler.set_current_link(5);
+        link_handler.move_right(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+    }
+
+    /// Creates a link handler with the following links (id: x)
+    /// 0: 0   1: 10   2: 20   3: 30
+    /// 4: 5   5: 15
+    /// 6: 0   7: 10   8: 20
+    fn multiline_link_handler() -> LinkHandler {
+        let mut link_handler = LinkHandler::new(false);
+        link_handler.push_link(0, 0, 0);
+        link_handler.push_link(1, 10, 0);
+        link_handler.push_link(2, 20, 0);
+        link_handler.push_link(3, 30, 0);
+        link_handler.push_link(4, 5, 1);
+        link_handler.push_link(5, 15, 1);
+        link_handler.push_link(6, 0, 2);
+        link_handler.push_link(7, 10, 2);
+        link_handler.push_link(8, 20, 2);
+        link_handler
+    }
+
+    #[test]
+    fn move_right_on_line() {
+        let mut link_handler = multiline_link_handler();
+
+        link_handler.move_right_on_line(2);
+        assert_eq!(link_handler.get_current_link(), Some(2));
+
+        link_handler.move_right_on_line(5);
+        assert_eq!(link_handler.get_current_link(), Some(3));
+
+        link_handler.set_current_link(4);
+        link_handler.move_right_on_line(1);
+        link_handler.move_right_on_line(1);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+
+        link_handler.set_current_link(8);
+        link_handler.move_right_on_line(1);
+        assert_eq!(link_handler.get_current_link(), Some(8));
+    }
+
+    #[test]
+    fn move_left_on_line() {
+        let mut link_handler = multiline_link_handler();
+
+        link_handler.move_left_on_line(1);
+        assert_eq!(link_handler.get_current_link(), Some(0));
+
+        link_handler.set_current_link(5);
+        link_handler.move_left_on_line(1);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+        link_handler.move_left_on_line(1);
+        assert_eq!(link_handler.get_current_link(), Some(4));
+
+        link_handler.set_current_link(8);
+        link_handler.move_left_on_line(10);
+        assert_eq!(link_handler.get_current_link(), Some(6));
+    }
+
+    #[test]
+    fn register_lines() {
+        let mut link_handler = multiline_link_handler();
+        link_handler.set_current_link(5);
+
+        link_handler.register_lines(1..3);
+        assert_eq!(link_handler.registered_links(), 5);
+        assert_eq!(link_handler.total_links(), 9);
+        assert_eq!(link_handler.get_current_link(), Some(5));
+        assert_eq!(link_handler.next_unregistered_line(&(1..3), true), None);
+
+        link_handler.move_right(1);
+        assert_eq!(link_handler.next_unregistered_line(&(1..3), true), None);
+        link_handler.set_current_link(4);
+        assert_eq!(link_handler.next_unregistered_line(&(1..3), false), Some(0));
+
+        // the current link isn't in the lines anymore, the first one in them is selected
+        link_handler.register_lines(0..1);
+        assert_eq!(link_handler.registered_links(), 4);
+        assert_eq!(link_handler.get_current_link(), Some(0));
+        link_handler.set_current_link(3);
+        assert_eq!(link_handler.next_unregistered_line(&(0..1), true), Some(1));
+        assert_eq!(link_handler.next_unregistered_line(&(0..1), false), None);
+
+        // without limiting the registered links, every link can be reached
+        assert_eq!(
+            multiline_link_handler().next_unregistered_line(&(0..1), true),
+            None
+        );
+    }
+}
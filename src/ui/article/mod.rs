@@ -1,85 +1,559 @@
-use crate::ui::utils::remove_view_from_layout;
+use crate::ui::utils::{
+    hide_loading_view, remove_view_from_layout, show_error, show_error_with_retry,
+    show_loading_view,
+};
 use crate::wiki::{
-    article::{parser::DefaultParser, Article, ArticleBuilder},
-    search::SearchResult,
+    article::{
+        cache::{CacheBackend, DiskBackend, MemoryBackend},
+        parser::{DefaultParser, SourceFormat},
+        wikitext::WikitextParser,
+        Article, ArticleBuilder, ArticleCache, ArticleImage,
+    },
+    error::WikiError,
+    random, request,
+    search::{SearchBuilder, SearchProperties, SearchResult},
 };
 use crate::{
-    config::{self, TocPosition, CONFIG},
-    ui::{self, RootLayout},
+    config::{
+        self, CacheStorage, ConfirmButton, ExportFormat, LinkOpenMode, NamespaceBehavior,
+        ParserType, TocPosition, CONFIG,
+    },
+    ui::{
+        self,
+        messages::{format_date, format_number, t, t_args},
+        status::{StatusContext, StatusTracker},
+        tabs::TabView,
+        RootLayout,
+    },
     view_with_theme,
 };
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use arboard::Clipboard;
 use cursive::align::HAlign;
 use cursive::direction::Orientation;
-use cursive::view::{Nameable, Scrollable};
-use cursive::views::{Dialog, TextView};
-use cursive::Cursive;
+use cursive::theme::{Effect, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::{Finder, Nameable, Resizable, Scrollable, View};
+use cursive::views::{Dialog, DialogFocus, EditView, NamedView, ScrollView, SelectView, TextView};
+use cursive::{CbSink, Cursive};
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
 
 mod content;
 mod lines;
 mod links;
 mod view;
 pub type ArticleView = view::ArticleView;
+/// The scroll view around the article view of a tab, named 'article_scroll'
+type ArticleScrollView = ScrollView<StatusTracker<NamedView<ArticleView>>>;
+
+/// How long the selection has to stay on a link before its preview is fetched
+const LINK_PREVIEW_DELAY: Duration = Duration::from_millis(500);
+
+/// Identifies the latest requested link preview. Previews of older requests are discarded
+static LINK_PREVIEW_REQUEST: AtomicUsize = AtomicUsize::new(0);
+
+/// Identifies the latest article request. Articles of cancelled or older requests are discarded
+static ARTICLE_REQUEST: AtomicUsize = AtomicUsize::new(0);
+
+/// How many threads are prefetching articles right now
+static PREFETCH_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The clipboard, it's only opened when something is copied
+    static CLIPBOARD: RefCell<Option<Clipboard>> = const { RefCell::new(None) };
+}
+
+lazy_static! {
+    /// The articles of links waiting to be prefetched
+    static ref PREFETCH_QUEUE: Mutex<VecDeque<ArticleBuilder>> = Mutex::new(VecDeque::new());
+    /// The articles cached during this session, when they aren't stored on disk
+    static ref MEMORY_CACHE: Arc<MemoryBackend> = Arc::new(MemoryBackend::new());
+}
+
+/// Where the reader was in an article, restored when going back to it
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ArticlePosition {
+    /// The scroll offset of the article view
+    pub offset: usize,
+    /// The id of the selected link, if there is one
+    pub link: Option<i32>,
+}
+
+/// A browsing history of displayed articles with a cursor pointing at the current one
+pub struct History {
+    /// The articles in the order they were visited, with the position they were left at
+    articles: Vec<(Article, Option<ArticlePosition>)>,
+    /// The index of the current article
+    cursor: usize,
+}
+
+impl History {
+    /// Creates a new, empty History
+    pub fn new() -> Self {
+        History {
+            articles: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    /// Adds an article after the current one. Any articles after the current one are discarded
+    pub fn push(&mut self, article: Article) {
+        if !self.articles.is_empty() {
+            self.articles.truncate(self.cursor + 1);
+        }
+        self.articles.push((article, None));
+        self.cursor = self.articles.len() - 1;
+    }
+
+    /// Returns the current article, if there is one
+    pub fn current(&self) -> Option<&Article> {
+        self.articles.get(self.cursor).map(|(article, _)| article)
+    }
+
+    /// Returns the position the current article was left at, if it has been left before
+    pub fn position(&self) -> Option<ArticlePosition> {
+        self.articles
+            .get(self.cursor)
+            .and_then(|(_, position)| *position)
+    }
+
+    /// Remembers the position of the current article, so it can be restored when going back to it
+    pub fn set_position(&mut self, position: ArticlePosition) {
+        if let Some((_, current_position)) = self.articles.get_mut(self.cursor) {
+            *current_position = Some(position);
+        }
+    }
+
+    /// Adds downloaded images to every article that is the given one
+    pub fn add_images(&mut self, article: &Article, images: &[(i32, ArticleImage)]) {
+        for (entry, _) in self.articles.iter_mut() {
+            if entry != article {
+                continue;
+            }
+            for (id, image) in images {
+                entry.set_image(*id, image.clone());
+            }
+        }
+    }
+
+    /// Replaces the current article, like with a newer version of it. Its position is kept
+    pub fn replace_current(&mut self, article: Article) {
+        if let Some((current, _)) = self.articles.get_mut(self.cursor) {
+            *current = article;
+        }
+    }
 
-/// Fetches an article from a given SearchResult and displays it. It's the on_submit callback for
-/// the search results view
+    /// Moves the cursor back by one and returns that article. If there is no previous article,
+    /// None is returned
+    pub fn back(&mut self) -> Option<&Article> {
+        if self.cursor == 0 {
+            return None;
+        }
+        self.cursor -= 1;
+        self.current()
+    }
+
+    /// Moves the cursor forward by one and returns that article. If there is no next article,
+    /// None is returned
+    pub fn forward(&mut self) -> Option<&Article> {
+        if self.cursor + 1 >= self.articles.len() {
+            return None;
+        }
+        self.cursor += 1;
+        self.current()
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the default ArticleBuilder for the given page id or link of the wikipedia at base_url
+fn build_article(base_url: &str, page_id: i32, target: Option<String>) -> ArticleBuilder {
+    let builder = ArticleBuilder::new(page_id, target, base_url)
+        .script_path(&ui::wikis::find_wiki(base_url).script_path)
+        .timeout(Duration::from_millis(CONFIG.api_config.timeout_ms))
+        .max_retries(CONFIG.api_config.max_retries)
+        .user_agent(&CONFIG.api_config.user_agent)
+        .images(CONFIG.features.images)
+        .offline(CONFIG.features.offline);
+
+    // only use the cache if it's enabled and we know where to put it. Offline, the cache is the
+    // only place articles can come from
+    match article_cache() {
+        Some(cache) if CONFIG.settings.cache.enabled || CONFIG.features.offline => {
+            builder.cache(cache)
+        }
+        _ => builder,
+    }
+}
+
+/// Returns the configured ArticleCache, if we know where to put it
+pub fn article_cache() -> Option<ArticleCache> {
+    let backend: Arc<dyn CacheBackend> = match CONFIG.settings.cache.storage {
+        CacheStorage::DISK => Arc::new(DiskBackend::new(
+            CONFIG.settings.cache.path.as_ref()?.join("articles"),
+        )),
+        CacheStorage::MEMORY => MEMORY_CACHE.clone(),
+    };
+    Some(
+        ArticleCache::with_backend(backend, Duration::from_secs(CONFIG.settings.cache.ttl * 60))
+            .max_entries(CONFIG.settings.cache.max_entries),
+    )
+}
+
+/// Helper function that fetches and parses an article with the configured parser
+fn parse_article(builder: &ArticleBuilder, title: &str) -> Result<Article> {
+    match CONFIG.settings.parser {
+        ParserType::HTML => builder.build(&mut DefaultParser::new(&CONFIG.settings.toc)),
+        ParserType::WIKITEXT => builder.build(&mut WikitextParser::new(
+            &CONFIG.settings.toc,
+            title.to_string(),
+        )),
+    }
+}
+
+/// Fetches the article with a given page id of the wikipedia at base_url and returns it as plain
+/// text, without displaying anything. It's used for printing articles with the --print argument
+pub fn article_text(base_url: &str, page_id: i32, title: &str) -> Result<String> {
+    log::info!("fetching the text of the article '{}'", title);
+    let article = parse_article(&build_article(base_url, page_id, None), title)?;
+    Ok(content::ArticleContent::new(article).export(ExportFormat::TEXT))
+}
+
+/// Fetches an article from a given SearchResult of the current wiki and displays it. It's the
+/// on_submit callback for the live search results
 pub fn on_article_submit(siv: &mut Cursive, search_result: &SearchResult) {
     log::info!("on_article_submit was called");
+    open_search_result(siv, &ui::wikis::current_wiki().base_url, search_result);
+    log::info!("on_article_submit finished successfully");
+}
 
-    // fetch the article
+/// Fetches the article of a search result from the wiki at base_url and displays it
+pub fn open_search_result(siv: &mut Cursive, base_url: &str, search_result: &SearchResult) {
     log::info!(
         "fetching the article '{}' with the id '{}'",
         search_result.title(),
         search_result.page_id()
     );
-    let article = match ArticleBuilder::new(
-        *search_result.page_id(),
-        None,
-        &CONFIG.api_config.base_url,
-    )
-    .build(&mut DefaultParser::new(&CONFIG.settings.toc))
-    {
+    let title = if search_result.title().is_empty() {
+        search_result.page_id().to_string()
+    } else {
+        search_result.title().to_string()
+    };
+    open_article(siv, base_url, *search_result.page_id(), title);
+}
+
+/// Fetches the article with a given id and title from the wikipedia at base_url and displays it
+pub fn open_article(siv: &mut Cursive, base_url: &str, page_id: i32, title: String) {
+    fetch_article(
+        siv,
+        build_article(base_url, page_id, None),
+        title,
+        LinkOpenMode::REPLACE,
+    );
+}
+
+/// Fetches a random article from the current wiki and displays it. It's the callback for the
+/// random_article keybinding
+pub fn on_random_article(siv: &mut Cursive) {
+    log::info!("on_random_article was called");
+
+    if CONFIG.features.offline {
+        show_error(
+            siv,
+            &t("error.finding_random_article"),
+            &anyhow!(WikiError::Offline),
+        );
+        return;
+    }
+
+    let request = ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+
+    show_loading_view(siv, t("article.random_loading"), |_| {
+        log::info!("cancelling the random article request");
+        ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let wiki = ui::wikis::current_wiki();
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let page = random::random_article(
+            &wiki.base_url,
+            &wiki.script_path,
+            Duration::from_millis(CONFIG.api_config.timeout_ms),
+            CONFIG.api_config.max_retries,
+            &CONFIG.api_config.user_agent,
+        );
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            if ARTICLE_REQUEST.load(Ordering::SeqCst) != request {
+                log::debug!("discarding the random article of the cancelled request");
+                return;
+            }
+            hide_loading_view(s);
+
+            match page {
+                Ok((page_id, title)) => open_article(s, &wiki.base_url, page_id, title),
+                Err(error) => {
+                    log::warn!("{:?}", error);
+                    show_error_with_retry(
+                        s,
+                        &t("error.finding_random_article"),
+                        &error,
+                        on_random_article,
+                    );
+                }
+            }
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+
+    log::info!("on_random_article finished successfully");
+}
+
+/// Fetches only a section of the displayed article and displays it as its own article. The lead of
+/// the article is the section 0
+pub fn open_section(siv: &mut Cursive, section: u32, title: String) {
+    log::info!("open_section was called with the section '{}'", section);
+
+    let current = ui::tabs::with_history(siv, |history| {
+        history.current().map(|article| {
+            (
+                article
+                    .base_url()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| ui::wikis::current_wiki().base_url),
+                article.page_id().unwrap_or_default(),
+            )
+        })
+    });
+    let (base_url, page_id) = match current.flatten() {
+        Some(current) => current,
+        None => {
+            log::warn!("there is no article to open the section of");
+            return;
+        }
+    };
+    if page_id == 0 {
+        log::warn!("the id of the article is unknown, its sections can't be fetched");
+        return;
+    }
+
+    fetch_article(
+        siv,
+        build_article(&base_url, page_id, None).section(Some(section)),
+        title,
+        LinkOpenMode::REPLACE,
+    );
+}
+
+/// Helper function that fetches an article on a background thread and displays it when it's
+/// done, the way a given mode says. In the meantime, a loading layer is shown which can be
+/// cancelled with Esc
+fn fetch_article(siv: &mut Cursive, builder: ArticleBuilder, title: String, mode: LinkOpenMode) {
+    log::debug!("fetch_article was called");
+
+    // articles opened in the background don't interrupt the reading with a loading layer
+    if mode == LinkOpenMode::BACKGROUND {
+        fetch_in_background(siv, builder, title);
+        return;
+    }
+
+    let request = ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+
+    show_loading_view(siv, t_args("article.loading", &[&title]), |_| {
+        log::info!("cancelling the article request");
+        ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let article = parse_article(&builder, &title);
+        let displayed = article.as_ref().ok().cloned();
+        let image_builder = builder.clone();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            on_article_fetched(s, request, article, builder, title, mode)
+        })) {
+            log::warn!("{:?}", error);
+        }
+        if let Some(article) = displayed {
+            fetch_images(&cb_sink, &image_builder, article);
+        }
+    });
+
+    log::debug!("fetch_article finished successfully");
+}
+
+/// Helper function that fetches an article on a background thread and opens it in a tab behind
+/// the active one when it's done. The status bar tells the user when it's queued and when it's
+/// opened. Other requests don't cancel it
+fn fetch_in_background(siv: &mut Cursive, builder: ArticleBuilder, title: String) {
+    log::info!("opening '{}' in the background", title);
+    ui::status::display_message(siv, t_args("article.background_loading", &[&title]));
+
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let article = parse_article(&builder, &title);
+        let displayed = article.as_ref().ok().cloned();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            let result =
+                article.and_then(|article| display_article(s, article, LinkOpenMode::BACKGROUND));
+            match result {
+                Ok(()) => {
+                    ui::status::display_message(s, t_args("article.background_opened", &[&title]))
+                }
+                Err(error) => {
+                    log::warn!("{:?}", error);
+                    show_error(s, &t("error.fetching_article"), &error);
+                }
+            }
+        })) {
+            log::warn!("{:?}", error);
+        }
+        if let Some(article) = displayed {
+            fetch_images(&cb_sink, &builder, article);
+        }
+    });
+}
+
+/// Helper function that downloads the images of an article after it has been displayed and adds
+/// them once they're all there, so the article can be read in the meantime. It's called on the
+/// thread that fetched the article
+fn fetch_images(cb_sink: &CbSink, builder: &ArticleBuilder, article: Article) {
+    let images = builder.fetch_images(&article);
+    if images.is_empty() {
+        return;
+    }
+    if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+        s.call_on_name("article_tabs", |view: &mut TabView| {
+            view.for_each_tab(|layout, history| {
+                history.add_images(&article, &images);
+                for name in ["article_view", "split_view"] {
+                    layout.call_on_name(name, |view: &mut ArticleView| {
+                        view.add_images(&article, &images)
+                    });
+                }
+            })
+        });
+    })) {
+        log::warn!("{:?}", error);
+    }
+}
+
+/// Helper function that displays a fetched article, or an error message if the fetching failed.
+/// The article can be fetched again from the error message with the same builder and title.
+/// Articles of cancelled or outdated requests are discarded
+fn on_article_fetched(
+    siv: &mut Cursive,
+    request: usize,
+    article: Result<Article>,
+    builder: ArticleBuilder,
+    title: String,
+    mode: LinkOpenMode,
+) {
+    if ARTICLE_REQUEST.load(Ordering::SeqCst) != request {
+        log::debug!(
+            "discarding the article of the cancelled request '{}'",
+            request
+        );
+        return;
+    }
+    hide_loading_view(siv);
+
+    let article = match article {
         Ok(article) => article,
         Err(error) => {
-            // log the error
-            log::warn!("{}", error);
-
-            // display an error message
-            siv.add_layer(
-                Dialog::info("A Problem occurred while fetching the article.\nCheck the logs for further information")
-                    .title("Error")
-                    .title_position(HAlign::Center)
-            );
-            log::info!("on_article_submit failed to finish");
+            log::warn!("{:?}", error);
+            show_error_with_retry(siv, &t("error.fetching_article"), &error, move |s| {
+                fetch_article(s, builder.clone(), title.clone(), mode)
+            });
             return;
         }
     };
 
-    // display the article
+    log::debug!("displaying the article");
+    let disambiguation = article.is_disambiguation().then(|| {
+        (
+            article
+                .base_url()
+                .map(str::to_string)
+                .unwrap_or_else(|| ui::wikis::current_wiki().base_url),
+            article.disambiguation_links().to_vec(),
+        )
+    });
+    if let Err(error) = display_article(siv, article, mode) {
+        log::warn!("{:?}", error);
+        show_error(siv, &t("error.displaying_article"), &error);
+        return;
+    }
+
+    // let the user pick the meaning of a disambiguation page right away
+    if let Some((base_url, links)) = disambiguation {
+        on_disambiguation_show(siv, base_url, links);
+    }
+}
+
+/// Lists the meanings of a disambiguation page in a dialog. Selecting one of them opens its
+/// article, closing the dialog leaves the disambiguation page open
+fn on_disambiguation_show(siv: &mut Cursive, base_url: String, links: Vec<(String, String)>) {
     log::info!(
-        "displaying the article '{}'",
-        if search_result.title().is_empty() {
-            search_result.page_id().to_string()
-        } else {
-            search_result.title().to_string()
-        }
+        "showing '{}' meanings of the disambiguation page",
+        links.len()
     );
-    if let Err(error) = display_article(siv, article) {
-        // log the error
-        log::warn!("{}", error);
+    siv.add_layer(links_dialog(
+        t("article.disambiguation_title"),
+        base_url,
+        links,
+    ));
+}
 
-        // display an error message
+/// Lists the articles of the "See also" section in a dialog. Selecting one of them opens its
+/// article. It's the callback for the see_also keybinding
+pub fn on_see_also_show(siv: &mut Cursive, base_url: String, links: Vec<(String, String)>) {
+    log::info!("showing '{}' related articles", links.len());
+
+    if links.is_empty() {
         siv.add_layer(
-            Dialog::info("A Problem occurred while displaying the article.\nCheck the logs for further information")
-                .title("Error")
-                .title_position(HAlign::Center)
+            Dialog::info(t("article.no_see_also"))
+                .title(t("article.see_also_title"))
+                .title_position(HAlign::Center),
         );
-        log::info!("on_article_submit failed to finish");
         return;
     }
+    siv.add_layer(links_dialog(t("article.see_also_title"), base_url, links));
+}
 
-    log::info!("on_article_submit finished successfully");
+/// Helper function that creates a dialog listing links with their description. Selecting one of
+/// them opens its article in the wiki at base_url
+fn links_dialog(title: String, base_url: String, links: Vec<(String, String)>) -> impl View {
+    let mut links_view = SelectView::<String>::new().on_submit(move |s, target: &String| {
+        open_link(s, &base_url, target.clone(), LinkOpenMode::REPLACE)
+    });
+    links_view.add_all(links);
+
+    Dialog::around(
+        RootLayout::new(Orientation::Vertical).child(view_with_theme!(
+            CONFIG.theme.search_results,
+            links_view.with_name("links_view").scrollable()
+        )),
+    )
+    .title(title)
+    .title_position(HAlign::Center)
+    .dismiss_button(t("dialog.close"))
+    .max_width(80)
+    .max_height(30)
 }
 
 /// Fetches an article from a given link and displays it. It's the on_submit callback for the
@@ -89,91 +563,906 @@ pub fn on_link_submit(siv: &mut Cursive, target: String) {
         "on_link_submit was called with the target link '{}'",
         target
     );
+    on_link_submit_with_mode(siv, target, CONFIG.settings.links.open_mode);
+}
+
+/// Fetches an article from a given link and displays it the way a given mode says, replacing the
+/// displayed article, in a new tab, in a split next to it or in a tab in the background. Depending
+/// on the configuration, the user is asked for confirmation first. It's the callback for the
+/// open_in_new_tab, open_in_split and open_in_background keybindings
+pub fn on_link_submit_with_mode(siv: &mut Cursive, target: String, mode: LinkOpenMode) {
+    // links to other language editions are opened in that edition
+    let (base_url, target) = match split_wiki_link(&target) {
+        Some((base_url, path)) => (base_url, path),
+        None => (ui::wikis::current_wiki().base_url, target),
+    };
+
+    // links to other websites are opened in the browser
+    if is_external_link(&target) {
+        if !CONFIG.features.external_links {
+            log::info!("external links are disabled, ignoring '{}'", target);
+            return;
+        }
+        on_external_link_submit(siv, target);
+        return;
+    }
+
+    // links to other namespaces than the articles can be opened in the browser or need a warning
+    let namespace = link_namespace(&target);
+    if let Some((_, NamespaceBehavior::BROWSER)) = namespace {
+        match request::join_url(&base_url, &target) {
+            Ok(url) => on_external_link_submit(siv, url),
+            Err(error) => log::warn!("{:?}", error),
+        }
+        return;
+    }
 
     // convert the target into a human-friendly format
-    let target_human = {
-        let target = target.strip_prefix("/wiki/").unwrap_or(&target);
-        target.replace('_', " ")
+    let mut target_human = format!("'{}'", human_target(&target));
+    if !ui::wikis::is_current_wiki(&base_url) {
+        target_human = t_args(
+            "article.link_from_wiki",
+            &[&target_human, &config::wiki_host(&base_url)],
+        );
+    }
+
+    let message = match namespace {
+        Some((namespace, NamespaceBehavior::WARN)) => t_args(
+            "article.namespace_confirmation",
+            &[&target_human, &namespace],
+        ),
+        _ if !CONFIG.settings.confirm_link_open => {
+            log::info!("opening the link without confirmation");
+            fetch_link(siv, &base_url, target, mode);
+            return;
+        }
+        _ => t_args("article.link_confirmation", &[&target_human]),
     };
 
     log::info!("requesting confirmation from the user");
+    // create a dialog that asks the user for confirmation whether he really wants to open this
+    // link
+    siv.add_layer(confirmation_dialog(message, move |s| {
+        log::info!("on_link_submit - user said yes :) continuing...");
+        // the human wants us to open the link for him... we will comply...
+        open_link(s, &base_url, target.clone(), mode)
+    }));
+
+    log::info!("submit_link finished successfully");
+}
+
+/// Asks the user for confirmation and then opens a given external link in the default browser
+fn on_external_link_submit(siv: &mut Cursive, target: String) {
+    log::info!("requesting confirmation from the user");
+    siv.add_layer(confirmation_dialog(
+        t_args("article.browser_confirmation", &[&target]),
+        move |s| {
+            log::info!("on_external_link_submit - user said yes :) continuing...");
+            s.pop_layer();
+            if let Err(error) = open::that(&target) {
+                log::warn!("{:?}", error);
+                s.add_layer(
+                    Dialog::info(t_args(
+                        "error.problem",
+                        &[&t("error.opening_link_in_browser")],
+                    ))
+                    .title(t("dialog.error"))
+                    .title_position(HAlign::Center),
+                );
+            }
+        },
+    ));
+}
+
+/// Helper function that creates a dialog asking the user to confirm opening a link. The labels of
+/// its buttons and which one is focused are taken from the configuration
+fn confirmation_dialog<F>(message: String, on_confirm: F) -> RootLayout
+where
+    F: 'static + Fn(&mut Cursive),
+{
+    let mut dialog = Dialog::around(TextView::new(message))
+        .button(&CONFIG.settings.links.confirm_label, on_confirm)
+        .button(&CONFIG.settings.links.cancel_label, |s| {
+            log::info!("the user said no :/ aborting...");
+            // so he doesn't want us to open the link... delete the whole dialog and pretend it
+            // didn't happen
+            s.pop_layer();
+        });
+
+    if CONFIG.settings.links.default_button == ConfirmButton::CANCEL {
+        dialog.set_focus(DialogFocus::Button(1));
+    }
+
+    RootLayout::new(Orientation::Vertical).child(dialog)
+}
+
+/// Displays information about an article in a dialog. It's the callback for the article_info
+/// keybinding
+pub fn on_article_info(siv: &mut Cursive, info: String) {
+    log::info!("on_article_info was called");
     siv.add_layer(
-        // create a dialog that asks the user for confirmation whether he really wants to open this
-        // link
-        RootLayout::new(Orientation::Vertical, CONFIG.keybindings.clone()).child(
-            Dialog::around(TextView::new(format!(
-                "Do you want to open the article '{}'?",
-                target_human
-            )))
-            .button("Yep", move |s| {
-                log::info!("on_link_submit - user said yes :) continuing...");
-                // the human wants us to open the link for him... we will comply...
-                open_link(s, target.clone())
-            })
-            .button("Nope", move |s| {
-                log::info!("on_link_submit - said no :/ aborting...");
-                // so he doesn't want us to open the link... delete the whole dialog and pretend it
-                // didn't happen
-                s.pop_layer();
-            }),
-        ),
+        Dialog::info(info)
+            .title(t("article.info_title"))
+            .title_position(HAlign::Center),
     );
+}
 
-    log::info!("on_link_submit finished successfully");
+/// Helper function that generates the information about an article, that is displayed by
+/// on_article_info
+fn article_info(article: &Article) -> String {
+    let unknown = t("article.unknown");
+    t_args(
+        "article.info",
+        &[
+            &article.title().unwrap_or(&unknown),
+            &article
+                .last_modified()
+                .map(format_date)
+                .unwrap_or_else(|| unknown.clone()),
+            &format_number(article.word_count()),
+            &format_number(article.references().len()),
+            &article.canonical_url().unwrap_or(&unknown),
+        ],
+    )
 }
 
-/// Helper function for fetching and displaying an article from a given link
-fn open_link(siv: &mut Cursive, target: String) {
-    log::debug!("open_link was called");
+/// Shows the text of a reference. It's the callback for opening the marker of a reference
+pub fn on_reference_show(siv: &mut Cursive, marker: String, text: String) {
+    log::info!("showing the reference '{}'", marker);
+    siv.add_layer(
+        Dialog::info(text)
+            .title(t_args("article.reference_title", &[&marker]))
+            .title_position(HAlign::Center)
+            .max_width(80),
+    );
+}
 
-    // hide the confirmation dialog
-    siv.pop_layer();
+/// Lists the references of an article in a scrollable dialog. It's the callback for the
+/// references keybinding
+pub fn on_references_show(siv: &mut Cursive, references: Vec<(String, String)>) {
+    log::info!("showing '{}' references", references.len());
 
-    // fetch the article
-    log::debug!("fetching the article");
-    let article = match ArticleBuilder::new(0, Some(target), &CONFIG.api_config.base_url)
-        .build(&mut DefaultParser::new(&CONFIG.settings.toc))
-    {
-        Ok(article) => article,
+    if references.is_empty() {
+        siv.add_layer(
+            Dialog::info(t("article.no_references"))
+                .title(t("article.references_title"))
+                .title_position(HAlign::Center),
+        );
+        return;
+    }
+
+    let mut content = StyledString::new();
+    for (i, (_, text)) in references.iter().enumerate() {
+        content.append_styled(
+            format!("[{}] ", i + 1),
+            Style::from(CONFIG.theme.text).combine(Effect::Bold),
+        );
+        content.append_plain(format!("{}\n", text));
+    }
+
+    siv.add_layer(
+        Dialog::around(TextView::new(content).scrollable())
+            .title(t("article.references_title"))
+            .title_position(HAlign::Center)
+            .dismiss_button(t("dialog.close"))
+            .max_width(80)
+            .max_height(30),
+    );
+}
+
+/// Copies the url of an article to the clipboard. If the clipboard can't be used, the url is
+/// displayed instead so it can be copied manually. It's the callback for the copy_url keybinding
+pub fn on_copy_url(siv: &mut Cursive, url: String) {
+    log::info!("on_copy_url was called");
+
+    let message = match copy_to_clipboard(&url) {
+        Ok(()) => t_args("article.clipboard_copied", &[&url]),
         Err(error) => {
             log::warn!("{:?}", error);
+            t_args("article.clipboard_unavailable", &[&url])
+        }
+    };
+
+    siv.add_layer(
+        Dialog::info(message)
+            .title(t("article.url_title"))
+            .title_position(HAlign::Center),
+    );
+}
+
+/// Copies the url of a link to the clipboard without opening it. If the clipboard can't be used,
+/// the url is displayed instead so it can be copied manually. It's the callback for the copy_link
+/// keybinding
+pub fn on_copy_link(siv: &mut Cursive, url: String) {
+    log::info!("on_copy_link was called");
 
-            // display an error message
+    match copy_to_clipboard(&url) {
+        Ok(()) => ui::status::display_message(siv, t_args("article.link_copied", &[&url])),
+        Err(error) => {
+            log::warn!("{:?}", error);
             siv.add_layer(
-                Dialog::info("A Problem occurred while fetching the article.\nCheck the logs for further information")
-                    .title("Error")
-                    .title_position(HAlign::Center)
+                Dialog::info(t_args("article.clipboard_unavailable", &[&url]))
+                    .title(t("article.url_title"))
+                    .title_position(HAlign::Center),
             );
+        }
+    }
+}
 
-            log::debug!("open_link failed to finish");
-            return;
+/// Copies the selected text of an article to the clipboard. If the clipboard can't be used, the
+/// text is displayed instead so it can be copied manually
+pub fn on_copy_selection(siv: &mut Cursive, text: String) {
+    log::info!("on_copy_selection was called");
+
+    let message = match copy_to_clipboard(&text) {
+        Ok(()) => t("article.selection_copied"),
+        Err(error) => {
+            log::warn!("{:?}", error);
+            t_args("article.selection_unavailable", &[&text])
         }
     };
 
-    // display the article
-    log::debug!("displaying the article");
-    if let Err(error) = display_article(siv, article) {
-        log::warn!("{:?}", error);
+    siv.add_layer(
+        Dialog::info(message)
+            .title(t("article.selection_title"))
+            .title_position(HAlign::Center),
+    );
+}
 
-        // display an error message
+/// Opens the url of an article in the default browser. It's the callback for the open_in_browser
+/// keybinding
+pub fn on_open_in_browser(siv: &mut Cursive, url: String) {
+    log::info!("opening '{}' in the browser", url);
+    if let Err(error) = open::that(&url) {
+        log::warn!("{:?}", error);
         siv.add_layer(
-            Dialog::info("A Problem occurred while displaying the article.\nCheck the logs for further information")
-                .title("Error")
-                .title_position(HAlign::Center)
+            Dialog::info(t_args(
+                "error.problem",
+                &[&t("error.opening_article_in_browser")],
+            ))
+            .title(t("dialog.error"))
+            .title_position(HAlign::Center),
         );
+    }
+}
+
+/// Asks for the file an exported article is saved in and saves it there. The configured export
+/// directory and the title of the article are suggested. It's the callback for the export_article
+/// keybinding
+pub fn on_article_export(siv: &mut Cursive, title: String, content: String) {
+    log::info!("on_article_export was called");
+
+    // the title can't contain path separators
+    let file_name = format!(
+        "{}.{}",
+        title.replace(['/', '\\'], "-"),
+        CONFIG.settings.export.format.extension()
+    );
+    let path = match CONFIG.settings.export.path {
+        Some(ref path) => path.join(file_name),
+        None => PathBuf::from(file_name),
+    };
+
+    let save = move |s: &mut Cursive, path: &str| {
+        s.pop_layer();
+        log::info!("exporting the article to '{}'", path);
+        match save_file(Path::new(path), &content) {
+            Ok(()) => s.add_layer(
+                Dialog::info(t_args("article.exported", &[&path]))
+                    .title(t("article.export_title"))
+                    .title_position(HAlign::Center),
+            ),
+            Err(error) => {
+                log::warn!("{:?}", error);
+                show_error(s, &t("error.exporting_article"), &error);
+            }
+        }
+    };
+
+    siv.add_layer(
+        Dialog::around(
+            EditView::new()
+                .content(path.to_string_lossy())
+                .on_submit(save.clone())
+                .with_name("export_path")
+                .min_width(40),
+        )
+        .title(t("article.export_title"))
+        .title_position(HAlign::Center)
+        .button(t("article.export"), move |s| {
+            let path = s
+                .call_on_name("export_path", |view: &mut EditView| view.get_content())
+                .unwrap_or_default();
+            save(s, &path)
+        })
+        .dismiss_button(t("dialog.cancel")),
+    );
+}
+
+/// Helper function that writes a given content into a file, creating its directory if needed
+fn save_file(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("couldn't create the directory '{}'", parent.display()))?;
+    }
+    fs::write(path, content).with_context(|| format!("couldn't write '{}'", path.display()))
+}
+
+/// Helper function that puts a given text onto the clipboard. The clipboard stays open
+/// afterwards, because on linux its content is lost when it's closed
+fn copy_to_clipboard(text: &str) -> Result<()> {
+    CLIPBOARD.with(|clipboard| {
+        let mut clipboard = clipboard.borrow_mut();
+        if clipboard.is_none() {
+            *clipboard = Some(Clipboard::new().context("couldn't open the clipboard")?);
+        }
+
+        clipboard
+            .as_mut()
+            .context("couldn't open the clipboard")?
+            .set_text(text)
+            .context("couldn't copy the text to the clipboard")
+    })
+}
+
+/// Helper function that returns the url of an article. The canonical url is preferred, otherwise
+/// the url is built from the id of the article
+fn article_url(article: &Article) -> Option<String> {
+    if let Some(url) = article.canonical_url() {
+        return Some(url.to_string());
+    }
+
+    let wiki = ui::wikis::find_wiki(article.base_url()?);
+    let path = format!("{}index.php?curid={}", wiki.script_path, article.page_id()?);
+    request::join_url(&wiki.base_url, &path).ok()
+}
 
-        log::debug!("open_link failed to finish");
+/// Helper function that returns the full url of a link in an article from a given wiki. Links to
+/// anchors point into the article itself, so they need its url
+fn link_url(base_url: &str, article_url: Option<&str>, target: &str) -> Option<String> {
+    if is_external_link(target) {
+        return Some(target.to_string());
+    }
+    if let Some(target) = target.strip_prefix("//") {
+        return Some(format!("https://{}", target));
+    }
+    if target.starts_with('/') {
+        return Some(format!("{}{}", base_url.trim_end_matches('/'), target));
+    }
+    if target.starts_with('#') {
+        let article_url = article_url?;
+        return Some(format!(
+            "{}{}",
+            article_url.split('#').next().unwrap_or(article_url),
+            target
+        ));
+    }
+    None
+}
+
+/// Helper function that checks if a link points to another website
+fn is_external_link(target: &str) -> bool {
+    target.starts_with("http://") || target.starts_with("https://")
+}
+
+/// Helper function that returns the namespace of a link together with how its links are opened.
+/// Links to articles and to namespaces that aren't configured return None
+fn link_namespace(target: &str) -> Option<(String, NamespaceBehavior)> {
+    let title = human_target(target);
+    let (namespace, _) = title.split_once(':')?;
+    let namespace = namespace.trim().to_lowercase();
+    let behavior = *CONFIG.settings.links.namespaces.get(&namespace)?;
+    Some((namespace, behavior))
+}
+
+/// Helper function that splits a link to an article of any wikipedia language edition into the
+/// url of that wikipedia and the path of the article. Returns None for any other link
+fn split_wiki_link(target: &str) -> Option<(String, String)> {
+    let link = target
+        .strip_prefix("https://")
+        .or_else(|| target.strip_prefix("http://"))
+        .or_else(|| target.strip_prefix("//"))?;
+    let (host, path) = link.split_at(link.find('/')?);
+
+    if !path.starts_with("/wiki/") {
+        return None;
+    }
+    if host.ends_with(".wikipedia.org") {
+        return Some((format!("https://{}/", host), path.to_string()));
+    }
+    if ui::wikis::is_configured_host(host) {
+        return Some((
+            ui::wikis::find_wiki(&format!("https://{}/", host)).base_url,
+            path.to_string(),
+        ));
+    }
+    None
+}
+
+/// Helper function that makes an article link absolute, if the article containing it was fetched
+/// from a wikipedia other than the configured one. This way, the link is opened in the same
+/// language edition as the article
+fn resolve_link(base_url: Option<&str>, target: &str) -> String {
+    match base_url {
+        Some(base_url) if target.starts_with("/wiki/") && !ui::wikis::is_current_wiki(base_url) => {
+            format!("{}{}", base_url.trim_end_matches('/'), target)
+        }
+        _ => target.to_string(),
+    }
+}
+
+/// Fetches a preview for the link with the given id and target and displays it in the article
+/// view. The preview is only fetched when no other preview was requested in the meantime, so
+/// rapidly changing the selection doesn't result in a request for every link
+pub fn on_link_preview(siv: &mut Cursive, id: i32, target: String) {
+    log::debug!("on_link_preview was called for the link '{}'", id);
+
+    // only links to other articles can be previewed
+    let (base_url, target) = match split_wiki_link(&target) {
+        Some((base_url, path)) => (base_url, path),
+        None if target.starts_with("/wiki/") => (ui::wikis::current_wiki().base_url, target),
+        None => {
+            log::debug!("the target '{}' can't be previewed", target);
+            return;
+        }
+    };
+
+    let request = LINK_PREVIEW_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        // wait for the selection to settle
+        thread::sleep(LINK_PREVIEW_DELAY);
+        if LINK_PREVIEW_REQUEST.load(Ordering::SeqCst) != request {
+            return;
+        }
+
+        let preview = match fetch_link_preview(&base_url, &target) {
+            Ok(preview) => preview,
+            Err(error) => {
+                log::warn!("{:?}", error);
+                return;
+            }
+        };
+
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            if LINK_PREVIEW_REQUEST.load(Ordering::SeqCst) != request {
+                return;
+            }
+            s.call_on_name("article_view", |view: &mut ArticleView| {
+                view.set_link_preview(id, preview)
+            });
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+}
+
+/// Helper function that searches the wikipedia at base_url for the article of a given link and
+/// generates a preview from the first result. Any errors it encounters are returned
+fn fetch_link_preview(base_url: &str, target: &str) -> Result<StyledString> {
+    let search = SearchBuilder::new(base_url)
+        .script_path(ui::wikis::find_wiki(base_url).script_path)
+        .query(human_target(target))
+        .limit(1)
+        .prop(SearchProperties::new().snippet())
+        .timeout(Duration::from_millis(CONFIG.api_config.timeout_ms))
+        .max_retries(CONFIG.api_config.max_retries)
+        .user_agent(CONFIG.api_config.user_agent.clone())
+        .offline(CONFIG.features.offline)
+        .search()?;
+
+    let result = search
+        .results()
+        .next()
+        .with_context(|| format!("couldn't find a preview for '{}'", target))?;
+    Ok(ui::search::generate_preview(result))
+}
+
+/// Stores the articles of the given links in the cache in the background, so they can be opened
+/// without waiting for the network. At most prefetch_limit articles are fetched at the same time
+pub fn prefetch_links(targets: Vec<String>) {
+    if !CONFIG.settings.cache.enabled || CONFIG.features.offline {
         return;
     }
 
+    {
+        let mut queue = PREFETCH_QUEUE.lock().unwrap();
+        for target in targets {
+            // links to other websites aren't articles
+            let (base_url, target) = match split_wiki_link(&target) {
+                Some((base_url, path)) => (base_url, path),
+                None if is_external_link(&target) => continue,
+                None => (ui::wikis::current_wiki().base_url, target),
+            };
+            queue.push_back(build_article(&base_url, 0, Some(target)));
+        }
+    }
+
+    // start new workers until the limit is reached, the running ones take from the same queue
+    while PREFETCH_WORKERS
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |workers| {
+            (workers < CONFIG.settings.cache.prefetch_limit).then_some(workers + 1)
+        })
+        .is_ok()
+    {
+        thread::spawn(prefetch_worker);
+    }
+}
+
+/// Helper function that prefetches the articles of the queue until it's empty
+fn prefetch_worker() {
+    let format = match CONFIG.settings.parser {
+        ParserType::HTML => SourceFormat::Html,
+        ParserType::WIKITEXT => SourceFormat::Wikitext,
+    };
+
+    loop {
+        // the worker stops while holding the lock, so links queued right after it are picked up
+        // by a new worker. The lock is released before the article is fetched
+        let builder = {
+            let mut queue = PREFETCH_QUEUE.lock().unwrap();
+            match queue.pop_front() {
+                Some(builder) => builder,
+                None => {
+                    PREFETCH_WORKERS.fetch_sub(1, Ordering::SeqCst);
+                    return;
+                }
+            }
+        };
+        if let Err(error) = builder.prefetch(format) {
+            log::debug!("{:?}", error);
+        }
+    }
+}
+
+/// Helper function that drops the links waiting to be prefetched, the ones being fetched right
+/// now are finished
+fn clear_prefetch_queue() {
+    let mut queue = PREFETCH_QUEUE.lock().unwrap();
+    if !queue.is_empty() {
+        log::debug!("dropping '{}' links waiting to be prefetched", queue.len());
+        queue.clear();
+    }
+}
+
+/// Helper function that converts a link target into a human-friendly format
+fn human_target(target: &str) -> String {
+    target
+        .strip_prefix("/wiki/")
+        .unwrap_or(target)
+        .replace('_', " ")
+}
+
+/// Helper function for fetching and displaying an article from a given link of the wikipedia at
+/// base_url. It's the callback for the confirmation dialog
+fn open_link(siv: &mut Cursive, base_url: &str, target: String, mode: LinkOpenMode) {
+    log::debug!("open_link was called");
+
+    // hide the confirmation dialog
+    siv.pop_layer();
+
+    fetch_link(siv, base_url, target, mode);
+
     log::debug!("open_link finished successfully");
 }
 
-/// Helper function for displaying an article on the screen. This includes creating an article view
-/// and any errors it encountred are returned
-fn display_article(siv: &mut Cursive, article: Article) -> Result<()> {
+/// Helper function that fetches and displays the article of a given link of the wikipedia at
+/// base_url, the way a given mode says
+fn fetch_link(siv: &mut Cursive, base_url: &str, target: String, mode: LinkOpenMode) {
+    log::debug!("fetching the article");
+    let title = human_target(&target);
+    fetch_article(siv, build_article(base_url, 0, Some(target)), title, mode);
+}
+
+/// Displays the previous article from the history. It's the callback for the history_back
+/// keybinding
+pub fn on_history_back(siv: &mut Cursive) {
+    log::info!("on_history_back was called");
+    move_in_history(siv, "previous", History::back);
+}
+
+/// Displays the next article from the history. It's the callback for the history_forward
+/// keybinding
+pub fn on_history_forward(siv: &mut Cursive) {
+    log::info!("on_history_forward was called");
+    move_in_history(siv, "next", History::forward);
+}
+
+/// Helper function that moves through the history of the tab with a given step, displays the
+/// article it arrives at and restores the position that article was left at. The direction only
+/// describes the step in the logs
+fn move_in_history(siv: &mut Cursive, direction: &str, step: fn(&mut History) -> Option<&Article>) {
+    save_position(siv);
+    let article = ui::tabs::with_history(siv, |history| {
+        step(history)
+            .cloned()
+            .map(|article| (article, history.position()))
+    });
+    let (article, position) = match article.flatten() {
+        Some(article) => article,
+        None => {
+            log::info!("there is no {} article in the history", direction);
+            return;
+        }
+    };
+
+    if let Err(error) = render_article(siv, article) {
+        log::warn!("{:?}", error);
+        log::info!("couldn't display the {} article of the history", direction);
+        return;
+    }
+    if let Some(position) = position {
+        restore_position(siv, position);
+    }
+
+    log::info!("displayed the {} article of the history", direction);
+}
+
+/// Refreshes the displayed article every few minutes, as configured. The refreshes are sent to the
+/// event loop of cursive, the timer stops when wiki-tui is closed
+pub fn start_auto_refresh(siv: &Cursive) {
+    let minutes = CONFIG.settings.article.auto_refresh;
+    if minutes == 0 || CONFIG.features.offline {
+        return;
+    }
+
+    log::info!(
+        "refreshing the displayed article every '{}' minutes",
+        minutes
+    );
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || loop {
+        thread::sleep(Duration::from_secs(minutes * 60));
+        if cb_sink.send(Box::new(on_auto_refresh)).is_err() {
+            break;
+        }
+    });
+}
+
+/// Fetches the displayed article again in the background. The new version is only displayed if
+/// the article has been edited since. It's called regularly when the auto refresh is enabled
+fn on_auto_refresh(siv: &mut Cursive) {
+    // don't interrupt the reader while a dialog is open
+    if siv.screen().len() > 1 {
+        log::debug!("a dialog is open, the article isn't refreshed");
+        return;
+    }
+
+    let article = match ui::tabs::with_history(siv, |history| history.current().cloned()).flatten()
+    {
+        Some(article) => article,
+        None => return,
+    };
+    let builder = match (article.base_url(), article.page_id()) {
+        (Some(base_url), Some(page_id)) => build_article(base_url, page_id, None).refresh(true),
+        _ => {
+            log::debug!("the displayed article can't be fetched again");
+            return;
+        }
+    };
+
+    let title = article.title().unwrap_or_default().to_string();
+    log::info!("refreshing the article '{}'", title);
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let revised = match parse_article(&builder, &title) {
+            Ok(revised) => revised,
+            Err(error) => {
+                log::warn!("{:?}", error);
+                return;
+            }
+        };
+        if !is_revised(&article, &revised) {
+            log::debug!("the article '{}' hasn't been edited", title);
+            return;
+        }
+
+        let displayed = revised.clone();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            on_article_replaced(s, article, revised, "article.refreshed")
+        })) {
+            log::warn!("{:?}", error);
+        }
+        fetch_images(&cb_sink, &builder, displayed);
+    });
+}
+
+/// Fetches the displayed article again, without using the cache, and replaces it with the fetched
+/// version. The position in the article is kept. It's the callback for the reload_article
+/// keybinding
+pub fn on_article_reload(siv: &mut Cursive) {
+    log::info!("on_article_reload was called");
+
+    let article = match ui::tabs::with_history(siv, |history| history.current().cloned()).flatten()
+    {
+        Some(article) => article,
+        None => {
+            log::info!("there is no article to reload");
+            return;
+        }
+    };
+    let builder = match (article.base_url(), article.page_id()) {
+        (Some(base_url), Some(page_id)) => build_article(base_url, page_id, None).refresh(true),
+        _ => {
+            log::warn!("the displayed article can't be fetched again");
+            ui::status::display_message(siv, t("article.reload_unavailable"));
+            return;
+        }
+    };
+
+    let title = article.title().unwrap_or_default().to_string();
+    log::info!("reloading the article '{}'", title);
+    let request = ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+    show_loading_view(siv, t_args("article.loading", &[&title]), |_| {
+        log::info!("cancelling the reload");
+        ARTICLE_REQUEST.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let reloaded = parse_article(&builder, &title);
+        let displayed = reloaded.as_ref().ok().cloned();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            if ARTICLE_REQUEST.load(Ordering::SeqCst) != request {
+                log::debug!(
+                    "discarding the reload of the cancelled request '{}'",
+                    request
+                );
+                return;
+            }
+            hide_loading_view(s);
+
+            match reloaded {
+                Ok(reloaded) => on_article_replaced(s, article, reloaded, "article.reloaded"),
+                Err(error) => {
+                    log::warn!("{:?}", error);
+                    show_error(s, &t("error.fetching_article"), &error);
+                }
+            }
+        })) {
+            log::warn!("{:?}", error);
+        }
+        if let Some(article) = displayed {
+            fetch_images(&cb_sink, &builder, article);
+        }
+    });
+}
+
+/// Helper function that replaces a displayed article with its new version, the position in the
+/// article is kept and the message with the given id is displayed. Nothing happens when another
+/// article is displayed by now
+fn on_article_replaced(siv: &mut Cursive, article: Article, replacement: Article, message: &str) {
+    let is_displayed = ui::tabs::with_history(siv, |history| history.current() == Some(&article))
+        .unwrap_or_default();
+    if !is_displayed {
+        log::debug!("the replaced article isn't displayed anymore");
+        return;
+    }
+
+    let title = replacement.title().unwrap_or_default().to_string();
+    let position = siv.call_on_name("article_view", |view: &mut ArticleView| view.position());
+    ui::tabs::with_history(siv, |history| history.replace_current(replacement.clone()));
+    if let Err(error) = render_article(siv, replacement) {
+        log::warn!("{:?}", error);
+        return;
+    }
+    if let Some(position) = position {
+        restore_position(siv, position);
+    }
+    ui::status::display_message(siv, t_args(message, &[&title]));
+}
+
+/// Helper function that checks if a fetched article is a newer version of another one. Without
+/// the dates of their last edits, their content is compared
+fn is_revised(article: &Article, fetched: &Article) -> bool {
+    match (article.last_modified(), fetched.last_modified()) {
+        (Some(last_modified), Some(fetched_last_modified)) => {
+            last_modified != fetched_last_modified
+        }
+        _ => !article.elements().eq(fetched.elements()),
+    }
+}
+
+/// Helper function for displaying a new article on the screen. Depending on the mode, the article
+/// replaces the displayed one, gets a new tab, gets a new tab behind the active one or is
+/// displayed in a split next to the displayed one. Except for splits, the article is added to the
+/// history of the tab and then rendered. Any errors it encountred are returned
+fn display_article(siv: &mut Cursive, article: Article, mode: LinkOpenMode) -> Result<()> {
     log::debug!("display_article was called");
 
+    // the links of the article that is left don't have to be prefetched anymore
+    if mode == LinkOpenMode::REPLACE || mode == LinkOpenMode::TAB {
+        clear_prefetch_queue();
+    }
+
+    match mode {
+        LinkOpenMode::SPLIT => return render_split(siv, article),
+        LinkOpenMode::BACKGROUND => return render_background(siv, article),
+        LinkOpenMode::TAB => ui::tabs::open_tab(siv),
+        LinkOpenMode::REPLACE => save_position(siv),
+    }
+    ui::tabs::with_history(siv, |history| history.push(article.clone()));
+    ui::reading_log::on_article_opened(&article);
+    render_article(siv, article)
+}
+
+/// Helper function that remembers the position of the displayed article in the history of the
+/// tab, so it can be restored when going back to the article
+fn save_position(siv: &mut Cursive) {
+    if let Some(position) =
+        siv.call_on_name("article_view", |view: &mut ArticleView| view.position())
+    {
+        ui::tabs::with_history(siv, |history| history.set_position(position));
+    }
+}
+
+/// Helper function that scrolls the displayed article to a given position and selects the link
+/// that was selected there. The screen is laid out first, otherwise the article has no size yet
+/// and can't be scrolled
+fn restore_position(siv: &mut Cursive, position: ArticlePosition) {
+    log::debug!("restoring the position '{:?}'", position);
+    let size = siv.screen_size();
+    siv.screen_mut().layout(size);
+
+    if let Some(link) = position.link {
+        siv.call_on_name("article_view", |view: &mut ArticleView| {
+            view.select_link(link)
+        });
+    }
+    siv.call_on_name("article_scroll", |view: &mut ArticleScrollView| {
+        view.set_offset((0, position.offset))
+    });
+}
+
+/// Helper function for rendering an article in a split next to the displayed one. The split
+/// replaces any split that is already displayed and any errors it encountred are returned
+fn render_split(siv: &mut Cursive, article: Article) -> Result<()> {
+    log::debug!("render_split was called");
+
+    // if the search layer still exists, then remove it
+    if siv
+        .find_name::<TextView>("search_results_preview")
+        .is_some()
+    {
+        siv.pop_layer();
+        log::debug!("removed the search_results_preview layer");
+    }
+
+    let title = article.title().unwrap_or_default().to_string();
+    let theme = CONFIG.theme.article_view_for(article.base_url());
+    ui::tabs::open_split(
+        siv,
+        view_with_theme!(
+            theme,
+            Dialog::around(
+                StatusTracker::new(
+                    StatusContext::Article,
+                    ArticleView::new(article).with_name("split_view")
+                )
+                .scrollable()
+            )
+            .title(title)
+        ),
+    );
+    log::debug!("added the split view to the screen");
+
+    siv.focus_name("split_view").with_context(|| {
+        log::debug!("render_split failed to finish");
+        "Failed to focus the split view"
+    })?;
+
+    log::debug!("render_split finished successfully");
+    Ok(())
+}
+
+/// Helper function for rendering an article on the screen. This includes creating an article view
+/// and any errors it encountred are returned
+fn render_article(siv: &mut Cursive, article: Article) -> Result<()> {
+    log::debug!("render_article was called");
+
     // if the search layer still exists, then remove it
     if siv
         .find_name::<TextView>("search_results_preview")
@@ -183,28 +1472,61 @@ fn display_article(siv: &mut Cursive, article: Article) -> Result<()> {
         log::debug!("removed the search_results_preview layer");
     }
 
+    add_article_view(siv, article)?;
+
+    // focus the article view
+    siv.focus_name("article_view").with_context(|| {
+        log::debug!("render_article failed to finish");
+        "Failed to focus the article view"
+    })?;
+
+    log::debug!("render_article finished successfully");
+    Ok(())
+}
+
+/// Helper function for rendering an article in a new tab that stays in the background. Unlike
+/// render_article, it leaves the layers, the focus and the reading log of the active tab alone
+fn render_background(siv: &mut Cursive, article: Article) -> Result<()> {
+    log::debug!("render_background was called");
+    ui::tabs::in_background(siv, |s| {
+        ui::tabs::open_tab(s);
+        ui::tabs::with_history(s, |history| history.push(article.clone()));
+        add_article_view(s, article)
+    })
+}
+
+/// Helper function that displays an article in the article layout of the active tab, together
+/// with its table of contents
+fn add_article_view(siv: &mut Cursive, article: Article) -> Result<()> {
     // remove views
-    remove_view_from_layout(siv, "logo_view", "article_layout");
+    remove_view_from_layout(siv, "start_view", "article_layout");
     remove_view_from_layout(siv, "article_view", "article_layout");
     remove_view_from_layout(siv, "toc_view", "article_layout");
 
-    // display the toc if there is one
-    if let Some(toc) = article.toc() {
+    // display the toc if there is one and it isn't hidden
+    let toc_position = ui::toc::toc_position();
+    if let (Some(toc), Some(_)) = (article.toc(), toc_position) {
         log::info!("displaying the table of contents");
-        ui::toc::add_table_of_contents(siv, toc);
+        ui::toc::add_table_of_contents(siv, toc, article.base_url());
     }
 
     // check if the article has a toc
-    let has_toc = article.toc().is_some();
+    let has_toc = article.toc().is_some() && toc_position.is_some();
+
+    if let Some(title) = article.title() {
+        ui::tabs::set_title(siv, title.to_string());
+    }
+    ui::visited::on_article_opened(&article);
 
-    // create the article view
+    // create the article view, with the theme of the wiki the article is from
+    let theme = CONFIG.theme.article_view_for(article.base_url());
     let article_view = ArticleView::new(article);
     log::debug!("created an instance of ArticleView");
 
     // get the index of the article view (this index determines the location of the toc)
-    let index = match CONFIG.settings.toc.position {
-        TocPosition::LEFT => 1,
-        TocPosition::RIGHT => 0,
+    let index = match toc_position {
+        Some(TocPosition::LEFT) | Some(TocPosition::TOP) => 1,
+        _ => 0,
     };
 
     // add the article view to the screen
@@ -213,29 +1535,268 @@ fn display_article(siv: &mut Cursive, article: Article) -> Result<()> {
             view.insert_child(
                 index,
                 view_with_theme!(
-                    CONFIG.theme.article_view,
-                    Dialog::around(article_view.with_name("article_view").scrollable())
+                    theme,
+                    Dialog::around(
+                        StatusTracker::new(
+                            StatusContext::Article,
+                            article_view.with_name("article_view")
+                        )
+                        .scrollable()
+                        .with_name("article_scroll")
+                    )
                 ),
             );
         } else {
             view.add_child(view_with_theme!(
-                CONFIG.theme.article_view,
-                Dialog::around(article_view.with_name("article_view").scrollable())
+                theme,
+                Dialog::around(
+                    StatusTracker::new(
+                        StatusContext::Article,
+                        article_view.with_name("article_view")
+                    )
+                    .scrollable()
+                    .with_name("article_scroll")
+                )
             ));
         }
     });
     if result.is_none() {
-        log::debug!("display_article failed to finish");
         bail!("Couldn't find the article layout");
     }
     log::debug!("added the ArticleView to the screen");
+    Ok(())
+}
 
-    // focus the article view
-    siv.focus_name("article_view").with_context(|| {
-        log::debug!("display_article failed to finish");
-        "Failed to focus the article view"
-    })?;
+#[cfg(test)]
+mod tests {
+    use super::{
+        display_article, is_revised, link_namespace, link_url, resolve_link, split_wiki_link,
+        ArticlePosition, History,
+    };
+    use crate::config::{LinkOpenMode, NamespaceBehavior};
+    use crate::ui::{
+        tabs::{self, TabView},
+        toc,
+    };
+    use crate::wiki::article::{Article, ArticleElement, ArticleImage};
+    use cursive::view::Nameable;
+    use cursive::views::{Dialog, TextView};
+    use cursive::Cursive;
 
-    log::debug!("display_article finished successfully");
-    Ok(())
+    fn article(id: i32) -> Article {
+        Article::new(vec![ArticleElement::newline(id)], None)
+    }
+
+    #[test]
+    fn back_and_forward() {
+        let mut history = History::new();
+        history.push(article(0));
+        history.push(article(1));
+        history.push(article(2));
+
+        assert_eq!(history.back(), Some(&article(1)));
+        assert_eq!(history.back(), Some(&article(0)));
+        assert_eq!(history.forward(), Some(&article(1)));
+        assert_eq!(history.forward(), Some(&article(2)));
+    }
+
+    #[test]
+    fn boundaries() {
+        let mut history = History::new();
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), None);
+
+        history.push(article(0));
+        assert_eq!(history.back(), None);
+        assert_eq!(history.forward(), None);
+    }
+
+    #[test]
+    fn push_truncates_forward() {
+        let mut history = History::new();
+        history.push(article(0));
+        history.push(article(1));
+        history.push(article(2));
+
+        history.back();
+        history.back();
+        history.push(article(3));
+
+        assert_eq!(history.forward(), None);
+        assert_eq!(history.back(), Some(&article(0)));
+        assert_eq!(history.forward(), Some(&article(3)));
+    }
+
+    #[test]
+    fn history_positions() {
+        let position = ArticlePosition {
+            offset: 42,
+            link: Some(7),
+        };
+        let mut history = History::new();
+        history.push(article(0));
+        history.set_position(position);
+        history.push(article(1));
+        assert_eq!(history.position(), None);
+
+        history.back();
+        assert_eq!(history.position(), Some(position));
+
+        // a new article after the current one doesn't forget its position
+        history.push(article(2));
+        history.back();
+        assert_eq!(history.position(), Some(position));
+    }
+
+    #[test]
+    fn replace_current() {
+        let mut history = History::new();
+        history.push(article(0));
+        history.push(article(1));
+        history.replace_current(article(2));
+
+        assert_eq!(history.current(), Some(&article(2)));
+        assert_eq!(history.back(), Some(&article(0)));
+    }
+
+    #[test]
+    fn revisions() {
+        let edited = |id, last_modified: &str| {
+            let mut article = article(id);
+            article.set_last_modified(last_modified.to_string());
+            article
+        };
+
+        assert!(!is_revised(
+            &edited(0, "2022-05-01"),
+            &edited(1, "2022-05-01")
+        ));
+        assert!(is_revised(
+            &edited(0, "2022-05-01"),
+            &edited(0, "2022-05-02")
+        ));
+        assert!(!is_revised(&article(0), &article(0)));
+        assert!(is_revised(&article(0), &article(1)));
+    }
+
+    #[test]
+    fn link_namespaces() {
+        assert_eq!(
+            link_namespace("/wiki/Category:Programming_languages"),
+            Some(("category".to_string(), NamespaceBehavior::WARN))
+        );
+        assert_eq!(
+            link_namespace("/wiki/File:Rust_logo.svg"),
+            Some(("file".to_string(), NamespaceBehavior::BROWSER))
+        );
+        assert_eq!(link_namespace("/wiki/Star_Wars:_Episode_IV"), None);
+        assert_eq!(link_namespace("/wiki/Rust_(programming_language)"), None);
+    }
+
+    #[test]
+    fn split_wiki_links() {
+        assert_eq!(
+            split_wiki_link("https://de.wikipedia.org/wiki/Rust_(Programmiersprache)"),
+            Some((
+                "https://de.wikipedia.org/".to_string(),
+                "/wiki/Rust_(Programmiersprache)".to_string()
+            ))
+        );
+        assert_eq!(
+            split_wiki_link("//fr.wikipedia.org/wiki/Linux"),
+            Some((
+                "https://fr.wikipedia.org/".to_string(),
+                "/wiki/Linux".to_string()
+            ))
+        );
+        assert_eq!(split_wiki_link("/wiki/Linux"), None);
+        assert_eq!(split_wiki_link("https://github.com/wiki/Linux"), None);
+        assert_eq!(
+            split_wiki_link("https://de.wikipedia.org/w/index.php"),
+            None
+        );
+    }
+
+    #[test]
+    fn resolve_links() {
+        assert_eq!(
+            resolve_link(Some("https://de.wikipedia.org/"), "/wiki/Linux"),
+            "https://de.wikipedia.org/wiki/Linux"
+        );
+        assert_eq!(resolve_link(None, "/wiki/Linux"), "/wiki/Linux");
+        assert_eq!(
+            resolve_link(Some("https://de.wikipedia.org/"), "https://github.com"),
+            "https://github.com"
+        );
+    }
+
+    #[test]
+    fn link_urls() {
+        let base_url = "https://en.wikipedia.org/";
+        let article_url = Some("https://en.wikipedia.org/wiki/Linux");
+        let url = |target| link_url(base_url, article_url, target);
+
+        assert_eq!(
+            url("/wiki/Unix").as_deref(),
+            Some("https://en.wikipedia.org/wiki/Unix")
+        );
+        assert_eq!(
+            url("//commons.wikimedia.org/wiki/Tux").as_deref(),
+            Some("https://commons.wikimedia.org/wiki/Tux")
+        );
+        assert_eq!(
+            url("https://kernel.org").as_deref(),
+            Some("https://kernel.org")
+        );
+        assert_eq!(
+            url("#History").as_deref(),
+            Some("https://en.wikipedia.org/wiki/Linux#History")
+        );
+        assert_eq!(link_url(base_url, None, "#History"), None);
+        assert_eq!(url("Unix"), None);
+    }
+
+    #[test]
+    fn history_images() {
+        let image = ArticleImage::new(1, 1, vec![(0, 0, 0)]).unwrap();
+        let mut history = History::new();
+        history.push(article(0));
+        history.push(article(1));
+        history.add_images(&article(0), &[(0, image.clone())]);
+
+        assert_eq!(history.current().unwrap().images().len(), 0);
+        assert_eq!(history.back().unwrap().images().get(&0), Some(&image));
+    }
+
+    #[test]
+    fn background_tabs() {
+        let mut siv = Cursive::new();
+        siv.add_layer(
+            TabView::new(toc::article_layout().with_name("article_layout"))
+                .with_name("article_tabs"),
+        );
+        siv.add_layer(Dialog::around(
+            TextView::new("").with_name("search_results_preview"),
+        ));
+
+        // the article is opened behind the active tab and the search stays open
+        display_article(&mut siv, article(0), LinkOpenMode::BACKGROUND).unwrap();
+        assert_eq!(siv.screen().len(), 2);
+        assert!(siv
+            .find_name::<TextView>("search_results_preview")
+            .is_some());
+
+        let active = siv.call_on_name("article_tabs", |view: &mut TabView| view.active());
+        assert_eq!(active, Some(0));
+        assert_eq!(
+            tabs::with_history(&mut siv, |history| history.current().cloned()),
+            Some(None)
+        );
+
+        siv.call_on_name("article_tabs", |view: &mut TabView| view.select_next());
+        assert_eq!(
+            tabs::with_history(&mut siv, |history| history.current().cloned()),
+            Some(Some(article(0)))
+        );
+    }
 }
@@ -1,16 +1,35 @@
 use crate::{
-    config::CONFIG, ui::article::content::ArticleContent, ui::article::on_link_submit,
-    wiki::article::Article,
+    config::{LinkOpenMode, CONFIG},
+    ui::article::content::ArticleContent,
+    ui::article::lines::Selection,
+    ui::article::{
+        article_info, article_url, link_url, on_article_export, on_article_info, on_copy_link,
+        on_copy_selection, on_copy_url, on_link_preview, on_link_submit, on_link_submit_with_mode,
+        on_open_in_browser, on_reference_show, on_references_show, on_see_also_show,
+        prefetch_links, resolve_link, ArticlePosition,
+    },
+    ui::bookmarks::{on_bookmark_toggle, Bookmark},
+    ui::messages::t,
+    ui::search::on_search,
+    ui::status::display_message,
+    ui::wikis::current_wiki,
+    wiki::article::{Article, ArticleElement, ArticleImage},
 };
 
 use cursive::{
     direction::Absolute,
     event::{Callback, Event, EventResult, Key, MouseButton, MouseEvent},
+    theme::ColorStyle,
+    utils::markup::StyledString,
     view::CannotFocus,
-    Rect, Vec2, View,
+    views::{Panel, TextView},
+    Cursive, Rect, Vec2, View,
 };
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::{Duration, Instant};
 
 /// A view displaying an article
 pub struct ArticleView {
@@ -25,8 +44,47 @@ pub struct ArticleView {
 
     /// The size of the viewport
     viewport_size: Cell<Vec2>,
+
+    /// Are link previews shown when a link is selected?
+    link_preview_enabled: bool,
+    /// The preview of the current link
+    link_preview: Option<Panel<TextView>>,
+    /// The size of the link preview
+    link_preview_size: Vec2,
+
+    /// The number typed in so far, when the link numbers are displayed
+    link_hint: Option<String>,
+
+    /// The query of the search in the article, if there is one
+    search_query: Option<String>,
+    /// Is the query still being typed in?
+    search_input: bool,
+    /// The position and the text of every match of the query
+    search_matches: Vec<(Vec2, String)>,
+    /// The index of the selected match
+    current_match: usize,
+
+    /// The selected text, if text is being selected
+    selection: Option<Selection>,
+
+    /// The link that was clicked last and when, used for detecting double clicks
+    last_click: Option<(i32, Instant)>,
+
+    /// The ids of the visible elements that have been checked for links to prefetch
+    prefetched: RefCell<HashSet<i32>>,
+
+    /// The y-position of the header that was jumped to last. Headers close to the end of the
+    /// article can't be scrolled to the top of the viewport, so the next jump starts from here
+    current_header: Option<usize>,
 }
 
+/// The maximal width of a link preview
+const LINK_PREVIEW_WIDTH: usize = 60;
+/// The maximal height of a link preview
+const LINK_PREVIEW_HEIGHT: usize = 10;
+/// How much time can pass between the two clicks of a double click
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(500);
+
 impl ArticleView {
     /// Creates a new ArticleView with a given article as its content
     pub fn new(article: Article) -> Self {
@@ -36,6 +94,821 @@ impl ArticleView {
             last_size: Vec2::zero(),
             viewport_offset: Cell::new(0),
             viewport_size: Cell::new(Vec2::zero()),
+
+            link_preview_enabled: false,
+            link_preview: None,
+            link_preview_size: Vec2::zero(),
+
+            link_hint: None,
+
+            search_query: None,
+            search_input: false,
+            search_matches: Vec::new(),
+            current_match: 0,
+
+            selection: None,
+
+            last_click: None,
+
+            prefetched: RefCell::new(HashSet::new()),
+
+            current_header: None,
+        }
+    }
+
+    /// Displays a preview for the link with the given id. If the link isn't selected anymore or
+    /// link previews are disabled, the preview is discarded
+    pub fn set_link_preview(&mut self, id: i32, preview: StyledString) {
+        if !self.link_preview_enabled || self.content.current_link() != Some(id) {
+            log::debug!("discarding the preview for the link '{}'", id);
+            return;
+        }
+
+        self.link_preview =
+            Some(Panel::new(TextView::new(preview)).title(t("article.preview_title")));
+    }
+
+    /// Enables or disables the link previews. Returns the callback fetching the preview of the
+    /// current link if they were enabled
+    fn toggle_link_preview(&mut self) -> EventResult {
+        self.link_preview_enabled = !self.link_preview_enabled;
+        log::info!(
+            "link previews are now {}",
+            if self.link_preview_enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+
+        if !self.link_preview_enabled {
+            self.link_preview = None;
+            return EventResult::Consumed(None);
+        }
+
+        self.request_link_preview()
+    }
+
+    /// Returns the callback fetching the preview of the current link. If the current link has no
+    /// target, no callback is returned
+    fn request_link_preview(&mut self) -> EventResult {
+        self.link_preview = None;
+
+        let id = match self.content.current_link() {
+            Some(id) => id,
+            None => return EventResult::Consumed(None),
+        };
+        // references are shown when they are opened, they have no preview
+        let target = match self
+            .content
+            .element_by_id(Some(id))
+            .filter(|element| element.get_attribute("type") != Some("reference"))
+            .and_then(|element| element.get_attribute("target"))
+        {
+            Some(target) => resolve_link(self.content.base_url(), target),
+            None => return EventResult::Consumed(None),
+        };
+
+        EventResult::with_cb(move |s| on_link_preview(s, id, target.clone()))
+    }
+
+    /// Handles an event while the link numbers are displayed. Typing a number selects the link
+    /// with that number, as soon as no other number starts with it
+    fn on_link_hint_event(&mut self, event: Event) -> EventResult {
+        let link_hint = match self.link_hint {
+            Some(ref mut link_hint) => link_hint,
+            None => return EventResult::Ignored,
+        };
+
+        match event {
+            Event::Char(digit) if digit.is_ascii_digit() => link_hint.push(digit),
+            Event::Key(Key::Backspace) => {
+                link_hint.pop();
+                return EventResult::Consumed(None);
+            }
+            Event::Key(Key::Enter) => {}
+            Event::Key(Key::Esc) => {
+                log::debug!("hiding the link numbers");
+                self.link_hint = None;
+                return EventResult::Consumed(None);
+            }
+            _ => return EventResult::Consumed(None),
+        }
+
+        let number = link_hint.parse::<usize>().unwrap_or_default();
+        let links_count = self.content.link_positions().len();
+
+        // wait for more digits if there are links whose number starts with this one
+        if event != Event::Key(Key::Enter) && number != 0 && number * 10 <= links_count {
+            return EventResult::Consumed(None);
+        }
+        self.link_hint = None;
+
+        if number == 0 || number > links_count {
+            log::info!("there is no link with the number '{}'", number);
+            return EventResult::Consumed(None);
+        }
+
+        log::info!("selecting the link with the number '{}'", number);
+        self.content.set_current_link_by_index(number - 1);
+        self.scroll_to_current_link();
+        EventResult::Consumed(None)
+    }
+
+    /// Returns the callback opening the current link in a new tab or a split. References and links
+    /// without a target can't be opened this way
+    fn open_link_with_mode(&self, mode: LinkOpenMode) -> EventResult {
+        let target = match self
+            .content
+            .element_by_id(self.content.current_link())
+            .filter(|element| element.get_attribute("type") != Some("reference"))
+            .and_then(|element| element.get_attribute("target"))
+        {
+            Some(target) => resolve_link(self.content.base_url(), target),
+            None => return EventResult::Consumed(None),
+        };
+
+        EventResult::with_cb(move |s| on_link_submit_with_mode(s, target.clone(), mode))
+    }
+
+    /// Returns the callback opening the current link. References are displayed instead
+    fn open_current_link(&self) -> EventResult {
+        log::info!("opening the link");
+
+        // get current link and retrieve the ArticleElement linked to it
+        let current_link = self.content.current_link();
+        log::debug!("current link is '{:?}'", current_link);
+
+        if let Some(element) = self.content.element_by_id(current_link) {
+            log::debug!("found the element");
+
+            if let Some(result) = self.show_reference(element) {
+                return result;
+            }
+
+            // get target link from the article element
+            let target = match element.get_attribute("target") {
+                Some(t) => resolve_link(self.content.base_url(), t),
+                None => return EventResult::Ignored,
+            };
+            log::info!("target article is '{}'", target);
+
+            // return the callback
+            log::debug!("returning the callback to open the link");
+            return EventResult::Consumed(Some(Callback::from_fn(move |s| {
+                on_link_submit(s, target.clone())
+            })));
+        }
+
+        EventResult::Ignored
+    }
+
+    /// Selects the first link of the article and opens it, without navigating to it first
+    fn open_first_link(&mut self) -> EventResult {
+        if !self.content.select_first_link() {
+            log::info!("the article has no links to open");
+            return EventResult::with_cb(|s| display_message(s, t("article.no_links")));
+        }
+        // the link stays visible when the article isn't replaced
+        self.scroll_to_current_link();
+        self.open_current_link()
+    }
+
+    /// Copies the full url of the selected link, without opening it
+    fn copy_link(&self) -> EventResult {
+        let article = self.content.article();
+        let base_url = article
+            .base_url()
+            .map(str::to_string)
+            .unwrap_or_else(|| current_wiki().base_url);
+        let url = match self
+            .content
+            .element_by_id(self.content.current_link())
+            .filter(|element| element.get_attribute("type") != Some("reference"))
+            .and_then(|element| element.get_attribute("target"))
+            .and_then(|target| link_url(&base_url, article_url(article).as_deref(), target))
+        {
+            Some(url) => url,
+            None => return EventResult::Consumed(None),
+        };
+
+        EventResult::with_cb(move |s| on_copy_link(s, url.clone()))
+    }
+
+    /// Searches for the text of the selected link
+    fn search_link_text(&self) -> EventResult {
+        match self.content.current_link_text() {
+            Some(text) => EventResult::with_cb(move |siv| on_search(siv, text.clone())),
+            None => EventResult::Consumed(None),
+        }
+    }
+
+    /// Returns the callback showing the reference of an element, if the element is the marker of
+    /// a reference
+    fn show_reference(&self, element: &ArticleElement) -> Option<EventResult> {
+        if element.get_attribute("type") != Some("reference") {
+            return None;
+        }
+
+        let marker = element.content().to_string();
+        let text = element
+            .get_attribute("target")
+            .and_then(|anchor| self.content.article().reference(anchor))
+            .map(str::to_string)
+            .unwrap_or_else(|| t("article.reference_missing"));
+        Some(EventResult::with_cb(move |siv| {
+            on_reference_show(siv, marker.clone(), text.clone())
+        }))
+    }
+
+    /// Checks if a click on the link with the given id opens it. When links are opened with a
+    /// double click, the first click only selects the link
+    fn is_link_opened_by_click(&mut self, id: i32) -> bool {
+        if !CONFIG.settings.links.double_click {
+            return true;
+        }
+
+        let is_double_click = matches!(
+            self.last_click,
+            Some((last_id, time)) if last_id == id && time.elapsed() <= DOUBLE_CLICK_TIME
+        );
+        self.last_click = if is_double_click {
+            None
+        } else {
+            Some((id, Instant::now()))
+        };
+        is_double_click
+    }
+
+    /// Scrolls the viewport so that the current link is visible
+    fn scroll_to_current_link(&mut self) {
+        if let Some(link_pos) = self.content.current_link_pos() {
+            self.scroll_to_line(link_pos.y);
+        }
+    }
+
+    /// Returns the lines inside of the viewport
+    fn visible_lines(&self) -> Range<usize> {
+        let viewport_top = self.viewport_offset.get();
+        viewport_top..viewport_top.saturating_add(self.viewport_size.get().y)
+    }
+
+    /// Registers only the links inside of the viewport, when the article has more links than
+    /// configured. Nothing happens before the viewport is known
+    fn register_visible_links(&mut self) {
+        if self.viewport_size.get().y > 0 {
+            self.content.register_links_in(self.visible_lines());
+        }
+    }
+
+    /// Scrolls to the next links below or above the viewport when only the visible links are
+    /// registered and the selection is at the last or the first of them. The closest of the new
+    /// links is selected. None is returned when the selection can move without scrolling
+    fn page_links(&mut self, forward: bool) -> Option<EventResult> {
+        if CONFIG.settings.links.stay_on_line {
+            return None;
+        }
+        let y = self
+            .content
+            .next_unregistered_line(&self.visible_lines(), forward)?;
+        log::debug!("scrolling to the links in line '{}'", y);
+
+        if forward {
+            self.scroll_line_to_top(y);
+            self.content.select_first_link_in(y..y + 1);
+        } else {
+            self.scroll_to_line(y);
+            let links_count = self.content.link_positions().len();
+            self.content
+                .set_current_link_by_index(links_count.saturating_sub(1));
+        }
+        Some(EventResult::Consumed(None))
+    }
+
+    /// Scrolls the viewport to the top or the bottom of the article and selects the first or the
+    /// last link, so that the selection stays inside of the viewport
+    fn scroll_to_edge(&mut self, direction: Absolute) -> EventResult {
+        match direction {
+            Absolute::Up => {
+                self.viewport_offset.set(0);
+                self.register_visible_links();
+                let links_count = self.content.link_positions().len();
+                if CONFIG.features.links && links_count > 0 {
+                    self.content.set_current_link_by_index(0);
+                }
+            }
+            Absolute::Down => {
+                let lines_count = self.content.get_rendered_lines().count();
+                self.viewport_offset
+                    .set(lines_count.saturating_sub(self.viewport_size.get().y));
+                self.register_visible_links();
+                let links_count = self.content.link_positions().len();
+                if CONFIG.features.links && links_count > 0 {
+                    self.content.set_current_link_by_index(links_count - 1);
+                }
+            }
+            _ => return EventResult::Ignored,
+        }
+        EventResult::Consumed(None)
+    }
+
+    /// Scrolls the viewport to the next or the previous header and selects the first link of its
+    /// section
+    fn jump_to_header(&mut self, forward: bool) -> EventResult {
+        let viewport_top = self.viewport_offset.get();
+        let viewport_bottom = viewport_top.saturating_add(self.viewport_size.get().y);
+        let y = match self.current_header {
+            Some(y) if (viewport_top..viewport_bottom).contains(&y) => y,
+            _ => viewport_top,
+        };
+
+        let header_y = match self.content.next_header_y(y, forward) {
+            Some(header_y) => header_y,
+            None => {
+                log::debug!("there is no header to jump to");
+                return EventResult::Consumed(None);
+            }
+        };
+        log::info!("jumping to the header in line '{}'", header_y);
+        self.scroll_line_to_top(header_y);
+        self.current_header = Some(header_y);
+
+        if CONFIG.features.links {
+            let section_end = self
+                .content
+                .next_header_y(header_y, true)
+                .unwrap_or(usize::MAX);
+            self.content.select_first_link_in(header_y..section_end);
+        }
+        EventResult::Consumed(None)
+    }
+
+    /// Scrolls the viewport so that the line at a given y position is at its top
+    fn scroll_line_to_top(&mut self, y: usize) {
+        let viewport_top = self.viewport_offset.get();
+        if y < viewport_top {
+            self.scroll(Absolute::Up, viewport_top - y);
+        } else {
+            self.scroll(Absolute::Down, y - viewport_top);
+        }
+    }
+
+    /// Scrolls the viewport so that the line at a given y position is visible
+    fn scroll_to_line(&mut self, y: usize) {
+        let viewport_top = self.viewport_offset.get();
+        let viewport_bottom = viewport_top.saturating_add(self.viewport_size.get().y);
+
+        if y < viewport_top {
+            self.scroll(Absolute::Up, viewport_top - y);
+        } else if y >= viewport_bottom {
+            self.scroll(Absolute::Down, y + 1 - viewport_bottom);
+        }
+    }
+
+    /// Handles an event while the query of the search is typed in. The matches are updated with
+    /// every change of the query
+    fn on_search_input_event(&mut self, event: Event) -> EventResult {
+        let search_query = match self.search_query {
+            Some(ref mut search_query) => search_query,
+            None => return EventResult::Ignored,
+        };
+
+        match event {
+            Event::Char(char) => search_query.push(char),
+            Event::Key(Key::Backspace) => {
+                search_query.pop();
+            }
+            Event::Key(Key::Enter) => {
+                self.search_input = false;
+                if search_query.is_empty() {
+                    self.clear_search();
+                }
+                return EventResult::Consumed(None);
+            }
+            Event::Key(Key::Esc) => {
+                self.clear_search();
+                return EventResult::Consumed(None);
+            }
+            _ => return EventResult::Consumed(None),
+        }
+
+        self.update_search_matches();
+
+        // select the first match that isn't above the viewport
+        let viewport_top = self.viewport_offset.get();
+        self.current_match = self
+            .search_matches
+            .iter()
+            .position(|(pos, _)| pos.y >= viewport_top)
+            .unwrap_or(0);
+        self.scroll_to_current_match();
+
+        EventResult::Consumed(None)
+    }
+
+    /// Searches the rendered lines for the query again. The selected match is kept in bounds
+    fn update_search_matches(&mut self) {
+        self.search_matches = match self.search_query {
+            Some(ref search_query) => self.content.find(search_query),
+            None => Vec::new(),
+        };
+        self.current_match = self
+            .current_match
+            .min(self.search_matches.len().saturating_sub(1));
+    }
+
+    /// Selects the next match, or the previous one if `forward` is false. Wraps around at either
+    /// end of the article
+    fn select_match(&mut self, forward: bool) -> EventResult {
+        let matches_count = self.search_matches.len();
+        if matches_count == 0 {
+            return EventResult::Consumed(None);
+        }
+
+        self.current_match = if forward {
+            (self.current_match + 1) % matches_count
+        } else {
+            (self.current_match + matches_count - 1) % matches_count
+        };
+        log::debug!("selecting the match '{}'", self.current_match);
+
+        self.scroll_to_current_match();
+        EventResult::Consumed(None)
+    }
+
+    /// Scrolls the viewport so that the selected match is visible
+    fn scroll_to_current_match(&mut self) {
+        if let Some((pos, _)) = self.search_matches.get(self.current_match) {
+            let y = pos.y;
+            self.scroll_to_line(y);
+        }
+    }
+
+    /// Stops the search and removes the highlights of its matches
+    fn clear_search(&mut self) {
+        log::debug!("clearing the search");
+        self.search_query = None;
+        self.search_input = false;
+        self.search_matches.clear();
+        self.current_match = 0;
+    }
+
+    /// Is the view currently waiting for text input?
+    fn is_typing(&self) -> bool {
+        self.link_hint.is_some() || self.search_input || self.selection.is_some()
+    }
+
+    /// Starts selecting text at the selected link, or at the top of the viewport if the link isn't
+    /// visible
+    fn start_selection(&mut self) -> EventResult {
+        let viewport_top = self.viewport_offset.get();
+        let viewport_bottom = viewport_top.saturating_add(self.viewport_size.get().y);
+        let position = match self.content.current_link_pos() {
+            Some(link_pos) if (viewport_top..viewport_bottom).contains(&link_pos.y) => link_pos,
+            _ => Vec2::new(0, viewport_top),
+        };
+
+        log::debug!("starting a selection at '({},{})'", position.x, position.y);
+        self.selection = Some(Selection::new(position));
+        EventResult::Consumed(None)
+    }
+
+    /// Handles an event while text is selected. The movement keys move the end of the selection,
+    /// Enter copies the selected text, search_selection searches for it and Esc cancels the
+    /// selection
+    fn on_selection_event(&mut self, event: Event) -> EventResult {
+        let mut selection = match self.selection {
+            Some(selection) => selection,
+            None => return EventResult::Ignored,
+        };
+
+        let lines_count = self.content.get_rendered_lines().count();
+        let cursor = selection.cursor();
+        let last_x = |y: usize| self.content.line_length(y).saturating_sub(1);
+        let cursor = match event {
+            Event::Key(Key::Left) if cursor.x > 0 => Vec2::new(cursor.x - 1, cursor.y),
+            Event::Key(Key::Left) if cursor.y > 0 => Vec2::new(last_x(cursor.y - 1), cursor.y - 1),
+            Event::Key(Key::Right) if cursor.x < last_x(cursor.y) => {
+                Vec2::new(cursor.x + 1, cursor.y)
+            }
+            Event::Key(Key::Right) if cursor.y + 1 < lines_count => Vec2::new(0, cursor.y + 1),
+            Event::Key(Key::Up) if cursor.y > 0 => {
+                Vec2::new(cursor.x.min(last_x(cursor.y - 1)), cursor.y - 1)
+            }
+            Event::Key(Key::Down) if cursor.y + 1 < lines_count => {
+                Vec2::new(cursor.x.min(last_x(cursor.y + 1)), cursor.y + 1)
+            }
+            Event::Key(Key::Home) => Vec2::new(0, cursor.y),
+            Event::Key(Key::End) => Vec2::new(last_x(cursor.y), cursor.y),
+            Event::Key(Key::Enter) => {
+                let text = self.content.selected_text(&selection);
+                self.selection = None;
+                return EventResult::with_cb(move |siv| on_copy_selection(siv, text.clone()));
+            }
+            event if event == CONFIG.keybindings.search_selection => {
+                let text = self.content.selected_text(&selection);
+                self.selection = None;
+                return EventResult::with_cb(move |siv| on_search(siv, text.clone()));
+            }
+            Event::Key(Key::Esc) => {
+                log::debug!("cancelling the selection");
+                self.selection = None;
+                return EventResult::Consumed(None);
+            }
+            _ => return EventResult::Consumed(None),
+        };
+
+        selection.set_cursor(cursor);
+        self.selection = Some(selection);
+        self.scroll_to_line(cursor.y);
+        EventResult::Consumed(None)
+    }
+
+    /// Bookmarks the displayed article or removes its bookmark. Articles without an id can't be
+    /// bookmarked
+    fn toggle_bookmark(&self) -> EventResult {
+        let page_id = match self.content.page_id() {
+            Some(page_id) => page_id,
+            None => {
+                log::warn!("the article has no id, it can't be bookmarked");
+                return EventResult::Ignored;
+            }
+        };
+
+        let bookmark = Bookmark::new(
+            page_id,
+            self.content.title().unwrap_or_default().to_string(),
+            self.content
+                .base_url()
+                .map(|base_url| base_url.to_string())
+                .unwrap_or_else(|| current_wiki().base_url),
+        );
+        EventResult::with_cb(move |siv| on_bookmark_toggle(siv, bookmark.clone()))
+    }
+
+    /// Returns a callback calling a given function with the url of the article. If the url is
+    /// unknown, the event is ignored
+    fn with_article_url(&self, callback: fn(&mut Cursive, String)) -> EventResult {
+        match article_url(self.content.article()) {
+            Some(url) => EventResult::with_cb(move |siv| callback(siv, url.clone())),
+            None => {
+                log::warn!("the url of the article is unknown");
+                EventResult::Ignored
+            }
+        }
+    }
+
+    /// Handles an event. This doesn't include the link previews
+    fn handle_event(&mut self, event: Event) -> EventResult {
+        if self.link_hint.is_some() {
+            return self.on_link_hint_event(event);
+        }
+
+        if self.search_input {
+            return self.on_search_input_event(event);
+        }
+
+        if self.selection.is_some() {
+            return self.on_selection_event(event);
+        }
+
+        if event == CONFIG.keybindings.select_text {
+            return self.start_selection();
+        }
+
+        if event == CONFIG.keybindings.search_article {
+            log::debug!("starting a search in the article");
+            self.clear_search();
+            self.search_query = Some(String::new());
+            self.search_input = true;
+            return EventResult::Consumed(None);
+        }
+
+        if self.search_query.is_some() {
+            if event == CONFIG.keybindings.search_next {
+                return self.select_match(true);
+            }
+            if event == CONFIG.keybindings.search_previous {
+                return self.select_match(false);
+            }
+            if event == Event::Key(Key::Esc) {
+                self.clear_search();
+                return EventResult::Consumed(None);
+            }
+        }
+
+        if event == CONFIG.keybindings.link_hints && CONFIG.features.links {
+            log::debug!("displaying the link numbers");
+            self.link_hint = Some(String::new());
+            return EventResult::Consumed(None);
+        }
+
+        if event == CONFIG.keybindings.open_in_new_tab && CONFIG.features.links {
+            return self.open_link_with_mode(LinkOpenMode::TAB);
+        }
+
+        if event == CONFIG.keybindings.open_in_split && CONFIG.features.links {
+            return self.open_link_with_mode(LinkOpenMode::SPLIT);
+        }
+
+        if event == CONFIG.keybindings.open_in_background && CONFIG.features.links {
+            return self.open_link_with_mode(LinkOpenMode::BACKGROUND);
+        }
+
+        if event == CONFIG.keybindings.toggle_bookmark {
+            return self.toggle_bookmark();
+        }
+
+        if event == CONFIG.keybindings.copy_link && CONFIG.features.links {
+            return self.copy_link();
+        }
+
+        if event == CONFIG.keybindings.open_first_link && CONFIG.features.links {
+            return self.open_first_link();
+        }
+
+        if event == CONFIG.keybindings.search_selection && CONFIG.features.links {
+            return self.search_link_text();
+        }
+
+        if event == CONFIG.keybindings.copy_url {
+            return self.with_article_url(on_copy_url);
+        }
+
+        if event == CONFIG.keybindings.open_in_browser {
+            return self.with_article_url(on_open_in_browser);
+        }
+
+        if event == CONFIG.keybindings.export_article {
+            let title = self.content.title().unwrap_or_default().to_string();
+            let content = self.content.export(CONFIG.settings.export.format);
+            return EventResult::with_cb(move |siv| {
+                on_article_export(siv, title.clone(), content.clone())
+            });
+        }
+
+        if event == CONFIG.keybindings.toggle_line_width {
+            self.content.toggle_max_line_width();
+            // the lines are computed again with the next layout
+            self.last_size = Vec2::zero();
+            return EventResult::Consumed(None);
+        }
+
+        if event == CONFIG.keybindings.toggle_spacing {
+            self.content.toggle_spacing();
+            // the lines are computed again with the next layout
+            self.last_size = Vec2::zero();
+            return EventResult::Consumed(None);
+        }
+
+        if event == CONFIG.keybindings.next_header {
+            return self.jump_to_header(true);
+        }
+
+        if event == CONFIG.keybindings.previous_header {
+            return self.jump_to_header(false);
+        }
+
+        if event == CONFIG.keybindings.references {
+            let references = self.content.article().references().to_vec();
+            return EventResult::with_cb(move |siv| on_references_show(siv, references.clone()));
+        }
+
+        if event == CONFIG.keybindings.see_also {
+            let article = self.content.article();
+            let base_url = article
+                .base_url()
+                .map(str::to_string)
+                .unwrap_or_else(|| current_wiki().base_url);
+            let links = article.see_also_links().to_vec();
+            return EventResult::with_cb(move |siv| {
+                on_see_also_show(siv, base_url.clone(), links.clone())
+            });
+        }
+
+        if event == CONFIG.keybindings.article_info {
+            let info = article_info(self.content.article());
+            return EventResult::with_cb(move |siv| on_article_info(siv, info.clone()));
+        }
+
+        // with the vim motions, the RootLayout waits for 'gg' and sends Home instead
+        if event == CONFIG.keybindings.scroll_top && !CONFIG.features.vim_motions
+            || event == Event::Key(Key::Home)
+        {
+            return self.scroll_to_edge(Absolute::Up);
+        }
+
+        if event == CONFIG.keybindings.scroll_bottom || event == Event::Key(Key::End) {
+            return self.scroll_to_edge(Absolute::Down);
+        }
+
+        // scroll by half of the viewport or the whole viewport
+        let page_height = self.viewport_size.get().y.max(1);
+        if event == CONFIG.keybindings.half_page_up {
+            return self.scroll(Absolute::Up, (page_height / 2).max(1));
+        }
+        if event == CONFIG.keybindings.half_page_down {
+            return self.scroll(Absolute::Down, (page_height / 2).max(1));
+        }
+        if event == CONFIG.keybindings.page_up {
+            return self.scroll(Absolute::Up, page_height);
+        }
+        if event == CONFIG.keybindings.page_down {
+            return self.scroll(Absolute::Down, page_height);
+        }
+
+        match event {
+            Event::Key(Key::Up) => self.scroll(Absolute::Up, CONFIG.settings.scroll_lines),
+            Event::Key(Key::Down) => self.scroll(Absolute::Down, CONFIG.settings.scroll_lines),
+            Event::Mouse {
+                event: MouseEvent::WheelUp,
+                ..
+            } => self.scroll(Absolute::Up, CONFIG.settings.wheel_lines),
+            Event::Mouse {
+                event: MouseEvent::WheelDown,
+                ..
+            } => self.scroll(Absolute::Down, CONFIG.settings.wheel_lines),
+            Event::Key(Key::Left) if CONFIG.features.links => {
+                if let Some(result) = self.page_links(false) {
+                    return result;
+                }
+                self.content
+                    .move_selected_link(Absolute::Left, CONFIG.settings.links.step);
+                // if the current link is outside of the viewport, then scroll. When the selection
+                // wraps around, the link can be on either side of the viewport
+                self.scroll_to_current_link();
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Right) if CONFIG.features.links => {
+                if let Some(result) = self.page_links(true) {
+                    return result;
+                }
+                self.content
+                    .move_selected_link(Absolute::Right, CONFIG.settings.links.step);
+                self.scroll_to_current_link();
+                EventResult::Consumed(None)
+            }
+            Event::Key(Key::Enter) if CONFIG.features.links => self.open_current_link(),
+            Event::Mouse {
+                event: MouseEvent::Release(MouseButton::Left),
+                position,
+                offset,
+            } => {
+                // get what element was clicked
+                if let Some(element) = self.content.get_element_at_position(
+                    position
+                        .saturating_sub(offset)
+                        .saturating_sub((self.content.line_offset(self.last_size.x), 0)),
+                ) {
+                    match element.get_attribute("type") {
+                        // if it's a reference, select it and show its text
+                        Some("reference") if CONFIG.features.links => {
+                            let element_id = *element.id();
+                            let result = self.show_reference(element);
+                            self.content.set_current_link(element_id);
+                            if !self.is_link_opened_by_click(element_id) {
+                                return EventResult::Consumed(None);
+                            }
+                            return result.unwrap_or(EventResult::Consumed(None));
+                        }
+
+                        // if it's a link, check if it's valid and then open it
+                        Some("link") if CONFIG.features.links => {
+                            log::info!("detected a mouse event over the link '{}'", element.id());
+                            let target = match element.get_attribute("target") {
+                                Some(t) => resolve_link(self.content.base_url(), t),
+                                None => {
+                                    log::warn!("the link '{}' is not valid!", element.id());
+                                    return EventResult::Consumed(None);
+                                }
+                            };
+                            log::info!("target article is '{}'", target);
+
+                            // select this link
+                            let element_id = *element.id();
+                            self.content.set_current_link(element_id);
+                            if !self.is_link_opened_by_click(element_id) {
+                                return EventResult::Consumed(None);
+                            }
+
+                            // return the callback
+                            return EventResult::Consumed(Some(Callback::from_fn(move |s| {
+                                on_link_submit(s, target.clone())
+                            })));
+                        }
+
+                        // if it's a button, don't do anything for now
+                        Some("button") => {
+                            log::error!("wow, you've found a secret!")
+                        }
+
+                        // this element doesn't support mouse clicking
+                        _ => {}
+                    }
+                }
+                EventResult::Consumed(None)
+            }
+            _ => EventResult::Ignored,
         }
     }
 
@@ -55,6 +928,7 @@ impl ArticleView {
         if !CONFIG.features.links {
             return EventResult::Consumed(None);
         }
+        self.register_visible_links();
 
         // get the position of the current link and the top of the viewport
         let link_pos = self.content.current_link_pos().unwrap_or_default();
@@ -66,7 +940,8 @@ impl ArticleView {
         if link_pos.y <= viewport_top {
             let move_amount = viewport_top.saturating_sub(link_pos.y);
             log::debug!("moving the link down by '{}'", move_amount);
-            self.content.move_selected_link(Absolute::Down, move_amount);
+            self.content
+                .move_selected_link_without_wrap(Absolute::Down, move_amount);
 
             return EventResult::Consumed(None);
         }
@@ -79,7 +954,8 @@ impl ArticleView {
         if link_pos.y >= viewport_bottom {
             let move_amount = link_pos.y.saturating_sub(viewport_bottom);
             log::debug!("moving the link up by '{}'", move_amount);
-            self.content.move_selected_link(Absolute::Up, move_amount);
+            self.content
+                .move_selected_link_without_wrap(Absolute::Up, move_amount);
 
             return EventResult::Consumed(None);
         }
@@ -87,32 +963,64 @@ impl ArticleView {
         EventResult::Consumed(None)
     }
 
-    /// Select a header by moving the viewport to its coordinates
-    pub fn select_header(&mut self, index: usize) {
-        if !CONFIG.features.toc {
+    /// Prefetches the articles of the links in the given lines that haven't been prefetched yet
+    fn prefetch_visible_links(&self, top: usize, height: usize) {
+        let mut prefetched = self.prefetched.borrow_mut();
+        let targets = self
+            .content
+            .get_rendered_lines()
+            .skip(top)
+            .take(height)
+            .flatten()
+            .filter(|element| prefetched.insert(element.id))
+            .filter_map(|element| self.content.element_by_id(Some(element.id)))
+            .filter(|element| element.get_attribute("type") == Some("link"))
+            .filter_map(|element| element.get_attribute("target"))
+            .map(|target| resolve_link(self.content.base_url(), target))
+            .collect::<Vec<String>>();
+
+        if !targets.is_empty() {
+            log::debug!("prefetching '{}' links", targets.len());
+            prefetch_links(targets);
+        }
+    }
+
+    /// Returns the scroll offset and the selected link of the view
+    pub fn position(&self) -> ArticlePosition {
+        ArticlePosition {
+            offset: self.viewport_offset.get(),
+            link: self.content.current_link(),
+        }
+    }
+
+    /// Adds the images downloaded for a given article, if it's the displayed one
+    pub fn add_images(&mut self, article: &Article, images: &[(i32, ArticleImage)]) {
+        if self.content.article() != article {
             return;
         }
-        log::info!("selecting the header number '{}'", index);
+        self.content.add_images(images);
+        // the lines are computed again with the next layout
+        self.last_size = Vec2::zero();
+    }
 
-        // get the position of the header and the viewport top and bottom
-        let header_pos = self
-            .content
-            .header_y_pos(index)
-            .unwrap_or_else(|| self.viewport_offset.get());
-        let viewport_top = self.viewport_offset.get();
+    /// Selects the link with a given id
+    pub fn select_link(&mut self, id: i32) {
+        self.content.set_current_link(id);
+    }
 
-        // if the header is above the viewport, then get the difference between the header and the
-        // viewport and scroll up by that amount
-        if header_pos < viewport_top {
-            let move_amount = viewport_top.saturating_sub(header_pos);
-            self.scroll(Absolute::Up, move_amount);
+    /// Scrolls the viewport to the header with a given id, so that the header is at its top
+    pub fn scroll_to_header(&mut self, id: i32) {
+        if !CONFIG.features.toc {
             return;
         }
+        log::info!("scrolling to the header '{}'", id);
 
-        // if the header is below the viewport, then get the difference between the header and the
-        // viewport and scroll down by that amount
-        let move_amount = header_pos.saturating_sub(viewport_top);
-        self.scroll(Absolute::Down, move_amount);
+        // get the position of the header and scroll it to the top of the viewport
+        let header_pos = self
+            .content
+            .header_y_pos(id)
+            .unwrap_or_else(|| self.viewport_offset.get());
+        self.scroll_line_to_top(header_pos);
     }
 }
 
@@ -126,6 +1034,21 @@ impl View for ArticleView {
         self.viewport_offset.set(miny);
         self.viewport_size.set(printer.output_size);
 
+        // prefetch the articles of the links that just became visible
+        if CONFIG.features.prefetch {
+            self.prefetch_visible_links(miny, printer.output_size.y);
+        }
+
+        // move the lines to the right if they are aligned
+        let printer = &printer.offset((self.content.line_offset(self.last_size.x), 0));
+
+        // the selected link uses its own color when the article view has one
+        let theme = CONFIG.theme.article_view_for(self.content.base_url());
+        let current_link_color = theme.map_or(CONFIG.theme.highlight, |theme| theme.current_link);
+        let selection_color = ColorStyle::back(
+            theme.map_or(CONFIG.theme.highlight_inactive, |theme| theme.selection),
+        );
+
         // go through every line and print it to the screen
         for (y, line) in self
             .content
@@ -139,7 +1062,7 @@ impl View for ArticleView {
                 let mut style = element.style;
 
                 if Some(element.id) == self.content.current_link() {
-                    style = style.combine(CONFIG.theme.highlight);
+                    style = style.combine(current_link_color);
                 }
 
                 printer.with_style(style, |printer| {
@@ -147,10 +1070,111 @@ impl View for ArticleView {
                     x += element.width;
                 });
             }
+
+            // draw the selected characters of the line on top of it
+            if let Some(ref selection) = self.selection {
+                let chars = line.iter().flat_map(|element| element.content.chars());
+                for (x, char) in chars.enumerate() {
+                    if selection.contains(Vec2::new(x, y)) {
+                        printer.with_style(selection_color, |printer| {
+                            printer.print((x, y), &char.to_string())
+                        });
+                    }
+                }
+            }
+        }
+
+        // highlight the matches of the search
+        for (i, (pos, text)) in self.search_matches.iter().enumerate() {
+            if pos.y < miny || pos.y > maxy {
+                continue;
+            }
+            let color = if i == self.current_match {
+                ColorStyle::highlight()
+            } else {
+                ColorStyle::highlight_inactive()
+            };
+            printer.with_color(color, |printer| printer.print(*pos, text));
+        }
+
+        // draw the numbers of the visible links that start with the number typed in so far
+        if let Some(ref link_hint) = self.link_hint {
+            for (i, link_pos) in self.content.link_positions().into_iter().enumerate() {
+                let number = (i + 1).to_string();
+                if link_pos.y < miny || link_pos.y > maxy || !number.starts_with(link_hint.as_str())
+                {
+                    continue;
+                }
+                printer.with_color(ColorStyle::highlight(), |printer| {
+                    printer.print(link_pos, &number)
+                });
+            }
+        }
+
+        // draw the link preview below the current link, or above it if there isn't enough space
+        if let (Some(ref preview), Some(link_pos)) =
+            (&self.link_preview, self.content.current_link_pos())
+        {
+            let preview_y = if link_pos.y + 1 + self.link_preview_size.y <= maxy {
+                link_pos.y + 1
+            } else {
+                link_pos.y.saturating_sub(self.link_preview_size.y)
+            };
+
+            let preview_x = link_pos
+                .x
+                .min(printer.size.x.saturating_sub(self.link_preview_size.x));
+
+            let printer = printer
+                .offset((preview_x, preview_y))
+                .cropped(self.link_preview_size);
+
+            // clear the area behind the preview
+            for y in 0..printer.size.y {
+                printer.print_hline((0, y), printer.size.x, " ");
+            }
+            preview.draw(&printer);
+        }
+
+        // draw how far the article has been read in the bottom right corner
+        if CONFIG.settings.article.scroll_progress {
+            let progress = format!(
+                " {}% ",
+                self.content.scroll_progress(miny, printer.output_size.y)
+            );
+            let x = printer.output_size.x.saturating_sub(progress.len());
+            let y = maxy.saturating_sub(1).max(miny);
+            printer.with_color(ColorStyle::highlight_inactive(), |printer| {
+                printer.print((x, y), &progress)
+            });
+        }
+
+        // draw the search bar in the last visible line
+        if let Some(ref search_query) = self.search_query {
+            let status = match self.search_matches.len() {
+                0 if search_query.is_empty() => String::new(),
+                0 => " [no matches]".to_string(),
+                count => format!(" [{}/{}]", self.current_match + 1, count),
+            };
+            let y = maxy.saturating_sub(1).max(miny);
+
+            printer.print_hline((0, y), printer.size.x, " ");
+            printer.print((0, y), &format!("/{}{}", search_query, status));
         }
     }
 
     fn layout(&mut self, size: Vec2) {
+        // the link preview gets its own layout, the article lines don't have to be recalculated
+        if let Some(ref mut preview) = self.link_preview {
+            let width = size.x.min(LINK_PREVIEW_WIDTH);
+            let height = preview
+                .required_size(Vec2::new(width, LINK_PREVIEW_HEIGHT))
+                .y
+                .min(LINK_PREVIEW_HEIGHT);
+            self.link_preview_size = Vec2::new(width, height);
+            preview.layout(self.link_preview_size);
+        }
+
         // is this the same size as before? stop recalculating things!
         if self.last_size == size {
             return;
@@ -160,6 +1184,11 @@ impl View for ArticleView {
         // save the new size and compute the lines
         self.last_size = size;
         self.content.compute_lines(size);
+
+        // the positions of the matches have changed with the lines
+        if self.search_query.is_some() {
+            self.update_search_matches();
+        }
     }
 
     fn required_size(&mut self, constraint: Vec2) -> Vec2 {
@@ -186,128 +1215,23 @@ impl View for ArticleView {
     }
 
     fn on_event(&mut self, event: Event) -> EventResult {
-        match event {
-            Event::Key(Key::Up) => self.scroll(Absolute::Up, 1),
-            Event::Key(Key::Down) => self.scroll(Absolute::Down, 1),
-            Event::Key(Key::Left) if CONFIG.features.links => {
-                self.content.move_selected_link(Absolute::Left, 1);
-                // if the current link is outside of the viewport, then scroll
-                // get the current links position
-                let current_link_pos = self
-                    .content
-                    .current_link_pos()
-                    .unwrap_or_else(|| (0, 0).into());
-
-                // we've moved the link to the left, so we only need to check if the link is above
-                // the viewport
-                let viewport_top = self.viewport_offset.get();
-                if current_link_pos.y <= viewport_top {
-                    // so the link is below the viewport... great...
-                    // calculate how much below the viewport the link is
-                    let move_amount = viewport_top.saturating_sub(current_link_pos.y);
-
-                    // then scroll that amount
-                    self.scroll(Absolute::Up, move_amount);
-                }
-                EventResult::Consumed(None)
-            }
-            Event::Key(Key::Right) if CONFIG.features.links => {
-                self.content.move_selected_link(Absolute::Right, 1);
-                // if the current link is outside of the viewport, then scroll
-                // get the current links position
-                let current_link_pos = self
-                    .content
-                    .current_link_pos()
-                    .unwrap_or_else(|| (0, 0).into());
-
-                // we've moved the link to the right, so we only need to check if the link is below
-                // the viewport
-                let viewport_bottom = self
-                    .viewport_offset
-                    .get()
-                    .saturating_add(self.viewport_size.get().y);
-                if current_link_pos.y >= viewport_bottom {
-                    // so the link is below the viewport... great...
-                    // calculate how much below the viewport the link is
-                    let move_amount = current_link_pos.y.saturating_sub(viewport_bottom);
-
-                    // then scroll that amount
-                    self.scroll(Absolute::Down, move_amount);
-                }
-
-                EventResult::Consumed(None)
-            }
-            Event::Key(Key::Enter) if CONFIG.features.links => {
-                log::info!("opening the link");
-
-                // get current link and retrieve the ArticleElement linked to it
-                let current_link = self.content.current_link();
-                log::debug!("current link is '{:?}'", current_link);
-
-                if let Some(element) = self.content.element_by_id(current_link) {
-                    log::debug!("found the element");
-
-                    // get target link from the article element
-                    let target = match element.get_attribute("target") {
-                        Some(t) => t.to_string(),
-                        None => return EventResult::Ignored,
-                    };
-                    log::info!("target article is '{}'", target);
-
-                    // return the callback
-                    log::debug!("returning the callback to open the link");
-                    return EventResult::Consumed(Some(Callback::from_fn(move |s| {
-                        on_link_submit(s, target.clone())
-                    })));
-                }
-
-                EventResult::Ignored
-            }
-            Event::Mouse {
-                event: MouseEvent::Release(MouseButton::Left),
-                position,
-                offset,
-            } => {
-                // get what element was clicked
-                if let Some(element) = self
-                    .content
-                    .get_element_at_position(position.saturating_sub(offset))
-                {
-                    match element.get_attribute("type") {
-                        // if it's a link, check if it's valid and then open it
-                        Some("link") if CONFIG.features.links => {
-                            log::info!("detected a mouse event over the link '{}'", element.id());
-                            let target = match element.get_attribute("target") {
-                                Some(t) => t.to_string(),
-                                None => {
-                                    log::warn!("the link '{}' is not valid!", element.id());
-                                    return EventResult::Consumed(None);
-                                }
-                            };
-                            log::info!("target article is '{}'", target);
-
-                            // select this link
-                            let element_id = *element.id();
-                            self.content.set_current_link(element_id);
-
-                            // return the callback
-                            return EventResult::Consumed(Some(Callback::from_fn(move |s| {
-                                on_link_submit(s, target.clone())
-                            })));
-                        }
+        if event == CONFIG.keybindings.toggle_link_preview
+            && CONFIG.features.links
+            && !self.is_typing()
+        {
+            return self.toggle_link_preview();
+        }
 
-                        // if it's a button, don't do anything for now
-                        Some("button") => {
-                            log::error!("wow, you've found a secret!")
-                        }
+        // the viewport may have been scrolled since the last event, without this view noticing
+        self.register_visible_links();
 
-                        // this element doesn't support mouse clicking
-                        _ => {}
-                    }
-                }
-                EventResult::Consumed(None)
-            }
-            _ => EventResult::Ignored,
+        // if the selected link has changed, request a preview for the new one
+        let previous_link = self.content.current_link();
+        let result = self.handle_event(event);
+        if self.link_preview_enabled && self.content.current_link() != previous_link {
+            return result.and(self.request_link_preview());
         }
+
+        result
     }
 }
@@ -0,0 +1,304 @@
+use crate::{
+    config::{self, CONFIG},
+    ui::{
+        self,
+        messages::{t, t_args},
+        utils::{load_json, save_json},
+        RootLayout,
+    },
+    view_with_theme, Orientation,
+};
+
+use anyhow::Result;
+use cursive::align::HAlign;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView, TextView};
+use cursive::Cursive;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The bookmarked articles
+    static ref BOOKMARKS: Mutex<Bookmarks> =
+        Mutex::new(Bookmarks::load(CONFIG.settings.bookmarks.path.clone()));
+}
+
+/// A bookmarked article
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Bookmark {
+    /// The id of the article
+    page_id: i32,
+    /// The title of the article
+    title: String,
+    /// The url of the wikipedia the article is from, this determines its language
+    base_url: String,
+}
+
+impl Bookmark {
+    /// Creates a new Bookmark for the article with a given id, title and wikipedia url
+    pub fn new(page_id: i32, title: String, base_url: String) -> Self {
+        Bookmark {
+            page_id,
+            title,
+            base_url,
+        }
+    }
+
+    /// The id of the article
+    pub fn page_id(&self) -> i32 {
+        self.page_id
+    }
+
+    /// The title of the article
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The url of the wikipedia the article is from
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Checks if both bookmarks point to the same article of the same wikipedia
+    fn is_same_article(&self, other: &Bookmark) -> bool {
+        self.page_id == other.page_id
+            && self.base_url.trim_end_matches('/') == other.base_url.trim_end_matches('/')
+    }
+}
+
+/// The bookmarked articles. They are stored on disk as json
+pub struct Bookmarks {
+    /// The bookmarks, in the order they were added
+    bookmarks: Vec<Bookmark>,
+    /// The file the bookmarks are stored in
+    path: Option<PathBuf>,
+}
+
+impl Bookmarks {
+    /// Creates new, empty Bookmarks that aren't stored on disk
+    pub fn new() -> Self {
+        Bookmarks {
+            bookmarks: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Loads the Bookmarks from a given file. If the file can't be read, there are no bookmarks
+    pub fn load(path: Option<PathBuf>) -> Self {
+        log::debug!("loading the bookmarks");
+        let mut bookmarks = Bookmarks::new();
+        if let Some(loaded_bookmarks) = load_json(path.as_deref(), "bookmarks") {
+            bookmarks.bookmarks = loaded_bookmarks;
+        }
+        bookmarks.path = path;
+        bookmarks
+    }
+
+    /// Adds a bookmark and stores the bookmarks. If the article is already bookmarked, nothing
+    /// happens
+    pub fn add_bookmark(&mut self, bookmark: Bookmark) {
+        if self.contains(&bookmark) {
+            return;
+        }
+
+        log::info!("bookmarking the article '{}'", bookmark.title());
+        self.bookmarks.push(bookmark);
+        self.save_or_warn();
+    }
+
+    /// Removes the bookmark of an article and stores the bookmarks
+    pub fn remove_bookmark(&mut self, bookmark: &Bookmark) {
+        log::info!(
+            "removing the bookmark of the article '{}'",
+            bookmark.title()
+        );
+        self.bookmarks
+            .retain(|existing| !existing.is_same_article(bookmark));
+        self.save_or_warn();
+    }
+
+    /// Iterate over the bookmarks in the order they were added
+    pub fn list_bookmarks(&self) -> impl Iterator<Item = &Bookmark> {
+        self.bookmarks.iter()
+    }
+
+    /// Checks if the article of a bookmark is bookmarked
+    pub fn contains(&self, bookmark: &Bookmark) -> bool {
+        self.bookmarks
+            .iter()
+            .any(|existing| existing.is_same_article(bookmark))
+    }
+
+    /// Adds the bookmark if the article isn't bookmarked yet, otherwise removes it. Returns true
+    /// when the bookmark was added
+    pub fn toggle_bookmark(&mut self, bookmark: Bookmark) -> bool {
+        if self.contains(&bookmark) {
+            self.remove_bookmark(&bookmark);
+            return false;
+        }
+        self.add_bookmark(bookmark);
+        true
+    }
+
+    /// Stores the bookmarks and logs any errors
+    fn save_or_warn(&self) {
+        if let Err(error) = self.save() {
+            log::warn!("{:?}", error);
+        }
+    }
+
+    /// Writes the bookmarks to the bookmarks file
+    fn save(&self) -> Result<()> {
+        save_json(self.path.as_deref(), &self.bookmarks, "bookmarks")
+    }
+}
+
+impl Default for Bookmarks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Bookmarks the article of a given bookmark, or removes its bookmark if it's already
+/// bookmarked. It's the callback for the toggle_bookmark keybinding
+pub fn on_bookmark_toggle(siv: &mut Cursive, bookmark: Bookmark) {
+    log::info!("on_bookmark_toggle was called");
+
+    let title = bookmark.title().to_string();
+    let message = if BOOKMARKS.lock().unwrap().toggle_bookmark(bookmark) {
+        t_args("bookmarks.added", &[&title])
+    } else {
+        t_args("bookmarks.removed", &[&title])
+    };
+
+    siv.add_layer(
+        Dialog::info(message)
+            .title(t("bookmarks.title"))
+            .title_position(HAlign::Center),
+    );
+}
+
+/// Displays the bookmarks in a new layer, where they can be opened or removed. It's the callback
+/// for the bookmarks keybinding
+pub fn on_bookmarks_show(siv: &mut Cursive) {
+    log::info!("on_bookmarks_show was called");
+
+    // don't open the bookmarks twice
+    if siv
+        .find_name::<SelectView<Bookmark>>("bookmarks_view")
+        .is_some()
+    {
+        return;
+    }
+
+    let mut bookmarks_view = SelectView::<Bookmark>::new().on_submit(on_bookmark_submit);
+    for bookmark in BOOKMARKS.lock().unwrap().list_bookmarks() {
+        bookmarks_view.add_item(bookmark_label(bookmark), bookmark.clone());
+    }
+
+    let content = if bookmarks_view.is_empty() {
+        log::debug!("there are no bookmarks");
+        Dialog::around(TextView::new(t("bookmarks.empty")))
+    } else {
+        Dialog::around(
+            bookmarks_view
+                .with_name("bookmarks_view")
+                .scrollable()
+                .min_height(10),
+        )
+    };
+
+    siv.add_layer(
+        Dialog::around(
+            RootLayout::new(Orientation::Vertical).child(view_with_theme!(
+                config::CONFIG.theme.search_results,
+                content
+            )),
+        )
+        .title(t("bookmarks.title"))
+        .button(t("bookmarks.remove"), on_bookmark_remove)
+        .dismiss_button(t("dialog.back"))
+        .max_height(20),
+    );
+}
+
+/// Opens the article of a given bookmark. It's the on_submit callback for the bookmarks view
+fn on_bookmark_submit(siv: &mut Cursive, bookmark: &Bookmark) {
+    log::info!("opening the bookmark '{}'", bookmark.title());
+
+    // hide the bookmarks layer
+    siv.pop_layer();
+
+    ui::article::open_article(
+        siv,
+        bookmark.base_url(),
+        bookmark.page_id(),
+        bookmark.title().to_string(),
+    );
+}
+
+/// Removes the selected bookmark. It's the callback for the remove button of the bookmarks layer
+fn on_bookmark_remove(siv: &mut Cursive) {
+    siv.call_on_name("bookmarks_view", |view: &mut SelectView<Bookmark>| {
+        let index = view.selected_id()?;
+        let bookmark = view.selection()?;
+        BOOKMARKS.lock().unwrap().remove_bookmark(&bookmark);
+        // the callback of the new selection can be ignored, there is none
+        let _ = view.remove_item(index);
+        Some(())
+    });
+}
+
+/// Helper function that generates the label of a bookmark. Bookmarks of other wikis than the
+/// current one also display their host
+fn bookmark_label(bookmark: &Bookmark) -> String {
+    if ui::wikis::is_current_wiki(bookmark.base_url()) {
+        return bookmark.title().to_string();
+    }
+    format!(
+        "{} ({})",
+        bookmark.title(),
+        config::wiki_host(bookmark.base_url())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bookmark, Bookmarks};
+
+    fn bookmark(page_id: i32, base_url: &str) -> Bookmark {
+        Bookmark::new(
+            page_id,
+            format!("Article {}", page_id),
+            base_url.to_string(),
+        )
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let mut bookmarks = Bookmarks::new();
+        bookmarks.add_bookmark(bookmark(1, "https://en.wikipedia.org/"));
+        bookmarks.add_bookmark(bookmark(2, "https://en.wikipedia.org/"));
+        bookmarks.add_bookmark(bookmark(1, "https://en.wikipedia.org"));
+        assert_eq!(bookmarks.list_bookmarks().count(), 2);
+
+        bookmarks.remove_bookmark(&bookmark(1, "https://en.wikipedia.org/"));
+        assert_eq!(
+            bookmarks.list_bookmarks().collect::<Vec<&Bookmark>>(),
+            vec![&bookmark(2, "https://en.wikipedia.org/")]
+        );
+    }
+
+    #[test]
+    fn toggle() {
+        let mut bookmarks = Bookmarks::new();
+        assert!(bookmarks.toggle_bookmark(bookmark(1, "https://en.wikipedia.org/")));
+        assert!(bookmarks.toggle_bookmark(bookmark(1, "https://de.wikipedia.org/")));
+        assert!(!bookmarks.toggle_bookmark(bookmark(1, "https://en.wikipedia.org/")));
+
+        assert!(!bookmarks.contains(&bookmark(1, "https://en.wikipedia.org/")));
+        assert!(bookmarks.contains(&bookmark(1, "https://de.wikipedia.org/")));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    config::CONFIG,
+    ui::{
+        self,
+        messages::{format_number, format_size, t, t_args},
+        utils::show_error,
+    },
+};
+
+use cursive::align::HAlign;
+use cursive::views::Dialog;
+use cursive::Cursive;
+
+/// Displays how many articles are cached and how much space they use, with a button for clearing
+/// the cache. It's the callback for the cache action of the command palette
+pub fn on_cache_show(siv: &mut Cursive) {
+    log::info!("on_cache_show was called");
+
+    let cache = match ui::article::article_cache() {
+        Some(cache) => cache,
+        None => {
+            log::info!("there is no cache directory");
+            siv.add_layer(
+                Dialog::info(t("cache.unavailable"))
+                    .title(t("cache.title"))
+                    .title_position(HAlign::Center),
+            );
+            return;
+        }
+    };
+
+    let (entries, bytes) = cache.size();
+    let limit = if CONFIG.settings.cache.max_entries == 0 {
+        t("cache.unlimited")
+    } else {
+        format_number(CONFIG.settings.cache.max_entries)
+    };
+    let mut message = t_args(
+        "cache.stats",
+        &[&format_number(entries), &limit, &format_size(bytes)],
+    );
+    if !CONFIG.settings.cache.enabled {
+        message = format!("{}\n\n{}", message, t("cache.disabled"));
+    }
+
+    siv.add_layer(
+        Dialog::text(message)
+            .title(t("cache.title"))
+            .title_position(HAlign::Center)
+            .button(t("cache.clear"), on_cache_clear)
+            .dismiss_button(t("dialog.back")),
+    );
+}
+
+/// Removes every cached article. It's the callback for the clear button of the cache dialog
+fn on_cache_clear(siv: &mut Cursive) {
+    siv.pop_layer();
+
+    let result = match ui::article::article_cache() {
+        Some(cache) => cache.clear(),
+        None => Ok(()),
+    };
+    if let Err(error) = result {
+        log::warn!("{:?}", error);
+        show_error(siv, &t("error.clearing_cache"), &error);
+        return;
+    }
+
+    siv.add_layer(
+        Dialog::info(t("cache.cleared"))
+            .title(t("cache.title"))
+            .title_position(HAlign::Center),
+    );
+}
@@ -0,0 +1,214 @@
+use crate::{
+    config::{self, keybinding_name, Keybindings},
+    ui::{
+        self,
+        messages::{t, t_args},
+        root, RootLayout,
+    },
+    view_with_theme, Orientation,
+};
+
+use cursive::align::HAlign;
+use cursive::event::{Event, EventResult, EventTrigger, Key};
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, OnEventView, SelectView};
+use cursive::Cursive;
+
+/// The keybindings that can be changed while wiki-tui is running, by their name in the
+/// configuration. They are the ones handled by the RootLayouts
+const REMAPPABLE_KEYBINDINGS: &[&str] = &[
+    "up",
+    "down",
+    "left",
+    "right",
+    "focus_next",
+    "focus_prev",
+    "history_back",
+    "history_forward",
+];
+
+/// Displays the remappable keybindings in a new layer. Selecting one of them asks for its new key.
+/// It's the callback for the keybindings action of the command palette
+pub fn on_keybindings_show(siv: &mut Cursive) {
+    log::info!("on_keybindings_show was called");
+
+    let mut keybindings_view = SelectView::<&'static str>::new().on_submit(on_keybinding_submit);
+    add_keybindings(&mut keybindings_view, &root::keybindings());
+
+    siv.add_layer(
+        Dialog::around(
+            RootLayout::new(Orientation::Vertical).child(view_with_theme!(
+                config::CONFIG.theme.search_results,
+                Dialog::around(
+                    keybindings_view
+                        .with_name("keybindings_view")
+                        .scrollable()
+                        .min_height(10)
+                )
+            )),
+        )
+        .title(t("keybindings.title"))
+        .dismiss_button(t("dialog.back"))
+        .max_height(20),
+    );
+}
+
+/// Asks for the new key of a given keybinding. It's the on_submit callback for the keybindings
+/// view
+fn on_keybinding_submit(siv: &mut Cursive, name: &&'static str) {
+    let name = *name;
+    log::info!("asking for the new key of '{}'", name);
+
+    // every key except Esc is captured, Esc cancels the remapping
+    let trigger = EventTrigger::from_fn(|event| {
+        !matches!(
+            event,
+            Event::Key(Key::Esc)
+                | Event::Mouse { .. }
+                | Event::Refresh
+                | Event::WindowResize
+                | Event::FocusLost
+                | Event::Unknown(_)
+        )
+    });
+
+    siv.add_layer(
+        OnEventView::new(
+            Dialog::text(t_args("keybindings.press", &[&t(&description_id(name))]))
+                .title(t("keybindings.title"))
+                .title_position(HAlign::Center),
+        )
+        .on_pre_event_inner(trigger, move |_, event| {
+            let event = event.clone();
+            Some(EventResult::with_cb(move |s| {
+                on_key_captured(s, name, event.clone())
+            }))
+        })
+        .on_event(Key::Esc, |s| {
+            s.pop_layer();
+        }),
+    );
+}
+
+/// Helper function that uses a captured key for a given keybinding. If another keybinding already
+/// uses the key, the conflict is reported and the keybinding stays the same
+fn on_key_captured(siv: &mut Cursive, name: &'static str, event: Event) {
+    // hide the dialog asking for the key
+    siv.pop_layer();
+
+    let mut keybindings = root::keybindings();
+    let conflicts = conflicting_keybindings(&keybindings, name, &event);
+    if !conflicts.is_empty() {
+        log::info!(
+            "'{}' can't be used for '{}', it's used by '{}'",
+            keybinding_name(&event),
+            name,
+            conflicts.join(", ")
+        );
+        siv.add_layer(
+            Dialog::info(t_args(
+                "keybindings.conflict",
+                &[&keybinding_name(&event), &conflicts.join(", ")],
+            ))
+            .title(t("dialog.error"))
+            .title_position(HAlign::Center),
+        );
+        return;
+    }
+
+    if let Some(keybinding) = keybinding_mut(&mut keybindings, name) {
+        log::info!("using '{}' for '{}'", keybinding_name(&event), name);
+        *keybinding = event;
+    }
+    siv.call_on_name("keybindings_view", |view: &mut SelectView<&'static str>| {
+        add_keybindings(view, &keybindings)
+    });
+    root::set_keybindings(keybindings);
+}
+
+/// Helper function that replaces the items of the keybindings view with the given keybindings
+fn add_keybindings(view: &mut SelectView<&'static str>, keybindings: &Keybindings) {
+    let selected = view.selected_id();
+    view.clear();
+    for (name, event) in keybindings.named() {
+        if REMAPPABLE_KEYBINDINGS.contains(&name) {
+            view.add_item(
+                format!("{}: {}", t(&description_id(name)), keybinding_name(event)),
+                name,
+            );
+        }
+    }
+    if let Some(selected) = selected {
+        // the callback of the selection can be ignored, there is none
+        let _ = view.set_selection(selected);
+    }
+}
+
+/// Helper function that returns the names of the other keybindings using a given key. The 'q'
+/// key always quits wiki-tui, so it can't be used either
+fn conflicting_keybindings(keybindings: &Keybindings, name: &str, event: &Event) -> Vec<String> {
+    let mut conflicts = keybindings
+        .named()
+        .into_iter()
+        .filter(|(other_name, other_event)| *other_name != name && *other_event == event)
+        .map(|(other_name, _)| other_name.to_string())
+        .collect::<Vec<String>>();
+    if *event == Event::Char('q') {
+        conflicts.push("quit".to_string());
+    }
+    conflicts
+}
+
+/// Helper function that returns the id of the message describing a keybinding
+fn description_id(name: &str) -> String {
+    format!("keybindings.{}", name)
+}
+
+/// Helper function that returns a remappable keybinding by its name
+fn keybinding_mut<'a>(keybindings: &'a mut Keybindings, name: &str) -> Option<&'a mut Event> {
+    match name {
+        "up" => Some(&mut keybindings.up),
+        "down" => Some(&mut keybindings.down),
+        "left" => Some(&mut keybindings.left),
+        "right" => Some(&mut keybindings.right),
+        "focus_next" => Some(&mut keybindings.focus_next),
+        "focus_prev" => Some(&mut keybindings.focus_prev),
+        "history_back" => Some(&mut keybindings.history_back),
+        "history_forward" => Some(&mut keybindings.history_forward),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{conflicting_keybindings, keybinding_mut, REMAPPABLE_KEYBINDINGS};
+    use crate::config::CONFIG;
+    use cursive::event::Event;
+
+    #[test]
+    fn remappable_keybindings() {
+        let mut keybindings = CONFIG.keybindings.clone();
+        for name in REMAPPABLE_KEYBINDINGS {
+            assert!(keybinding_mut(&mut keybindings, name).is_some());
+        }
+        assert!(keybinding_mut(&mut keybindings, "bookmarks").is_none());
+    }
+
+    #[test]
+    fn conflicts() {
+        let mut keybindings = CONFIG.keybindings.clone();
+        keybindings.bookmarks = Event::Char('z');
+        assert_eq!(
+            conflicting_keybindings(&keybindings, "up", &Event::Char('z')),
+            vec!["bookmarks".to_string()]
+        );
+        assert_eq!(
+            conflicting_keybindings(&keybindings, "bookmarks", &Event::Char('z')),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            conflicting_keybindings(&keybindings, "up", &Event::Char('q')),
+            vec!["quit".to_string()]
+        );
+    }
+}
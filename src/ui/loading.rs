@@ -0,0 +1,41 @@
+use cursive::{Printer, Vec2, View};
+use std::time::Instant;
+
+/// The frames of the spinner animation
+const SPINNER_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+/// How long a single frame of the spinner is displayed, in milliseconds
+const SPINNER_FRAME_DURATION: u128 = 100;
+
+/// A view displaying a message next to an animated spinner. The spinner only moves when the
+/// screen is refreshed regularly, so autorefresh has to be enabled while it's shown
+pub struct LoadingView {
+    /// The message displayed next to the spinner
+    message: String,
+    /// When the view was created, used for selecting the frame of the spinner
+    start: Instant,
+}
+
+impl LoadingView {
+    /// Creates a new LoadingView with a given message
+    pub fn new(message: String) -> Self {
+        LoadingView {
+            message,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl View for LoadingView {
+    fn draw(&self, printer: &Printer) {
+        let frame = (self.start.elapsed().as_millis() / SPINNER_FRAME_DURATION) as usize
+            % SPINNER_FRAMES.len();
+        printer.print(
+            (0, 0),
+            &format!("{} {}", SPINNER_FRAMES[frame], self.message),
+        );
+    }
+
+    fn required_size(&mut self, _: Vec2) -> Vec2 {
+        Vec2::new(self.message.chars().count() + 2, 1)
+    }
+}
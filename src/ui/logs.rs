@@ -0,0 +1,100 @@
+use crate::{
+    logging::{self, LogEntry},
+    ui::messages::{t, t_args},
+};
+
+use cursive::align::HAlign;
+use cursive::view::{Nameable, Resizable, ScrollStrategy};
+use cursive::views::{Dialog, ScrollView, TextView};
+use cursive::Cursive;
+use lazy_static::lazy_static;
+use log::LevelFilter;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The least severe level of the displayed log entries
+    static ref LOGS_LEVEL: Mutex<LevelFilter> = Mutex::new(LevelFilter::Trace);
+}
+
+/// Displays the recent log entries in a new layer, the newest one at the bottom. It's the
+/// callback for the show_logs keybinding
+pub fn on_logs_show(siv: &mut Cursive) {
+    log::info!("on_logs_show was called");
+
+    // don't open the logs twice
+    if siv.find_name::<TextView>("logs_view").is_some() {
+        return;
+    }
+
+    let level = *LOGS_LEVEL.lock().unwrap();
+    siv.add_layer(
+        Dialog::around(
+            ScrollView::new(TextView::new(logs_text(level)).with_name("logs_view"))
+                .scroll_strategy(ScrollStrategy::StickToBottom)
+                .full_screen(),
+        )
+        .title(logs_title(level))
+        .title_position(HAlign::Center)
+        .button(t("logs.level"), on_logs_level)
+        .dismiss_button(t("dialog.back"))
+        .with_name("logs_dialog"),
+    );
+}
+
+/// Switches to the next level of the displayed log entries. It's the callback for the level
+/// button of the logs dialog
+fn on_logs_level(siv: &mut Cursive) {
+    let level = {
+        let mut level = LOGS_LEVEL.lock().unwrap();
+        *level = next_level(*level);
+        *level
+    };
+    log::info!(
+        "displaying the log entries of the level '{}' and above",
+        level
+    );
+
+    siv.call_on_name("logs_view", |view: &mut TextView| {
+        view.set_content(logs_text(level))
+    });
+    siv.call_on_name("logs_dialog", |view: &mut Dialog| {
+        view.set_title(logs_title(level))
+    });
+}
+
+/// Helper function that returns the level following a given one, after errors every entry is
+/// displayed again
+fn next_level(level: LevelFilter) -> LevelFilter {
+    match level {
+        LevelFilter::Trace | LevelFilter::Debug => LevelFilter::Info,
+        LevelFilter::Info => LevelFilter::Warn,
+        LevelFilter::Warn => LevelFilter::Error,
+        LevelFilter::Error | LevelFilter::Off => LevelFilter::Trace,
+    }
+}
+
+/// Helper function that returns the title of the logs dialog for a given level
+fn logs_title(level: LevelFilter) -> String {
+    match level {
+        LevelFilter::Trace => t("logs.title"),
+        level => t_args("logs.title_level", &[&level]),
+    }
+}
+
+/// Helper function that formats the buffered log entries of a given level
+fn logs_text(level: LevelFilter) -> String {
+    let entries = logging::recent_entries(level);
+    if entries.is_empty() {
+        return t("logs.empty");
+    }
+    entries
+        .iter()
+        .map(format_entry)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Helper function that formats a single log entry
+fn format_entry(entry: &LogEntry) -> String {
+    format!("{:<5} {}", entry.level, entry.message)
+}
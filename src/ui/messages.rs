@@ -0,0 +1,523 @@
+use crate::config::CONFIG;
+
+use anyhow::{bail, Context, Result};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use toml::Value;
+
+lazy_static! {
+    /// The translated messages of the configured locale, by their id
+    static ref MESSAGES: HashMap<String, String> = match CONFIG.settings.locale_path {
+        Some(ref path) => load_messages(path).unwrap_or_else(|error| {
+            log::warn!("{:?}", error);
+            HashMap::new()
+        }),
+        None => HashMap::new(),
+    };
+}
+
+/// The english messages, by their id. They're used when the locale doesn't translate a message
+const DEFAULT_MESSAGES: &[(&str, &str)] = &[
+    // dialogs
+    ("dialog.back", "Back"),
+    ("dialog.cancel", "Cancel"),
+    ("dialog.close", "Close"),
+    ("dialog.dismiss", "Dismiss"),
+    ("dialog.error", "Error"),
+    ("dialog.loading", "Loading"),
+    ("dialog.quit", "Quit"),
+    ("dialog.retry", "Retry"),
+    // formats
+    ("format.date", "%Y-%m-%d"),
+    ("format.thousands_separator", ","),
+    // errors
+    ("error.cause", "{}\nCheck the logs for further information"),
+    (
+        "error.problem",
+        "A Problem occurred while {}.\nCheck the logs for further information",
+    ),
+    ("error.clearing_cache", "clearing the cache"),
+    ("error.displaying_article", "displaying the article"),
+    ("error.exporting_article", "exporting the article"),
+    ("error.fetching_article", "fetching the article"),
+    ("error.finding_random_article", "finding a random article"),
+    (
+        "error.opening_article_in_browser",
+        "opening the article in your browser",
+    ),
+    (
+        "error.opening_link_in_browser",
+        "opening the link in your browser",
+    ),
+    ("error.searching", "searching"),
+    (
+        "error.rate_limited",
+        "The wiki received too many requests, retrying in {}s...",
+    ),
+    // cache
+    ("cache.clear", "Clear"),
+    ("cache.cleared", "The cache has been cleared"),
+    (
+        "cache.disabled",
+        "The cache is disabled, articles are only read from it when you're offline",
+    ),
+    (
+        "cache.stats",
+        "Cached articles: {} (at most {})\nSize on disk: {}",
+    ),
+    ("cache.title", "Cache"),
+    ("cache.unavailable", "There is no cache directory"),
+    ("cache.unlimited", "unlimited"),
+    // config
+    ("config.continue", "Continue with defaults"),
+    (
+        "config.errors",
+        "The config file has problems, the affected settings use their defaults:\n\n{}",
+    ),
+    ("config.errors_title", "Config Errors"),
+    // articles
+    ("article.background_loading", "Opening '{}' in the background..."),
+    ("article.background_opened", "Opened '{}' in a new tab"),
+    (
+        "article.browser_confirmation",
+        "Do you want to open '{}' in your browser?",
+    ),
+    ("article.clipboard_copied", "Copied '{}' to the clipboard"),
+    (
+        "article.clipboard_unavailable",
+        "The clipboard isn't available, you can copy the url from here:\n\n{}",
+    ),
+    (
+        "article.info",
+        "Title: {}\nLast edited: {}\nWords: {}\nReferences: {}\nUrl: {}",
+    ),
+    ("article.disambiguation_title", "Which one do you mean?"),
+    ("article.export", "Export"),
+    ("article.export_title", "Export the article"),
+    ("article.exported", "Saved the article in '{}'"),
+    ("article.info_title", "Article Information"),
+    (
+        "article.link_confirmation",
+        "Do you want to open the article {}?",
+    ),
+    ("article.link_copied", "Copied the link '{}' to the clipboard"),
+    ("article.link_from_wiki", "{} from {}"),
+    ("article.loading", "Loading '{}'..."),
+    ("article.redirected_from", "Redirected from {}"),
+    ("article.refreshed", "'{}' has been edited, showing the new version"),
+    ("article.reload_unavailable", "This article can't be reloaded"),
+    ("article.reloaded", "Reloaded '{}'"),
+    (
+        "article.namespace_confirmation",
+        "{} isn't an article but a page of the namespace '{}', which might not be displayed well. Do you want to open it anyway?",
+    ),
+    ("article.no_links", "This article has no links"),
+    ("article.no_references", "This article has no references"),
+    ("article.no_see_also", "This article has no related articles"),
+    ("article.preview_title", "Preview"),
+    ("article.random_loading", "Finding a random article..."),
+    (
+        "article.reference_missing",
+        "The text of this reference couldn't be found",
+    ),
+    ("article.reference_title", "Reference {}"),
+    ("article.references_title", "References"),
+    ("article.see_also_title", "See also"),
+    ("article.selection_copied", "Copied the selected text to the clipboard"),
+    ("article.selection_title", "Selection"),
+    (
+        "article.selection_unavailable",
+        "The clipboard isn't available, you can copy the text from here:\n\n{}",
+    ),
+    ("article.unknown", "unknown"),
+    ("article.url_title", "Article Url"),
+    // search
+    ("search.empty", "Enter something to search for"),
+    ("search.filter", "Filter: "),
+    ("search.info_filtered", "{}, {} match the filter"),
+    ("search.loading", "Searching for '{}'..."),
+    ("search.loading_more", "Loading more results..."),
+    ("search.mode_exact", "exact"),
+    ("search.mode_full_text", "full text"),
+    ("search.mode_title_prefix", "title prefix"),
+    ("search.more", "Show more results..."),
+    ("search.no_results", "No results"),
+    ("search.result_info", "Last edited: {}\nWords: {}"),
+    ("search.result_words", "{} words"),
+    ("search.results_title", "Results for \"{}\""),
+    ("search.showing", "Showing {}–{} results"),
+    ("search.showing_total", "Showing {}–{} of {} results"),
+    ("search.suggestion", "Did you mean: {}?"),
+    ("search.title", "Search"),
+    ("search.title_mode", "{} ({})"),
+    ("search.title_wiki", "Search {}"),
+    // bookmarks
+    ("bookmarks.added", "Added '{}' to your bookmarks"),
+    ("bookmarks.empty", "You haven't bookmarked any articles yet"),
+    ("bookmarks.remove", "Remove"),
+    ("bookmarks.removed", "Removed '{}' from your bookmarks"),
+    ("bookmarks.title", "Bookmarks"),
+    // keybindings
+    (
+        "keybindings.conflict",
+        "'{}' is already used by: {}.\nChoose another key",
+    ),
+    ("keybindings.down", "Scroll down"),
+    ("keybindings.focus_next", "Focus the next view"),
+    ("keybindings.focus_prev", "Focus the previous view"),
+    ("keybindings.history_back", "Go back in the history"),
+    ("keybindings.history_forward", "Go forward in the history"),
+    ("keybindings.left", "Scroll / select left"),
+    (
+        "keybindings.press",
+        "Press the new key for '{}'.\nPress Esc to cancel",
+    ),
+    ("keybindings.right", "Scroll / select right"),
+    ("keybindings.title", "Keybindings"),
+    ("keybindings.up", "Scroll up"),
+    // command palette
+    // logs
+    ("logs.empty", "There are no log entries"),
+    ("logs.level", "Level"),
+    ("logs.title", "Logs"),
+    ("logs.title_level", "Logs ({} and above)"),
+    ("palette.article_info", "Show the article information"),
+    ("palette.bookmarks", "Show the bookmarks"),
+    ("palette.cache", "Show or clear the cache"),
+    ("palette.close_tab", "Close the tab"),
+    ("palette.copy_url", "Copy the article url"),
+    ("palette.focus_toc", "Focus the table of contents"),
+    ("palette.export_article", "Export the article"),
+    ("palette.history_back", "Go back in the history"),
+    ("palette.history_forward", "Go forward in the history"),
+    ("palette.keybindings", "Change the keybindings"),
+    ("palette.logs", "Show the logs"),
+    ("palette.next_tab", "Switch to the next tab"),
+    ("palette.open_in_browser", "Open the article in the browser"),
+    ("palette.previous_tab", "Switch to the previous tab"),
+    ("palette.quit", "Quit"),
+    ("palette.random_article", "Open a random article"),
+    ("palette.reading_log", "Show the reading log"),
+    ("palette.references", "Show the references"),
+    ("palette.scroll_bottom", "Jump to the bottom of the article"),
+    ("palette.scroll_top", "Jump to the top of the article"),
+    ("palette.search", "Search"),
+    ("palette.see_also", "Show the related articles"),
+    ("palette.switch_search_mode", "Switch the search mode"),
+    ("palette.switch_wiki", "Switch the wiki"),
+    ("palette.title", "Commands"),
+    ("palette.toggle_bookmark", "Bookmark the article"),
+    ("palette.toggle_line_width", "Toggle the maximum line width"),
+    ("palette.toggle_spacing", "Switch the line spacing"),
+    ("palette.toggle_toc", "Show, hide or move the table of contents"),
+    // start screen
+    ("start.bookmarks", "Show your bookmarks"),
+    ("start.random", "Open a random article"),
+    ("start.recent", "Show the reading log"),
+    ("start.recent_title", "Recently read"),
+    ("start.search", "Search for an article"),
+    // status bar
+    ("status.bookmark", "bookmark"),
+    ("status.close", "close"),
+    ("status.commands", "commands"),
+    ("status.focus_next", "next view"),
+    ("status.history_back", "back"),
+    ("status.jump", "jump to section"),
+    ("status.link_hints", "link numbers"),
+    ("status.open", "open"),
+    ("status.open_link", "open link"),
+    ("status.open_section", "open section"),
+    ("status.quit", "quit"),
+    ("status.scroll", "scroll"),
+    ("status.search", "search"),
+    ("status.search_article", "find"),
+    ("status.search_history", "previous searches"),
+    ("status.search_mode", "search mode"),
+    ("status.select", "select"),
+    ("status.select_link", "select link"),
+    ("status.toggle_section", "collapse"),
+    // reading log
+    ("reading_log.clear", "Clear"),
+    ("reading_log.cleared", "Your reading log has been cleared"),
+    (
+        "reading_log.disabled",
+        "The reading log is disabled. Enable it with features.reading_log in your config",
+    ),
+    ("reading_log.duration", "{} min"),
+    ("reading_log.empty", "You haven't read any articles yet"),
+    ("reading_log.entry", "{}  {} ({})"),
+    ("reading_log.title", "Reading Log"),
+    // tabs
+    ("tabs.new", "New tab"),
+    // toc
+    ("toc.bottom", "bottom"),
+    ("toc.hidden", "hidden"),
+    ("toc.left", "left"),
+    ("toc.position", "Table of contents: {}"),
+    ("toc.right", "right"),
+    ("toc.top", "top"),
+    // wikis
+    (
+        "wikis.error",
+        "Couldn't use the wiki '{}'.\nCheck its base_url and script_path in your config\n\n{}",
+    ),
+    ("wikis.title", "Switch the wiki"),
+];
+
+/// Returns the message with a given id in the configured locale. Messages the locale doesn't
+/// translate are returned in english and unknown ids are returned as they are
+pub fn t(id: &str) -> String {
+    if let Some(message) = MESSAGES.get(id) {
+        return message.to_string();
+    }
+    default_message(id).unwrap_or(id).to_string()
+}
+
+/// Returns the message with a given id like t, replacing its placeholders ('{}') with the given
+/// arguments in order
+pub fn t_args(id: &str, args: &[&dyn Display]) -> String {
+    fill_placeholders(&t(id), args)
+}
+
+/// Formats a date in the ISO-8601 format, like the ones of the api, with the date format of the
+/// configured locale. Other dates are returned as they are
+pub fn format_date(date: &str) -> String {
+    match parse_date(date) {
+        Some(parts) => fill_date(&t("format.date"), &parts),
+        None => date.to_string(),
+    }
+}
+
+/// Formats a number, grouping its digits with the thousands separator of the configured locale
+pub fn format_number(number: impl Display) -> String {
+    group_digits(&number.to_string(), &t("format.thousands_separator"))
+}
+
+/// Formats a number of bytes with the largest fitting unit, like '1.5 MB'
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["KB", "MB", "GB"];
+    if bytes < 1000 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1000.0;
+    let mut unit = 0;
+    while size >= 1000.0 && unit + 1 < UNITS.len() {
+        size /= 1000.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Helper function that splits a date in the ISO-8601 format (2022-05-01T10:00:00Z) into its
+/// year, month, day, hour, minute and second. The time is optional
+fn parse_date(date: &str) -> Option<[&str; 6]> {
+    let digits = |range: std::ops::Range<usize>| {
+        date.get(range)
+            .filter(|part| part.chars().all(|char| char.is_ascii_digit()))
+    };
+    let is_separator =
+        |index: usize, separator: &str| date.get(index..index + 1) == Some(separator);
+
+    if !(is_separator(4, "-") && is_separator(7, "-")) || date.len() != 10 && !is_separator(10, "T")
+    {
+        return None;
+    }
+    let (hour, minute, second) = match date.len() {
+        10 => ("00", "00", "00"),
+        _ if is_separator(13, ":") && is_separator(16, ":") => {
+            (digits(11..13)?, digits(14..16)?, digits(17..19)?)
+        }
+        _ => return None,
+    };
+    Some([
+        digits(0..4)?,
+        digits(5..7)?,
+        digits(8..10)?,
+        hour,
+        minute,
+        second,
+    ])
+}
+
+/// Helper function that replaces the placeholders of a date format (%Y, %m, %d, %H, %M and %S)
+/// with the parts of a date
+fn fill_date(format: &str, parts: &[&str; 6]) -> String {
+    ["%Y", "%m", "%d", "%H", "%M", "%S"]
+        .iter()
+        .zip(parts)
+        .fold(format.to_string(), |date, (placeholder, part)| {
+            date.replace(placeholder, part)
+        })
+}
+
+/// Helper function that groups the digits of a number by three with a given separator. Anything
+/// that isn't an integer is returned as it is
+fn group_digits(number: &str, separator: &str) -> String {
+    let digits = number.strip_prefix('-').unwrap_or(number);
+    if digits.is_empty() || !digits.chars().all(|char| char.is_ascii_digit()) {
+        return number.to_string();
+    }
+
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push_str(separator);
+        }
+        grouped.push(digit);
+    }
+    if number.starts_with('-') {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+/// Helper function that returns the english message with a given id
+fn default_message(id: &str) -> Option<&'static str> {
+    DEFAULT_MESSAGES
+        .iter()
+        .find(|(default_id, _)| *default_id == id)
+        .map(|(_, message)| *message)
+}
+
+/// Helper function that loads the messages of a locale from a toml file mapping the message ids
+/// to their translation
+fn load_messages(path: &Path) -> Result<HashMap<String, String>> {
+    log::info!("loading the messages from '{}'", path.display());
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("couldn't read the locale '{}'", path.display()))?;
+    parse_messages(&content).with_context(|| format!("'{}' isn't a valid locale", path.display()))
+}
+
+/// Helper function that parses the content of a locale file. The ids can be written as dotted
+/// keys or grouped into tables
+fn parse_messages(content: &str) -> Result<HashMap<String, String>> {
+    let mut messages = HashMap::new();
+    flatten_messages("", &toml::from_str(content)?, &mut messages)?;
+    Ok(messages)
+}
+
+/// Helper function that adds every message of a table to messages, prefixing their ids with the
+/// id of the table
+fn flatten_messages(
+    prefix: &str,
+    value: &Value,
+    messages: &mut HashMap<String, String>,
+) -> Result<()> {
+    match value {
+        Value::String(message) => {
+            messages.insert(prefix.to_string(), message.to_string());
+        }
+        Value::Table(table) => {
+            for (key, value) in table {
+                let id = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten_messages(&id, value, messages)?;
+            }
+        }
+        _ => bail!("the message '{}' isn't a string", prefix),
+    }
+    Ok(())
+}
+
+/// Helper function that replaces the placeholders of a message with the given arguments in order.
+/// Placeholders without an argument are left empty
+fn fill_placeholders(message: &str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = message.split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        default_message, fill_date, fill_placeholders, format_size, group_digits, parse_date,
+        parse_messages, DEFAULT_MESSAGES,
+    };
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(999), "999 B");
+        assert_eq!(format_size(1500), "1.5 KB");
+        assert_eq!(format_size(2_300_000), "2.3 MB");
+        assert_eq!(format_size(4_000_000_000_000), "4000.0 GB");
+    }
+
+    #[test]
+    fn placeholders() {
+        assert_eq!(
+            fill_placeholders("Loading '{}'...", &[&"Linux"]),
+            "Loading 'Linux'..."
+        );
+        assert_eq!(fill_placeholders("{} from {}", &[&1, &"en"]), "1 from en");
+        assert_eq!(fill_placeholders("{} from {}", &[&1]), "1 from ");
+        assert_eq!(fill_placeholders("Bookmarks", &[&1]), "Bookmarks");
+    }
+
+    #[test]
+    fn locale_file() {
+        let messages = parse_messages(
+            r#"
+            dialog.back = "Zurück"
+
+            [article]
+            loading = "'{}' wird geladen..."
+            "#,
+        )
+        .unwrap();
+        assert_eq!(messages.get("dialog.back").unwrap(), "Zurück");
+        assert_eq!(
+            messages.get("article.loading").unwrap(),
+            "'{}' wird geladen..."
+        );
+        assert!(parse_messages("dialog.back = 1").is_err());
+    }
+
+    #[test]
+    fn dates() {
+        let parts = parse_date("2022-05-01T10:03:09Z").unwrap();
+        assert_eq!(parts, ["2022", "05", "01", "10", "03", "09"]);
+        assert_eq!(fill_date("%Y-%m-%d", &parts), "2022-05-01");
+        assert_eq!(fill_date("%d.%m.%Y %H:%M", &parts), "01.05.2022 10:03");
+        assert_eq!(
+            parse_date("2022-05-01"),
+            Some(["2022", "05", "01", "00", "00", "00"])
+        );
+        assert_eq!(parse_date("1 May 2022, at 10:03"), None);
+        assert_eq!(parse_date("2022-05-01 10:03"), None);
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(group_digits("1234567", ","), "1,234,567");
+        assert_eq!(group_digits("123", ","), "123");
+        assert_eq!(group_digits("-1234", "."), "-1.234");
+        assert_eq!(group_digits("1234", ""), "1234");
+        assert_eq!(group_digits("12.5", ","), "12.5");
+    }
+
+    #[test]
+    fn unique_ids() {
+        for (i, (id, _)) in DEFAULT_MESSAGES.iter().enumerate() {
+            assert!(!DEFAULT_MESSAGES[i + 1..]
+                .iter()
+                .any(|(other_id, _)| other_id == id));
+        }
+        assert_eq!(default_message("dialog.back"), Some("Back"));
+        assert_eq!(default_message("unknown"), None);
+    }
+}
@@ -1,10 +1,24 @@
 pub mod article;
+pub mod bookmarks;
+pub mod cache;
+pub mod keybindings;
+mod loading;
+pub mod logs;
+pub mod messages;
 pub mod models;
+pub mod palette;
+pub mod reading_log;
 mod root;
 pub mod search;
+pub mod start;
+pub mod status;
+pub mod tabs;
 mod theme_view;
 pub mod toc;
 pub mod utils;
+pub mod visited;
+pub mod wikis;
 
+pub type LoadingView = loading::LoadingView;
 pub type ThemedView<T> = theme_view::ThemedView<T>;
 pub type RootLayout = root::RootLayout;
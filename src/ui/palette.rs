@@ -0,0 +1,284 @@
+use crate::{
+    config::{self, CONFIG},
+    ui::{self, messages::t, search::fuzzy_match},
+    view_with_theme,
+};
+
+use cursive::event::{Event, Key};
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, EditView, LinearLayout, OnEventView, SelectView};
+use cursive::Cursive;
+
+/// An action that can be executed from the command palette
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Search,
+    RandomArticle,
+    Bookmarks,
+    ReadingLog,
+    Cache,
+    Logs,
+    ToggleBookmark,
+    SwitchWiki,
+    SwitchSearchMode,
+    HistoryBack,
+    HistoryForward,
+    ArticleInfo,
+    References,
+    SeeAlso,
+    CopyUrl,
+    OpenInBrowser,
+    ExportArticle,
+    ScrollTop,
+    ScrollBottom,
+    ToggleLineWidth,
+    ToggleSpacing,
+    FocusToc,
+    ToggleToc,
+    NextTab,
+    PreviousTab,
+    CloseTab,
+    Keybindings,
+    Quit,
+}
+
+/// Every action of the command palette, in the order they are listed in
+const ACTIONS: &[Action] = &[
+    Action::Search,
+    Action::RandomArticle,
+    Action::Bookmarks,
+    Action::ReadingLog,
+    Action::Cache,
+    Action::Logs,
+    Action::ToggleBookmark,
+    Action::SwitchWiki,
+    Action::SwitchSearchMode,
+    Action::HistoryBack,
+    Action::HistoryForward,
+    Action::ArticleInfo,
+    Action::References,
+    Action::SeeAlso,
+    Action::CopyUrl,
+    Action::OpenInBrowser,
+    Action::ExportArticle,
+    Action::ScrollTop,
+    Action::ScrollBottom,
+    Action::ToggleLineWidth,
+    Action::ToggleSpacing,
+    Action::FocusToc,
+    Action::ToggleToc,
+    Action::NextTab,
+    Action::PreviousTab,
+    Action::CloseTab,
+    Action::Keybindings,
+    Action::Quit,
+];
+
+impl Action {
+    /// The name of the action, as it's displayed in the command palette
+    fn name(&self) -> String {
+        t(match self {
+            Action::Search => "palette.search",
+            Action::RandomArticle => "palette.random_article",
+            Action::Bookmarks => "palette.bookmarks",
+            Action::ReadingLog => "palette.reading_log",
+            Action::Cache => "palette.cache",
+            Action::Logs => "palette.logs",
+            Action::ToggleBookmark => "palette.toggle_bookmark",
+            Action::SwitchWiki => "palette.switch_wiki",
+            Action::SwitchSearchMode => "palette.switch_search_mode",
+            Action::HistoryBack => "palette.history_back",
+            Action::HistoryForward => "palette.history_forward",
+            Action::ArticleInfo => "palette.article_info",
+            Action::References => "palette.references",
+            Action::SeeAlso => "palette.see_also",
+            Action::CopyUrl => "palette.copy_url",
+            Action::OpenInBrowser => "palette.open_in_browser",
+            Action::ExportArticle => "palette.export_article",
+            Action::ScrollTop => "palette.scroll_top",
+            Action::ScrollBottom => "palette.scroll_bottom",
+            Action::ToggleLineWidth => "palette.toggle_line_width",
+            Action::ToggleSpacing => "palette.toggle_spacing",
+            Action::FocusToc => "palette.focus_toc",
+            Action::ToggleToc => "palette.toggle_toc",
+            Action::NextTab => "palette.next_tab",
+            Action::PreviousTab => "palette.previous_tab",
+            Action::CloseTab => "palette.close_tab",
+            Action::Keybindings => "palette.keybindings",
+            Action::Quit => "palette.quit",
+        })
+    }
+
+    /// Executes the action by calling the callback of its keybinding. Actions of the article are
+    /// sent to the article view as the event of their keybinding
+    fn run(&self, siv: &mut Cursive) {
+        log::info!("running the action '{:?}'", self);
+        match self {
+            Action::Search => focus(siv, "search_bar"),
+            Action::RandomArticle => ui::article::on_random_article(siv),
+            Action::Bookmarks => ui::bookmarks::on_bookmarks_show(siv),
+            Action::ReadingLog => ui::reading_log::on_reading_log_show(siv),
+            Action::Cache => ui::cache::on_cache_show(siv),
+            Action::Logs => ui::logs::on_logs_show(siv),
+            Action::SwitchWiki => ui::wikis::on_wiki_select(siv),
+            Action::SwitchSearchMode => ui::search::on_search_mode_switch(siv),
+            Action::HistoryBack => ui::article::on_history_back(siv),
+            Action::HistoryForward => ui::article::on_history_forward(siv),
+            Action::FocusToc => focus(siv, "toc_view"),
+            Action::ToggleToc => ui::toc::on_toc_position_cycle(siv),
+            Action::NextTab => ui::tabs::on_next_tab(siv),
+            Action::PreviousTab => ui::tabs::on_previous_tab(siv),
+            Action::CloseTab => ui::tabs::on_tab_close(siv),
+            Action::Keybindings => ui::keybindings::on_keybindings_show(siv),
+            Action::Quit => siv.quit(),
+            Action::ToggleBookmark => {
+                send_to_article(siv, CONFIG.keybindings.toggle_bookmark.clone())
+            }
+            Action::ArticleInfo => send_to_article(siv, CONFIG.keybindings.article_info.clone()),
+            Action::References => send_to_article(siv, CONFIG.keybindings.references.clone()),
+            Action::SeeAlso => send_to_article(siv, CONFIG.keybindings.see_also.clone()),
+            Action::CopyUrl => send_to_article(siv, CONFIG.keybindings.copy_url.clone()),
+            Action::OpenInBrowser => {
+                send_to_article(siv, CONFIG.keybindings.open_in_browser.clone())
+            }
+            Action::ExportArticle => {
+                send_to_article(siv, CONFIG.keybindings.export_article.clone())
+            }
+            // the keys work even when 'g' waits for a second 'g'
+            Action::ScrollTop => send_to_article(siv, Event::Key(Key::Home)),
+            Action::ScrollBottom => send_to_article(siv, Event::Key(Key::End)),
+            Action::ToggleLineWidth => {
+                send_to_article(siv, CONFIG.keybindings.toggle_line_width.clone())
+            }
+            Action::ToggleSpacing => {
+                send_to_article(siv, CONFIG.keybindings.toggle_spacing.clone())
+            }
+        }
+    }
+}
+
+/// Displays the command palette in a new layer, where every action can be searched for by its
+/// name and executed. It's the callback for the command_palette keybinding
+pub fn on_palette_show(siv: &mut Cursive) {
+    log::info!("on_palette_show was called");
+
+    // don't open the palette twice
+    if siv
+        .find_name::<SelectView<Action>>("palette_view")
+        .is_some()
+    {
+        return;
+    }
+
+    let mut palette_view = SelectView::<Action>::new().on_submit(on_action_submit);
+    add_actions(&mut palette_view, "");
+
+    let palette_filter = EditView::new()
+        .on_edit(|s, filter, _| {
+            s.call_on_name("palette_view", |view: &mut SelectView<Action>| {
+                add_actions(view, filter)
+            });
+        })
+        .on_submit(|s, _| {
+            let action = s
+                .call_on_name("palette_view", |view: &mut SelectView<Action>| {
+                    view.selection()
+                })
+                .flatten();
+            if let Some(action) = action {
+                on_action_submit(s, &action);
+            }
+        })
+        .with_name("palette_filter");
+
+    siv.add_layer(
+        OnEventView::new(
+            Dialog::around(
+                // not a RootLayout, every key has to reach the filter
+                LinearLayout::vertical().child(view_with_theme!(
+                    config::CONFIG.theme.search_results,
+                    Dialog::around(
+                        LinearLayout::vertical()
+                            .child(palette_filter.full_width())
+                            .child(
+                                palette_view
+                                    .with_name("palette_view")
+                                    .scrollable()
+                                    .min_height(10)
+                            )
+                    )
+                )),
+            )
+            .title(t("palette.title"))
+            .max_width(60)
+            .max_height(20),
+        )
+        .on_event(Key::Esc, |s| {
+            s.pop_layer();
+        }),
+    );
+}
+
+/// Closes the command palette and executes the selected action. It's the on_submit callback for
+/// the palette view
+fn on_action_submit(siv: &mut Cursive, action: &Action) {
+    siv.pop_layer();
+    action.run(siv);
+}
+
+/// Helper function that replaces the actions of the palette view with the ones matching a filter
+fn add_actions(view: &mut SelectView<Action>, filter: &str) {
+    view.clear();
+    for action in ACTIONS {
+        let name = action.name();
+        if fuzzy_match(filter, &name) {
+            view.add_item(name, *action);
+        }
+    }
+}
+
+/// Helper function that focuses the view with a given name, if it's displayed
+fn focus(siv: &mut Cursive, name: &str) {
+    if let Err(error) = siv.focus_name(name) {
+        log::info!("couldn't focus '{}': {:?}", name, error);
+    }
+}
+
+/// Helper function that focuses the article view and sends it a given event, like pressing a
+/// keybinding inside of it would. Nothing happens when no article is displayed
+fn send_to_article(siv: &mut Cursive, event: Event) {
+    if let Err(error) = siv.focus_name("article_view") {
+        log::info!("there is no article to run the action on: {:?}", error);
+        return;
+    }
+    siv.on_event(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{on_palette_show, ACTIONS};
+    use cursive::event::Event;
+    use cursive::views::EditView;
+    use cursive::Cursive;
+
+    #[test]
+    fn unique_actions() {
+        for (i, action) in ACTIONS.iter().enumerate() {
+            assert!(!ACTIONS[i + 1..].contains(action));
+        }
+    }
+
+    #[test]
+    fn filter_keys() {
+        let mut siv = Cursive::new();
+        on_palette_show(&mut siv);
+
+        // 'H' and 'L' move through the history of the article, but they're typed into the filter
+        siv.on_event(Event::Char('H'));
+        siv.on_event(Event::Char('L'));
+        let filter = siv
+            .call_on_name("palette_filter", |view: &mut EditView| view.get_content())
+            .unwrap();
+        assert_eq!(filter.as_str(), "HL");
+    }
+}
@@ -0,0 +1,398 @@
+use crate::{
+    config::{self, CONFIG},
+    ui::{
+        self,
+        messages::{format_date, t, t_args},
+        utils::{load_json, save_json},
+        RootLayout,
+    },
+    view_with_theme,
+    wiki::article::Article,
+    Orientation,
+};
+
+use anyhow::Result;
+use cursive::align::HAlign;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView, TextView};
+use cursive::Cursive;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    /// The articles that have been read
+    static ref READING_LOG: Mutex<ReadingLog> =
+        Mutex::new(ReadingLog::load(CONFIG.settings.reading_log.path.clone()));
+    /// The article that is read right now and when it was opened. It's logged as soon as another
+    /// article is opened or wiki-tui is closed
+    static ref CURRENT_ENTRY: Mutex<Option<(ReadingLogEntry, Instant)>> = Mutex::new(None);
+}
+
+/// An article that has been read
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ReadingLogEntry {
+    /// The id of the article
+    page_id: i32,
+    /// The title of the article
+    title: String,
+    /// The url of the wikipedia the article is from
+    base_url: String,
+    /// When the article was opened, in the ISO-8601 format
+    opened_at: String,
+    /// How many seconds the article was read for
+    duration: u64,
+}
+
+impl ReadingLogEntry {
+    /// Creates a new ReadingLogEntry for an article opened at a given time
+    pub fn new(page_id: i32, title: String, base_url: String, opened_at: String) -> Self {
+        ReadingLogEntry {
+            page_id,
+            title,
+            base_url,
+            opened_at,
+            duration: 0,
+        }
+    }
+
+    /// The id of the article
+    pub fn page_id(&self) -> i32 {
+        self.page_id
+    }
+
+    /// The title of the article
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The url of the wikipedia the article is from
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+}
+
+/// The log of the articles that have been read. It's stored on disk as json and only written to
+/// when the reading log is enabled
+pub struct ReadingLog {
+    /// The entries, in the order the articles were opened
+    entries: Vec<ReadingLogEntry>,
+    /// The file the entries are stored in
+    path: Option<PathBuf>,
+}
+
+impl ReadingLog {
+    /// Creates a new, empty ReadingLog that isn't stored on disk
+    pub fn new() -> Self {
+        ReadingLog {
+            entries: Vec::new(),
+            path: None,
+        }
+    }
+
+    /// Loads the ReadingLog from a given file. If the file can't be read, the log is empty
+    pub fn load(path: Option<PathBuf>) -> Self {
+        log::debug!("loading the reading log");
+        let mut reading_log = ReadingLog::new();
+        if let Some(entries) = load_json(path.as_deref(), "reading log") {
+            reading_log.entries = entries;
+        }
+        reading_log.path = path;
+        reading_log
+    }
+
+    /// Adds an entry and stores the log
+    pub fn add_entry(&mut self, entry: ReadingLogEntry) {
+        log::info!("logging the article '{}'", entry.title());
+        self.entries.push(entry);
+        self.save_or_warn();
+    }
+
+    /// Removes every entry and stores the empty log
+    pub fn clear(&mut self) {
+        log::info!("clearing the reading log");
+        self.entries.clear();
+        self.save_or_warn();
+    }
+
+    /// Iterate over the entries, starting with the article opened last
+    pub fn list_entries(&self) -> impl Iterator<Item = &ReadingLogEntry> {
+        self.entries.iter().rev()
+    }
+
+    /// Returns up to count articles, starting with the one opened last. Articles that were read
+    /// more than once are only returned once
+    pub fn recent_entries(&self, count: usize) -> Vec<ReadingLogEntry> {
+        let mut entries: Vec<ReadingLogEntry> = Vec::new();
+        for entry in self.list_entries() {
+            if entries.len() == count {
+                break;
+            }
+            if !entries
+                .iter()
+                .any(|known| known.page_id == entry.page_id && known.base_url == entry.base_url)
+            {
+                entries.push(entry.clone());
+            }
+        }
+        entries
+    }
+
+    /// Stores the log and logs any errors
+    fn save_or_warn(&self) {
+        if let Err(error) = self.save() {
+            log::warn!("{:?}", error);
+        }
+    }
+
+    /// Writes the entries to the reading log file
+    fn save(&self) -> Result<()> {
+        save_json(self.path.as_deref(), &self.entries, "reading log")
+    }
+}
+
+impl Default for ReadingLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Starts logging a displayed article. The article that was displayed before is logged with the
+/// time it was read for. Nothing happens if the reading log is disabled
+pub fn on_article_opened(article: &Article) {
+    if !CONFIG.features.reading_log {
+        return;
+    }
+    finish_reading();
+
+    let page_id = match article.page_id() {
+        Some(page_id) => page_id,
+        None => {
+            log::debug!("the id of the article is unknown, it isn't logged");
+            return;
+        }
+    };
+    let entry = ReadingLogEntry::new(
+        page_id,
+        article.title().unwrap_or_default().to_string(),
+        article
+            .base_url()
+            .map(str::to_string)
+            .unwrap_or_else(|| ui::wikis::current_wiki().base_url),
+        iso_date(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        ),
+    );
+    *CURRENT_ENTRY.lock().unwrap() = Some((entry, Instant::now()));
+}
+
+/// Logs the article that is read right now with the time it was read for. It's called when
+/// another article is opened and when wiki-tui is closed
+pub fn finish_reading() {
+    if let Some((mut entry, opened_at)) = CURRENT_ENTRY.lock().unwrap().take() {
+        entry.duration = opened_at.elapsed().as_secs();
+        READING_LOG.lock().unwrap().add_entry(entry);
+    }
+}
+
+/// Returns up to count of the articles read last, without the ones read more than once. Nothing is
+/// returned if the reading log is disabled
+pub fn recent_articles(count: usize) -> Vec<ReadingLogEntry> {
+    if !CONFIG.features.reading_log {
+        return Vec::new();
+    }
+    READING_LOG.lock().unwrap().recent_entries(count)
+}
+
+/// Displays the reading log in a new layer, where the articles can be opened again and the log
+/// can be cleared. It's the callback for the reading_log keybinding
+pub fn on_reading_log_show(siv: &mut Cursive) {
+    log::info!("on_reading_log_show was called");
+
+    if !CONFIG.features.reading_log {
+        siv.add_layer(
+            Dialog::info(t("reading_log.disabled"))
+                .title(t("reading_log.title"))
+                .title_position(HAlign::Center),
+        );
+        return;
+    }
+
+    // don't open the reading log twice
+    if siv
+        .find_name::<SelectView<ReadingLogEntry>>("reading_log_view")
+        .is_some()
+    {
+        return;
+    }
+
+    let mut reading_log_view =
+        SelectView::<ReadingLogEntry>::new().on_submit(on_reading_log_submit);
+    for entry in READING_LOG.lock().unwrap().list_entries() {
+        reading_log_view.add_item(entry_label(entry), entry.clone());
+    }
+
+    let content = if reading_log_view.is_empty() {
+        log::debug!("the reading log is empty");
+        Dialog::around(TextView::new(t("reading_log.empty")))
+    } else {
+        Dialog::around(
+            reading_log_view
+                .with_name("reading_log_view")
+                .scrollable()
+                .min_height(10),
+        )
+    };
+
+    siv.add_layer(
+        Dialog::around(
+            RootLayout::new(Orientation::Vertical).child(view_with_theme!(
+                config::CONFIG.theme.search_results,
+                content
+            )),
+        )
+        .title(t("reading_log.title"))
+        .button(t("reading_log.clear"), on_reading_log_clear)
+        .dismiss_button(t("dialog.back"))
+        .max_height(20),
+    );
+}
+
+/// Opens the article of a given entry. It's the on_submit callback for the reading log view
+fn on_reading_log_submit(siv: &mut Cursive, entry: &ReadingLogEntry) {
+    // hide the reading log layer
+    siv.pop_layer();
+    open_entry(siv, entry);
+}
+
+/// Opens the article of a given entry again
+pub fn open_entry(siv: &mut Cursive, entry: &ReadingLogEntry) {
+    log::info!("opening the logged article '{}'", entry.title());
+    ui::article::open_article(
+        siv,
+        entry.base_url(),
+        entry.page_id(),
+        entry.title().to_string(),
+    );
+}
+
+/// Removes every entry of the reading log. It's the callback for the clear button of the reading
+/// log layer
+fn on_reading_log_clear(siv: &mut Cursive) {
+    // the article that is read right now isn't logged either
+    CURRENT_ENTRY.lock().unwrap().take();
+    READING_LOG.lock().unwrap().clear();
+
+    siv.pop_layer();
+    siv.add_layer(
+        Dialog::info(t("reading_log.cleared"))
+            .title(t("reading_log.title"))
+            .title_position(HAlign::Center),
+    );
+}
+
+/// Helper function that generates the label of an entry with its date, title and duration
+fn entry_label(entry: &ReadingLogEntry) -> String {
+    t_args(
+        "reading_log.entry",
+        &[
+            &format_date(&entry.opened_at),
+            &entry.title,
+            &t_args(
+                "reading_log.duration",
+                &[&(entry.duration / 60).to_string()],
+            ),
+        ],
+    )
+}
+
+/// Helper function that converts seconds since the unix epoch into a date in the ISO-8601 format
+/// (2022-05-01T10:00:00Z)
+fn iso_date(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let time = seconds % 86400;
+
+    // convert the days into a date, see http://howardhinnant.github.io/date_algorithms.html
+    let days = days + 719468;
+    let era = days / 146097;
+    let day_of_era = days % 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let month = if month < 10 { month + 3 } else { month - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{iso_date, ReadingLog, ReadingLogEntry};
+
+    #[test]
+    fn iso_dates() {
+        assert_eq!(iso_date(0), "1970-01-01T00:00:00Z");
+        assert_eq!(iso_date(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(iso_date(1651399200), "2022-05-01T10:00:00Z");
+    }
+
+    #[test]
+    fn add_and_clear() {
+        let mut reading_log = ReadingLog::new();
+        for page_id in 1..=2 {
+            reading_log.add_entry(ReadingLogEntry::new(
+                page_id,
+                format!("Article {}", page_id),
+                "https://en.wikipedia.org/".to_string(),
+                iso_date(0),
+            ));
+        }
+        assert_eq!(
+            reading_log
+                .list_entries()
+                .map(ReadingLogEntry::page_id)
+                .collect::<Vec<i32>>(),
+            vec![2, 1]
+        );
+
+        reading_log.clear();
+        assert_eq!(reading_log.list_entries().count(), 0);
+    }
+
+    #[test]
+    fn recent_entries() {
+        let mut reading_log = ReadingLog::new();
+        for page_id in [1, 2, 1, 3] {
+            reading_log.add_entry(ReadingLogEntry::new(
+                page_id,
+                format!("Article {}", page_id),
+                "https://en.wikipedia.org/".to_string(),
+                iso_date(0),
+            ));
+        }
+        let page_ids = |entries: Vec<ReadingLogEntry>| {
+            entries
+                .iter()
+                .map(ReadingLogEntry::page_id)
+                .collect::<Vec<i32>>()
+        };
+        assert_eq!(page_ids(reading_log.recent_entries(5)), vec![3, 1, 2]);
+        assert_eq!(page_ids(reading_log.recent_entries(2)), vec![3, 1]);
+        assert!(reading_log.recent_entries(0).is_empty());
+    }
+}
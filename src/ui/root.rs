@@ -1,23 +1,80 @@
-use crate::config::Keybindings;
+use crate::config::{Keybindings, CONFIG};
+use crate::ui::article::{on_history_back, on_history_forward};
 use cursive::direction::Orientation;
 use cursive::event::{Event, EventResult, Key};
-use cursive::view::{IntoBoxedView, View, ViewWrapper};
-use cursive::views::LinearLayout;
+use cursive::view::{Finder, IntoBoxedView, View, ViewWrapper};
+use cursive::views::{BoxedView, HideableView, LinearLayout};
 use cursive::Vec2;
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+
+lazy_static! {
+    /// The keybindings of every RootLayout. Unlike the other keybindings, they can be changed
+    /// while wiki-tui is running
+    static ref KEYBINDINGS: RwLock<Keybindings> = RwLock::new(CONFIG.keybindings.clone());
+}
+
+/// Returns the keybindings currently used by the RootLayouts
+pub fn keybindings() -> Keybindings {
+    KEYBINDINGS.read().unwrap().clone()
+}
+
+/// Replaces the keybindings of every RootLayout, including the ones already displayed
+pub fn set_keybindings(keybindings: Keybindings) {
+    *KEYBINDINGS.write().unwrap() = keybindings;
+}
+
+/// The largest count that can be typed in before a movement
+const MAX_COUNT: usize = 999;
 
 pub struct RootLayout {
     layout: LinearLayout,
-    keybindings: Keybindings,
+
+    /// The keybindings of this layout, if it doesn't use the ones of every RootLayout
+    keybindings: Option<Keybindings>,
+    /// Are counts, 'gg' and 'G' handled?
+    vim_motions: bool,
+    /// The count typed in before the next movement, if there is one
+    count: Option<usize>,
+    /// Was the first key of 'gg' pressed?
+    top_pending: bool,
+    /// The name of a child that is hidden while the layout is smaller than the size
+    hidden_below: Option<(&'static str, Vec2)>,
 }
 
 impl RootLayout {
-    pub fn new(orientation: Orientation, keybindings: Keybindings) -> Self {
+    pub fn new(orientation: Orientation) -> Self {
         RootLayout {
             layout: LinearLayout::new(orientation),
-            keybindings,
+            keybindings: None,
+            vim_motions: CONFIG.features.vim_motions,
+            count: None,
+            top_pending: false,
+            hidden_below: None,
         }
     }
 
+    /// Hides the child with the given name while the layout is narrower or shorter than min_size.
+    /// The child has to be a named HideableView<BoxedView>
+    pub fn hide_below(mut self, name: &'static str, min_size: Vec2) -> Self {
+        self.hidden_below = Some((name, min_size));
+        self
+    }
+
+    /// Uses the given keybindings instead of the ones of every RootLayout. Changing those doesn't
+    /// change the keybindings of this layout then
+    pub fn keybindings(mut self, keybindings: Keybindings) -> Self {
+        self.keybindings = Some(keybindings);
+        self
+    }
+
+    /// Enables or disables the vim motions. By default, they're enabled with the vim_motions
+    /// feature
+    pub fn vim_motions(mut self, enabled: bool) -> Self {
+        self.vim_motions = enabled;
+        self
+    }
+
     pub fn child<V: IntoBoxedView + 'static>(mut self, view: V) -> Self {
         self.add_child(view);
         self
@@ -38,30 +95,194 @@ impl RootLayout {
     pub fn find_child_from_name(&mut self, name: &str) -> Option<usize> {
         self.layout.find_child_from_name(name)
     }
+
+    /// Helper function that shows or hides the child given to hide_below, depending on the size
+    /// of the layout. A hidden child loses the focus
+    fn update_hidden_child(&mut self, size: Vec2) {
+        let (name, min_size) = match self.hidden_below {
+            Some(hidden_below) => hidden_below,
+            None => return,
+        };
+
+        let visible = size.x >= min_size.x && size.y >= min_size.y;
+        let changed = self
+            .layout
+            .call_on_name(name, |view: &mut HideableView<BoxedView>| {
+                let changed = view.is_visible() != visible;
+                view.set_visible(visible);
+                changed
+            })
+            .unwrap_or_default();
+        if !changed {
+            return;
+        }
+        log::info!(
+            "the layout is now {}x{}, {} '{}'",
+            size.x,
+            size.y,
+            if visible { "showing" } else { "hiding" },
+            name
+        );
+
+        if visible || self.layout.find_child_from_name(name) != Some(self.layout.get_focus_index())
+        {
+            return;
+        }
+        for index in 0..self.layout.len() {
+            if self.layout.set_focus_index(index).is_ok() {
+                break;
+            }
+        }
+    }
+
+    /// Sends a movement to the children count times. A movement cancels a pending 'gg'
+    fn move_by(&mut self, key: Key, count: usize) -> EventResult {
+        self.top_pending = false;
+        (0..count).fold(EventResult::Ignored, |result, _| {
+            result.and(self.layout.on_event(Event::Key(key)))
+        })
+    }
+
+    /// Handles the vim motions: counts before a movement, 'gg' and 'G'. The children get the
+    /// event first, so typing into an EditView still works
+    fn on_motion_event(
+        &mut self,
+        event: Event,
+        count: Option<usize>,
+        keybindings: &Keybindings,
+    ) -> EventResult {
+        let top_pending = self.top_pending;
+        self.top_pending = false;
+
+        let result = self.layout.on_event(event.clone());
+        if result.is_consumed() {
+            return result;
+        }
+
+        match event {
+            // a count can't start with a zero
+            Event::Char(digit) if digit.is_ascii_digit() && (digit != '0' || count.is_some()) => {
+                let digit = digit.to_digit(10).unwrap_or_default() as usize;
+                let count = count.unwrap_or_default() * 10 + digit;
+                log::debug!("the count is now '{}'", count.min(MAX_COUNT));
+                self.count = Some(count.min(MAX_COUNT));
+                EventResult::Consumed(None)
+            }
+            key if key == keybindings.scroll_top && top_pending => {
+                self.layout.on_event(Event::Key(Key::Home))
+            }
+            key if key == keybindings.scroll_top => {
+                self.top_pending = true;
+                EventResult::Consumed(None)
+            }
+            key if key == keybindings.scroll_bottom => self.layout.on_event(Event::Key(Key::End)),
+            _ => result,
+        }
+    }
 }
 
 impl ViewWrapper for RootLayout {
     wrap_impl!(self.layout: LinearLayout);
 
     fn wrap_on_event(&mut self, ch: Event) -> EventResult {
+        // the lock isn't held while the children handle the event, they can be RootLayouts too
+        let keybindings = self.keybindings.clone().unwrap_or_else(keybindings);
+        let count = self.count.take();
+        let times = count.unwrap_or(1);
         match ch {
-            // movement
-            key if key == self.keybindings.up => self.layout.on_event(Event::Key(Key::Up)),
-            key if key == self.keybindings.down => self.layout.on_event(Event::Key(Key::Down)),
-            key if key == self.keybindings.left => self.layout.on_event(Event::Key(Key::Left)),
-            key if key == self.keybindings.right => self.layout.on_event(Event::Key(Key::Right)),
+            // movement. An outer RootLayout sends its movements as arrow keys, so they use the
+            // count typed into this one
+            key if key == keybindings.up || key == Event::Key(Key::Up) => {
+                self.move_by(Key::Up, times)
+            }
+            key if key == keybindings.down || key == Event::Key(Key::Down) => {
+                self.move_by(Key::Down, times)
+            }
+            key if key == keybindings.left || key == Event::Key(Key::Left) => {
+                self.move_by(Key::Left, times)
+            }
+            key if key == keybindings.right || key == Event::Key(Key::Right) => {
+                self.move_by(Key::Right, times)
+            }
 
             // focus
-            key if key == self.keybindings.focus_next => self.layout.on_event(Event::Key(Key::Tab)),
-            key if key == self.keybindings.focus_prev => {
-                self.layout.on_event(Event::Shift(Key::Tab))
-            }
+            key if key == keybindings.focus_next => self.layout.on_event(Event::Key(Key::Tab)),
+            key if key == keybindings.focus_prev => self.layout.on_event(Event::Shift(Key::Tab)),
 
+            // history
+            key if key == keybindings.history_back => EventResult::with_cb(on_history_back),
+            key if key == keybindings.history_forward => EventResult::with_cb(on_history_forward),
+
+            _ if self.vim_motions => self.on_motion_event(ch, count, &keybindings),
             _ => self.layout.on_event(ch),
         }
     }
 
     fn wrap_layout(&mut self, size: Vec2) {
+        self.update_hidden_child(size);
         self.layout.layout(size);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{keybindings, RootLayout};
+    use cursive::direction::Orientation;
+    use cursive::event::Event;
+    use cursive::view::{Finder, Nameable, View};
+    use cursive::views::{BoxedView, Button, HideableView, TextView};
+    use cursive::Vec2;
+
+    #[test]
+    fn hide_below() {
+        let mut layout = RootLayout::new(Orientation::Horizontal)
+            .child(TextView::new("article"))
+            .child(HideableView::new(BoxedView::boxed(TextView::new("toc"))).with_name("toc"))
+            .hide_below("toc", Vec2::new(60, 15));
+        let is_visible = |layout: &mut RootLayout| {
+            layout
+                .call_on_name("toc", |view: &mut HideableView<BoxedView>| {
+                    view.is_visible()
+                })
+                .unwrap()
+        };
+
+        layout.layout(Vec2::new(40, 20));
+        assert!(!is_visible(&mut layout));
+        layout.layout(Vec2::new(80, 20));
+        assert!(is_visible(&mut layout));
+        layout.layout(Vec2::new(80, 10));
+        assert!(!is_visible(&mut layout));
+    }
+
+    #[test]
+    fn nested_counts() {
+        let mut keybindings = keybindings();
+        keybindings.right = Event::Char('l');
+
+        let mut buttons = RootLayout::new(Orientation::Horizontal)
+            .keybindings(keybindings.clone())
+            .vim_motions(true);
+        for index in 0..10 {
+            buttons.add_child(Button::new(index.to_string(), |_| {}));
+        }
+        let mut layout = RootLayout::new(Orientation::Vertical)
+            .keybindings(keybindings)
+            .vim_motions(true)
+            .child(buttons.with_name("buttons"));
+        let focus_index = |layout: &mut RootLayout| {
+            layout
+                .call_on_name("buttons", |view: &mut RootLayout| {
+                    view.layout.get_focus_index()
+                })
+                .unwrap()
+        };
+
+        // the count typed into the inner layout is used for the movement of the outer one
+        layout.on_event(Event::Char('5'));
+        layout.on_event(Event::Char('l'));
+        assert_eq!(focus_index(&mut layout), 5);
+        layout.on_event(Event::Char('l'));
+        assert_eq!(focus_index(&mut layout), 6);
+    }
+}
@@ -1,50 +1,643 @@
 use crate::{
-    config,
-    ui::{self, RootLayout},
+    config::{self, SearchColumn, Wiki},
+    ui::{
+        self,
+        messages::{format_date, format_number, format_size, t, t_args},
+        status::{StatusContext, StatusTracker},
+        utils::{hide_loading_view, show_error, show_loading_view},
+        RootLayout,
+    },
     view_with_theme,
     wiki::search::{
-        SearchBuilder, SearchMetadata, SearchProperties, SearchResult, SearchSortOrder,
+        Search, SearchBuilder, SearchCache, SearchInfo, SearchMetadata, SearchMode,
+        SearchProperties, SearchResult, SearchSortOrder,
     },
+    wiki::{category, error::WikiError},
     Orientation, CONFIG,
 };
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use cursive::direction::Absolute;
+use cursive::event::{EventResult, Key};
+use cursive::theme::{Effect, Style};
 use cursive::view::{Nameable, Resizable, Scrollable};
-use cursive::views::{Button, Dialog, EditView, LinearLayout, SelectView, TextView};
-use cursive::{align::HAlign, utils::markup::StyledString, Cursive};
+use cursive::views::{
+    Button, Dialog, EditView, HideableView, LinearLayout, NamedView, OnEventView, SelectView,
+    TextView,
+};
+use cursive::{utils::markup::StyledString, Cursive};
+use lazy_static::lazy_static;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// How many characters of the title of a search result are displayed when there are columns
+const RESULT_TITLE_WIDTH: usize = 35;
+
+/// Identifies the latest search. Results of cancelled or older searches are discarded
+static SEARCH_REQUEST: AtomicUsize = AtomicUsize::new(0);
+/// Identifies the latest edit of the search bar. Live searches for older queries are discarded
+static LIVE_SEARCH_REQUEST: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// Every result of the displayed search, including the ones hidden by the filter
+    static ref SEARCH_RESULTS: Mutex<Vec<SearchResult>> = Mutex::new(Vec::new());
+    /// How many results the displayed search has in total, if the wiki tells it
+    static ref SEARCH_TOTAL_HITS: Mutex<Option<i32>> = Mutex::new(None);
+    /// Where the displayed results are continued, if there are more of them
+    static ref SEARCH_CONTINUATION: Mutex<Option<SearchContinuation>> = Mutex::new(None);
+    /// How queries are matched when they don't start with the prefix of a mode
+    static ref SEARCH_MODE: Mutex<SearchMode> = Mutex::new(SearchMode::default());
+    /// The previous search queries, used for cycling through them in the search bar
+    static ref SEARCH_HISTORY: Mutex<SearchHistory> = Mutex::new(SearchHistory::load(
+        CONFIG.settings.search.history_path.clone(),
+        CONFIG.settings.search.history_size,
+    ));
+    /// The recent searches, so searching for them again doesn't need the network
+    static ref SEARCH_CACHE: SearchCache = SearchCache::new(
+        Duration::from_secs(CONFIG.settings.search.cache_ttl),
+        SEARCH_CACHE_CAPACITY,
+    );
+}
+
+/// How many searches are kept in the search cache at most
+const SEARCH_CACHE_CAPACITY: usize = 50;
+/// How many results a live search displays at most
+const LIVE_SEARCH_LIMIT: usize = 5;
+/// How many characters a query needs before it's searched for while typing
+const LIVE_SEARCH_MIN_LENGTH: usize = 2;
+
+/// The prefix of queries listing the pages of a category instead of searching
+const CATEGORY_PREFIX: &str = "category:";
+
+/// Where the displayed results are continued
+#[derive(Clone)]
+enum SearchContinuation {
+    /// The search for a query with a mode in a wiki continues at an offset
+    Search(String, SearchMode, usize, Wiki),
+    /// The pages of a category in a wiki continue at a token
+    Category(String, String, Wiki),
+}
+
+/// The prefixes of queries that are searched with a given mode, regardless of the active one
+const SEARCH_MODE_PREFIXES: &[(&str, SearchMode)] = &[
+    ("text:", SearchMode::FullText),
+    ("title:", SearchMode::TitlePrefix),
+    ("exact:", SearchMode::Exact),
+];
+
+/// A history of search queries that is stored on disk, with a cursor for cycling through them
+pub struct SearchHistory {
+    /// The queries, from the oldest to the newest one
+    queries: Vec<String>,
+    /// The index of the query currently displayed in the search bar, if any
+    cursor: Option<usize>,
+    /// The maximum number of queries that are stored
+    size: usize,
+    /// The file the queries are stored in
+    path: Option<PathBuf>,
+}
+
+impl SearchHistory {
+    /// Creates a new, empty SearchHistory that isn't stored on disk
+    pub fn new(size: usize) -> Self {
+        SearchHistory {
+            queries: Vec::new(),
+            cursor: None,
+            size,
+            path: None,
+        }
+    }
+
+    /// Loads the SearchHistory from a given file, one query per line. If the file can't be read,
+    /// the history will be empty
+    pub fn load(path: Option<PathBuf>, size: usize) -> Self {
+        log::debug!("loading the search history");
+        let mut search_history = SearchHistory::new(size);
+
+        if let Some(ref path) = path {
+            if let Ok(content) = fs::read_to_string(path) {
+                search_history.queries = content.lines().map(|line| line.to_string()).collect();
+                search_history.truncate();
+            }
+        }
+
+        search_history.path = path;
+        search_history
+    }
+
+    /// Adds a query to the history and stores it. Empty queries and queries that are identical to
+    /// the previous one are ignored
+    pub fn push(&mut self, query: &str) {
+        self.cursor = None;
+
+        // queries are stored line by line, so they can't contain any newlines
+        let query = query.trim().replace('\n', " ");
+        if self.size == 0 || query.is_empty() || self.queries.last() == Some(&query) {
+            return;
+        }
+
+        self.queries.push(query);
+        self.truncate();
+
+        if let Err(error) = self.save() {
+            log::warn!("{:?}", error);
+        }
+    }
+
+    /// Returns the last n queries, starting with the newest one
+    pub fn last(&self, n: usize) -> Vec<&str> {
+        self.queries
+            .iter()
+            .rev()
+            .take(n)
+            .map(|query| query.as_str())
+            .collect()
+    }
+
+    /// Moves the cursor to the next older query and returns it. If there is no older query, None
+    /// is returned
+    pub fn older(&mut self) -> Option<&str> {
+        let cursor = match self.cursor {
+            Some(cursor) => cursor.checked_sub(1)?,
+            None => self.queries.len().checked_sub(1)?,
+        };
+        self.cursor = Some(cursor);
+        self.queries.get(cursor).map(|query| query.as_str())
+    }
+
+    /// Moves the cursor to the next newer query and returns it. When moving past the newest query, an
+    /// empty query is returned. If the cursor isn't on any query, None is returned
+    pub fn newer(&mut self) -> Option<&str> {
+        let cursor = self.cursor? + 1;
+        if cursor >= self.queries.len() {
+            self.cursor = None;
+            return Some("");
+        }
+        self.cursor = Some(cursor);
+        self.queries.get(cursor).map(|query| query.as_str())
+    }
+
+    /// Removes the oldest queries until the history fits into its size
+    fn truncate(&mut self) {
+        if self.queries.len() > self.size {
+            let excess = self.queries.len() - self.size;
+            self.queries.drain(..excess);
+        }
+    }
+
+    /// Writes the queries to the history file
+    fn save(&self) -> Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("couldn't create the search history directory")?;
+        }
+        fs::write(path, self.queries.join("\n")).context("couldn't write the search history")?;
+        log::debug!("saved the search history");
+        Ok(())
+    }
+}
 
 /// Returns the default SearchBuilder
 fn build_search() -> SearchBuilder {
-    SearchBuilder::new(&config::CONFIG.api_config.base_url)
-        .info(SearchMetadata::new().total_hits())
-        .prop(SearchProperties::new().snippet())
+    build_search_in(&ui::wikis::current_wiki())
+}
+
+/// Returns the default SearchBuilder for searching a given wiki
+fn build_search_in(wiki: &Wiki) -> SearchBuilder {
+    let builder = SearchBuilder::new(&wiki.base_url)
+        .script_path(wiki.script_path.clone())
+        .info(SearchMetadata::new().total_hits().suggestion())
+        .prop(
+            SearchProperties::new()
+                .snippet()
+                .title_snippet()
+                .timestamp()
+                .wordcount()
+                .size(),
+        )
         .sort(SearchSortOrder::JustMatch)
+        .limit(CONFIG.settings.search.results_limit)
+        .timeout(Duration::from_millis(CONFIG.api_config.timeout_ms))
+        .max_retries(CONFIG.api_config.max_retries)
+        .user_agent(CONFIG.api_config.user_agent.clone())
+        .offline(CONFIG.features.offline);
+
+    // a ttl of zero disables the cache
+    if CONFIG.settings.search.cache_ttl == 0 {
+        return builder;
+    }
+    builder.cache(SEARCH_CACHE.clone())
+}
+
+/// Searches for a given query and returns the best result, without displaying anything. It's used
+/// for printing articles with the --print argument
+pub fn find_article(search_query: &str) -> Result<SearchResult> {
+    let search_query = normalize_query(search_query);
+    let (search_mode, search_query) = parse_search_mode(&search_query);
+    if search_query.is_empty() {
+        bail!("the search query is empty");
+    }
+
+    log::info!("searching for the best result of '{}'", search_query);
+    let search = build_search()
+        .mode(search_mode)
+        .query(search_query.to_string())
+        .limit(1)
+        .search()?;
+    let search_result = search.results().next().cloned();
+    search_result.ok_or_else(|| anyhow!("no article was found for '{}'", search_query))
+}
+
+/// Returns the active search mode
+pub fn search_mode() -> SearchMode {
+    *SEARCH_MODE.lock().unwrap()
+}
+
+/// Returns the name of a search mode, as it's displayed in the title of the search bar
+pub fn search_mode_name(search_mode: SearchMode) -> String {
+    t(match search_mode {
+        SearchMode::FullText => "search.mode_full_text",
+        SearchMode::TitlePrefix => "search.mode_title_prefix",
+        SearchMode::Exact => "search.mode_exact",
+    })
+}
+
+/// Switches to the next search mode and shows it in the title of the search bar. It's the
+/// callback for the search_mode keybinding
+pub fn on_search_mode_switch(siv: &mut Cursive) {
+    let search_mode = {
+        let mut search_mode = SEARCH_MODE.lock().unwrap();
+        *search_mode = search_mode.next();
+        *search_mode
+    };
+    log::info!("switched the search mode to '{:?}'", search_mode);
+
+    siv.call_on_name("search_dialog", |view: &mut Dialog| {
+        view.set_title(ui::wikis::search_title());
+    });
+}
+
+/// Removes every cached search. The cache has to be cleared when the wiki changes
+pub fn clear_search_cache() {
+    SEARCH_CACHE.clear();
 }
 
 /// Searches for a given query and displays the results. Returns an error if something went wrong.
 pub fn on_search(siv: &mut Cursive, search_query: String) {
     log::info!("on_search was called");
 
-    // do the search and if something went wrong, display an error message to the user
-    log::info!("searching for '{}'", search_query);
-    let search = match build_search().query(search_query.clone()).search() {
-        Ok(search) => search,
-        Err(error) => {
-            // log the error
-            log::warn!("{}", error);
-
-            // display an error message
-            siv.add_layer(
-                Dialog::info(
-                    "A Problem occurred while searching. \nCheck the logs for further information",
-                )
-                .title("Error")
-                .title_position(HAlign::Center),
-            );
-            log::info!("on_search failed to finish");
+    // the results of the live search are replaced by the ones of the full search
+    hide_live_results(siv);
+
+    // there is nothing to search for in an empty query
+    let full_query = normalize_query(&search_query);
+    let (wiki, query) = parse_language(&full_query);
+    let (search_mode, search_query) = parse_search_mode(query);
+    if search_query.is_empty() {
+        log::info!("the search query is empty, not searching");
+        siv.add_layer(
+            Dialog::info(t("search.empty"))
+                .title(t("search.title"))
+                .title_position(cursive::align::HAlign::Center),
+        );
+        return;
+    }
+
+    // remember the query so it can be searched for again, including the prefix of its mode
+    SEARCH_HISTORY.lock().unwrap().push(&full_query);
+    if let Some(category) = strip_category_prefix(query) {
+        on_category_search(siv, category.to_string(), wiki);
+        return;
+    }
+    search_in(siv, search_query.to_string(), search_mode, wiki);
+
+    log::info!("on_search finished successfully");
+}
+
+/// Helper function that searches a wiki for a query with a given mode in the background and
+/// displays the results when it's done
+fn search_in(siv: &mut Cursive, search_query: String, search_mode: SearchMode, wiki: Wiki) {
+    log::info!(
+        "searching for '{}' with the mode '{:?}' in '{}'",
+        search_query,
+        search_mode,
+        wiki.base_url
+    );
+    let search_builder = build_search_in(&wiki)
+        .mode(search_mode)
+        .query(search_query.clone());
+    run_search(
+        siv,
+        move || search_builder.search(),
+        t_args("search.loading", &[&search_query]),
+        move |s, search| {
+            let continuation = search_continuation(&search_query, search_mode, &wiki, &search);
+            let suggestion = suggested_query(&search_query, search.info().suggestion())
+                .map(|suggestion| (suggestion, search_mode));
+            display_search_results(s, search_query, wiki, search, continuation, suggestion)
+        },
+    );
+}
+
+/// Helper function that returns what the wiki suggests searching for instead of a query, if it
+/// suggests something else than the query
+fn suggested_query(search_query: &str, suggestion: Option<&str>) -> Option<String> {
+    let suggestion = suggestion?.trim();
+    if suggestion.is_empty() || suggestion.to_lowercase() == search_query.trim().to_lowercase() {
+        return None;
+    }
+    Some(suggestion.to_string())
+}
+
+/// Lists the pages of a category in the search results. The pages can be opened and filtered
+/// like search results
+fn on_category_search(siv: &mut Cursive, category: String, wiki: Wiki) {
+    log::info!(
+        "listing the pages of the category '{}' in '{}'",
+        category,
+        wiki.base_url
+    );
+    let title = format!("{}{}", CATEGORY_PREFIX, category);
+    let fetch_category = category.clone();
+    let fetch_wiki = wiki.clone();
+    run_search(
+        siv,
+        move || category_members(&fetch_wiki, &fetch_category, None),
+        t_args("search.loading", &[&title]),
+        move |s, (search, continuation)| {
+            let continuation = continuation
+                .map(|token| SearchContinuation::Category(category, token, wiki.clone()));
+            display_search_results(s, title, wiki, search, continuation, None)
+        },
+    );
+}
+
+/// Helper function that requests the pages of a category from a given wiki, beginning with
+/// the page of a continuation token. The pages are returned as a search, together with the token
+/// for continuing if there are more pages
+fn category_members(
+    wiki: &Wiki,
+    category: &str,
+    continuation: Option<&str>,
+) -> Result<(Search, Option<String>)> {
+    // without the network, there is nothing to request
+    if CONFIG.features.offline {
+        return Err(anyhow!(WikiError::Offline));
+    }
+
+    let members = category::category_members(
+        &wiki.base_url,
+        &wiki.script_path,
+        category,
+        CONFIG.settings.search.results_limit,
+        continuation,
+        Duration::from_millis(CONFIG.api_config.timeout_ms),
+        CONFIG.api_config.max_retries,
+        &CONFIG.api_config.user_agent,
+    )?;
+    Ok((
+        Search::new(None, SearchInfo::new(None, None, None), members.members),
+        members.continuation,
+    ))
+}
+
+/// Helper function that returns where a search for a query with a given mode in a wiki is
+/// continued, if there are more results
+fn search_continuation(
+    search_query: &str,
+    search_mode: SearchMode,
+    wiki: &Wiki,
+    search: &Search,
+) -> Option<SearchContinuation> {
+    search.search_offset().map(|offset| {
+        SearchContinuation::Search(search_query.to_string(), search_mode, offset, wiki.clone())
+    })
+}
+
+/// Helper function that returns the category of a query listing the pages of one. The prefix is
+/// matched ignoring the case, like the namespace is by the wikis
+fn strip_category_prefix(query: &str) -> Option<&str> {
+    let prefix = query.get(..CATEGORY_PREFIX.len())?;
+    let category = query[CATEGORY_PREFIX.len()..].trim_start();
+    (prefix.eq_ignore_ascii_case(CATEGORY_PREFIX) && !category.is_empty()).then_some(category)
+}
+
+/// Creates the hidden view displaying the results of the live search below the search bar
+pub fn live_search_view() -> NamedView<HideableView<SelectView<SearchResult>>> {
+    HideableView::new(SelectView::<SearchResult>::new().on_submit(
+        |s, search_result: &SearchResult| {
+            hide_live_results(s);
+            s.call_on_name("search_bar", |view: &mut EditView| {
+                view.set_content("");
+            });
+            ui::article::on_article_submit(s, search_result);
+        },
+    ))
+    .hidden()
+    .with_name("live_search_view")
+}
+
+/// Searches for the query in the search bar once the user stops typing and displays the results
+/// below the search bar. Nothing happens if the live search is disabled. It's the on_edit callback
+/// for the search bar
+pub fn on_search_edit(siv: &mut Cursive, query: &str) {
+    if !CONFIG.settings.search.live {
+        return;
+    }
+
+    let request = LIVE_SEARCH_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+    let full_query = normalize_query(query);
+    let (search_mode, search_query) = parse_search_mode(&full_query);
+    // the live results are always opened in the current wiki
+    if search_query.chars().count() < LIVE_SEARCH_MIN_LENGTH
+        || strip_category_prefix(&full_query).is_some()
+        || split_language_prefix(&full_query).is_some()
+    {
+        hide_live_results(siv);
+        return;
+    }
+
+    let search_builder = build_search()
+        .mode(search_mode)
+        .query(search_query.to_string())
+        .limit(LIVE_SEARCH_LIMIT);
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        // only search when nothing was typed in the meantime
+        thread::sleep(Duration::from_millis(CONFIG.settings.search.live_delay_ms));
+        if LIVE_SEARCH_REQUEST.load(Ordering::SeqCst) != request {
             return;
         }
-    };
+
+        let search = search_builder.search();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            if LIVE_SEARCH_REQUEST.load(Ordering::SeqCst) != request {
+                log::debug!("discarding the results of the live search '{}'", request);
+                return;
+            }
+
+            // errors would interrupt the typing, the full search displays them instead
+            match search {
+                Ok(search) => display_live_results(s, search),
+                Err(error) => log::warn!("{:?}", error),
+            }
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+}
+
+/// Helper function that displays the results of a live search below the search bar. Without any
+/// results, the view is hidden
+fn display_live_results(siv: &mut Cursive, search: Search) {
+    log::debug!(
+        "displaying '{}' results of the live search",
+        search.results().count()
+    );
+    siv.call_on_name(
+        "live_search_view",
+        |view: &mut HideableView<SelectView<SearchResult>>| {
+            let results_view = view.get_inner_mut();
+            results_view.clear();
+            for search_result in search.results() {
+                results_view.add_item(result_label(search_result), search_result.clone());
+            }
+            view.set_visible(!view.get_inner().is_empty());
+        },
+    );
+}
+
+/// Helper function that hides the results of the live search and discards the pending one
+fn hide_live_results(siv: &mut Cursive) {
+    LIVE_SEARCH_REQUEST.fetch_add(1, Ordering::SeqCst);
+    siv.call_on_name(
+        "live_search_view",
+        |view: &mut HideableView<SelectView<SearchResult>>| {
+            view.get_inner_mut().clear();
+            view.hide();
+        },
+    );
+}
+
+/// Helper function that trims a search query and collapses the whitespace inside of it
+fn normalize_query(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Helper function that splits the prefix of a language off a query, like 'de:' in 'de:Berlin'.
+/// The query is searched in the edition of the current wiki in that language. Without a prefix,
+/// or if the current wiki has no other languages, the current wiki is searched with the whole
+/// query
+fn parse_language(query: &str) -> (Wiki, &str) {
+    let wiki = ui::wikis::current_wiki();
+    match split_language_prefix(query)
+        .and_then(|(language, rest)| Some((ui::wikis::language_wiki(&wiki, language)?, rest)))
+    {
+        Some((language_wiki, rest)) => (language_wiki, rest),
+        None => (wiki, query),
+    }
+}
+
+/// Helper function that returns the language code at the start of a query and the rest of it
+fn split_language_prefix(query: &str) -> Option<(&str, &str)> {
+    if !CONFIG.settings.search.language_prefix {
+        return None;
+    }
+    let (language, rest) = query.split_once(':')?;
+    ui::wikis::is_language_code(language).then(|| (language, rest.trim_start()))
+}
+
+/// Helper function that splits the prefix of a mode off a query. Queries without a prefix are
+/// searched with the active search mode
+fn parse_search_mode(query: &str) -> (SearchMode, &str) {
+    for (prefix, search_mode) in SEARCH_MODE_PREFIXES {
+        if let Some(query) = query.strip_prefix(prefix) {
+            return (*search_mode, query.trim_start());
+        }
+    }
+    (search_mode(), query)
+}
+
+/// Helper function that runs a search on a background thread and calls on_finish with the search
+/// when it's done. In the meantime, a loading layer is shown which can be cancelled with Esc. If
+/// the search fails, an error message is displayed instead
+fn run_search<T, S, F>(siv: &mut Cursive, search: S, message: String, on_finish: F)
+where
+    T: Send + 'static,
+    S: FnOnce() -> Result<T> + Send + 'static,
+    F: FnOnce(&mut Cursive, T) + Send + 'static,
+{
+    let request = SEARCH_REQUEST.fetch_add(1, Ordering::SeqCst) + 1;
+    show_loading_view(siv, message, |_| {
+        log::info!("cancelling the search");
+        SEARCH_REQUEST.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let search = search();
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            if SEARCH_REQUEST.load(Ordering::SeqCst) != request {
+                log::debug!(
+                    "discarding the results of the cancelled search '{}'",
+                    request
+                );
+                return;
+            }
+            hide_loading_view(s);
+
+            match search {
+                Ok(search) => on_finish(s, search),
+                Err(error) => {
+                    log::warn!("{:?}", error);
+                    show_error(s, &t("error.searching"), &error);
+                }
+            }
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+}
+
+/// Helper function that moves the selection of the results view up or down by the configured
+/// amount of lines. At the first and the last result, the event is left to the view, so the focus
+/// can move to the filter or the continue button
+fn move_results_selection(
+    view: &mut SelectView<SearchResult>,
+    direction: Absolute,
+) -> Option<EventResult> {
+    let lines = CONFIG.settings.scroll_lines;
+    let selected = view.selected_id()?;
+    match direction {
+        Absolute::Up if lines > 1 && selected > 0 => {
+            Some(EventResult::Consumed(Some(view.select_up(lines))))
+        }
+        Absolute::Down if lines > 1 && selected + 1 < view.len() => {
+            Some(EventResult::Consumed(Some(view.select_down(lines))))
+        }
+        _ => None,
+    }
+}
+
+/// Helper function that displays the results of a search in a new layer. A query the wiki
+/// suggests instead is offered above the results, it's searched for with the given mode
+fn display_search_results(
+    siv: &mut Cursive,
+    search_query: String,
+    wiki: Wiki,
+    search: Search,
+    continuation: Option<SearchContinuation>,
+    suggestion: Option<(String, SearchMode)>,
+) {
+    log::debug!("display_search_results was called");
 
     // clear the search bar
     log::debug!("clearing the search bar");
@@ -52,8 +645,36 @@ pub fn on_search(siv: &mut Cursive, search_query: String) {
         view.set_content("");
     });
 
+    // a search with only one result doesn't need a list to choose from
+    if CONFIG.settings.search.auto_open_single_result
+        && search.results().count() == 1
+        && search.search_offset().is_none()
+    {
+        if let Some(search_result) = search.results().next() {
+            log::info!("opening the only search result");
+            ui::article::open_search_result(siv, &wiki.base_url, search_result);
+            return;
+        }
+    }
+
     // Create the views
 
+    // create the suggestion, selecting it searches for the suggested query instead
+    let search_suggestion = match suggestion {
+        Some((suggestion, search_mode)) => {
+            let wiki = wiki.clone();
+            HideableView::new(Button::new_raw(
+                t_args("search.suggestion", &[&suggestion]),
+                move |s| {
+                    log::info!("searching for the suggestion '{}'", suggestion);
+                    s.pop_layer();
+                    search_in(s, suggestion.clone(), search_mode, wiki.clone());
+                },
+            ))
+        }
+        None => HideableView::new(Button::new_raw("", |_| {})).hidden(),
+    };
+
     // create the results view letting the user select an result
     log::info!(
         "displaying '{}' out of '{}' search results",
@@ -61,20 +682,19 @@ pub fn on_search(siv: &mut Cursive, search_query: String) {
         search.info().total_hits().unwrap_or(&-1),
     );
     let mut search_results_view = SelectView::<SearchResult>::new()
-        .on_select(on_result_select)
-        .on_submit(ui::article::on_article_submit);
-
-    // create the continue button
-    let search_continue_button = {
-        let query = search_query.to_string();
-        let offset = search.search_offset().to_owned();
-        Button::new("Show more results...", move |s| {
-            if let Err(error) = continue_search(s, &query, &offset) {
-                log::warn!("{:?}", error);
+        .on_select(|s, item| {
+            on_result_select(s, item);
+            if CONFIG.settings.search.infinite_scroll {
+                on_results_scroll(s);
             }
         })
-        .with_name("search_continue_button")
-    };
+        .on_submit(move |s, item| ui::article::open_search_result(s, &wiki.base_url, item));
+
+    // create the continue button, it's disabled when there are no more results
+    let mut search_continue_button = Button::new(t("search.more"), continue_search);
+    search_continue_button.set_enabled(continuation.is_some());
+    *SEARCH_CONTINUATION.lock().unwrap() = continuation;
+    let search_continue_button = search_continue_button.with_name("search_continue_button");
 
     // create the results preview displaying previews of the currently selected article
     let search_results_preview = TextView::empty()
@@ -82,61 +702,87 @@ pub fn on_search(siv: &mut Cursive, search_query: String) {
         .with_name("search_results_preview")
         .fixed_width(50);
 
-    // create the info view showing the total hits
-    let mut search_info_view = TextView::empty();
+    // create the info view showing how many of the results are loaded
+    let total_hits = search.info().total_hits().copied();
+    *SEARCH_TOTAL_HITS.lock().unwrap() = total_hits;
+    let search_info_view = TextView::new(search_info(search.results().count(), total_hits, None))
+        .with_name("search_info_view");
     log::debug!("created the search results view, the search continue button, the search results preview and the search info view");
-    if let Some(total_hits) = search.info().total_hits() {
-        search_info_view.set_content(format!(
-            "Found {} articles matching your search",
-            total_hits
-        ));
-    }
 
     // save the first result so we can display its preview
     let first_result = search.results().next().cloned();
 
-    // add the search results to the results view
+    // add the search results to the results view and remember them for the filter
     log::debug!("adding the results to the search results view");
     for search_result in search.results() {
-        search_results_view.add_item(search_result.title().to_string(), search_result.to_owned())
-    }
-
-    // create the search results layout
-    let search_results_layout =
-        RootLayout::new(Orientation::Horizontal, CONFIG.keybindings.clone())
-            .child(view_with_theme!(
-                config::CONFIG.theme.search_results,
-                Dialog::around(
-                    LinearLayout::vertical()
-                        .child(
-                            search_results_view
-                                .with_name("search_results_view")
+        search_results_view.add_item(result_label(search_result), search_result.to_owned())
+    }
+    *SEARCH_RESULTS.lock().unwrap() = search.results().cloned().collect();
+
+    // create the filter, hiding the results that don't match it
+    let search_results_filter = EditView::new()
+        .on_edit(|s, filter, _| on_results_filter(s, filter))
+        .on_submit(|s, _| {
+            if let Err(error) = s.focus_name("search_results_view") {
+                log::warn!("{:?}", error);
+            }
+        })
+        .with_name("search_results_filter");
+
+    // create the search results layout. Only the results use the keybindings of a RootLayout, the
+    // filter gets every key that is typed into it
+    let search_results_layout = LinearLayout::horizontal()
+        .child(view_with_theme!(
+            config::CONFIG.theme.search_results,
+            Dialog::around(
+                LinearLayout::vertical()
+                    .child(
+                        LinearLayout::horizontal()
+                            .child(TextView::new(t("search.filter")))
+                            .child(search_results_filter.full_width())
+                    )
+                    .child(search_suggestion)
+                    .child(search_info_view)
+                    .child(
+                        RootLayout::new(Orientation::Vertical).child(
+                            OnEventView::new(search_results_view.with_name("search_results_view"))
+                                .on_pre_event_inner(Key::Up, |view, _| {
+                                    move_results_selection(&mut view.get_mut(), Absolute::Up)
+                                })
+                                .on_pre_event_inner(Key::Down, |view, _| {
+                                    move_results_selection(&mut view.get_mut(), Absolute::Down)
+                                })
                                 .scrollable()
                                 .min_height(10)
                         )
-                        .child(search_continue_button),
-                )
-            ))
-            .child(view_with_theme!(
-                config::CONFIG.theme.search_preview,
-                Dialog::around(search_results_preview)
-            ));
+                    )
+                    .child(search_continue_button),
+            )
+        ))
+        .child(view_with_theme!(
+            config::CONFIG.theme.search_preview,
+            Dialog::around(search_results_preview)
+        ));
     log::debug!("created the search results layout");
 
     // finally, add the whole thing as a new layer
     siv.add_layer(
-        Dialog::around(
-            LinearLayout::vertical()
-                .child(search_results_layout)
-                .child(search_info_view),
-        )
-        .title(format!("Results for \"{}\"", search_query))
-        .dismiss_button("Back")
-        .button("Quit", Cursive::quit)
+        Dialog::around(LinearLayout::vertical().child(StatusTracker::new(
+            StatusContext::SearchResults,
+            search_results_layout,
+        )))
+        .title(t_args("search.results_title", &[&search_query]))
+        .dismiss_button(t("dialog.back"))
+        .button(t("dialog.quit"), Cursive::quit)
         .max_height(20),
     );
     log::debug!("added the search view to the screen");
 
+    // the filter is above the results, but the results should be selected first
+    if let Err(error) = siv.focus_name("search_results_view") {
+        log::warn!("{:?}", error);
+    }
+
     // send a callback selecting the first search result
     log::debug!("sending the callback to select the first search result");
     if let Err(error) = siv.cb_sink().send(Box::new(|s| {
@@ -145,11 +791,128 @@ pub fn on_search(siv: &mut Cursive, search_query: String) {
         }
     })) {
         log::warn!("{:?}", error);
-        log::info!("on_search failed to finish");
+        log::debug!("display_search_results failed to finish");
         return;
     }
 
-    log::info!("on_search finished successfully");
+    log::debug!("display_search_results finished successfully");
+}
+
+/// Displays only the search results matching a given filter. An empty filter displays every
+/// result. It's the on_edit callback for the filter of the search results
+fn on_results_filter(siv: &mut Cursive, filter: &str) {
+    log::debug!("filtering the search results with '{}'", filter);
+
+    let selected_result = siv.call_on_name(
+        "search_results_view",
+        |view: &mut SelectView<SearchResult>| {
+            view.clear();
+            for search_result in SEARCH_RESULTS.lock().unwrap().iter() {
+                if matches_filter(search_result, filter) {
+                    view.add_item(result_label(search_result), search_result.clone());
+                }
+            }
+            view.selection()
+        },
+    );
+    update_search_info(siv, filter);
+
+    // show the preview of the first matching result
+    match selected_result.flatten() {
+        Some(search_result) => on_result_select(siv, &search_result),
+        None => {
+            siv.call_on_name("search_results_preview", |view: &mut TextView| {
+                view.set_content("");
+            });
+        }
+    }
+}
+
+/// Helper function that displays how many of the results are loaded and how many of them match
+/// a given filter
+fn update_search_info(siv: &mut Cursive, filter: &str) {
+    let info = {
+        let search_results = SEARCH_RESULTS.lock().unwrap();
+        let matching = (!filter.trim().is_empty()).then(|| {
+            search_results
+                .iter()
+                .filter(|search_result| matches_filter(search_result, filter))
+                .count()
+        });
+        search_info(
+            search_results.len(),
+            *SEARCH_TOTAL_HITS.lock().unwrap(),
+            matching,
+        )
+    };
+    siv.call_on_name("search_info_view", |view: &mut TextView| {
+        view.set_content(info)
+    });
+}
+
+/// Helper function that describes which of the results are loaded, out of how many there are in
+/// total, and how many of them match the filter, if there is one
+fn search_info(loaded: usize, total_hits: Option<i32>, matching: Option<usize>) -> String {
+    let info = match total_hits {
+        _ if loaded == 0 => t("search.no_results"),
+        Some(total_hits) => t_args(
+            "search.showing_total",
+            &[&1, &format_number(loaded), &format_number(total_hits)],
+        ),
+        None => t_args("search.showing", &[&1, &format_number(loaded)]),
+    };
+    match matching {
+        Some(matching) => t_args("search.info_filtered", &[&info, &format_number(matching)]),
+        None => info,
+    }
+}
+
+/// Helper function that checks if a search result matches a filter. Either its title or its
+/// snippet has to fuzzily match the filter
+fn matches_filter(search_result: &SearchResult, filter: &str) -> bool {
+    if filter.trim().is_empty() {
+        return true;
+    }
+
+    let snippet = search_result
+        .snippet()
+        .map(|snippet| {
+            snippet_parts(snippet, 0)
+                .into_iter()
+                .map(|(text, _)| text)
+                .collect::<String>()
+        })
+        .unwrap_or_default();
+    fuzzy_match(filter, search_result.title()) || fuzzy_match(filter, &snippet)
+}
+
+/// Helper function that checks if every character of the filter appears in the text in the same
+/// order, ignoring the case and whitespace of the filter
+pub fn fuzzy_match(filter: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    filter
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|filter_char| text.any(|text_char| text_char == filter_char))
+}
+
+/// Replaces the content of the search bar with the previous query in the search history
+pub fn on_search_history_previous(search_bar: &mut EditView) -> Option<EventResult> {
+    if let Some(query) = SEARCH_HISTORY.lock().unwrap().older() {
+        log::debug!("displaying the previous search query '{}'", query);
+        search_bar.set_content(query);
+    }
+    Some(EventResult::Consumed(None))
+}
+
+/// Replaces the content of the search bar with the next query in the search history
+pub fn on_search_history_next(search_bar: &mut EditView) -> Option<EventResult> {
+    if let Some(query) = SEARCH_HISTORY.lock().unwrap().newer() {
+        log::debug!("displaying the next search query '{}'", query);
+        search_bar.set_content(query);
+    }
+    Some(EventResult::Consumed(None))
 }
 
 /// Generates and displays a preview of a given search result. It's used as a callback for the
@@ -162,6 +925,25 @@ fn on_result_select(siv: &mut Cursive, item: &SearchResult) {
     );
 
     log::debug!("generating the preview");
+    let preview = generate_preview(item);
+
+    // set the content of the preview view to the generated preview
+    log::debug!("displaying the generated preivew");
+    let result = siv.call_on_name("search_results_preview", |view: &mut TextView| {
+        view.set_content(preview);
+    });
+    if result.is_none() {
+        log::warn!("couldn't find the search results preview view");
+        log::info!("on_result_select failed to finish");
+        return;
+    }
+
+    log::info!("on_result_select finished successfully");
+}
+
+/// Generates a preview of a given search result. The preview contains the title and the snippet
+/// of the result, with the search matches highlighted
+pub fn generate_preview(item: &SearchResult) -> StyledString {
     let mut preview = StyledString::new();
 
     // add the title to the preview
@@ -171,62 +953,295 @@ fn on_result_select(siv: &mut Cursive, item: &SearchResult) {
     // only go through this if we have a snippet
     if let Some(snippet) = item.snippet() {
         log::debug!("found a snippet for the result, adding it to the preview now");
-        let splitted_snippet: Vec<&str> = snippet.split(r#"<span class="searchmatch">"#).collect();
-
-        // go through every slice of the splitted_snippet and if it contains </span>,
-        // split the slice again and make the first split red
-        for slice in splitted_snippet {
-            if slice.contains("</span>") {
-                let split_slice: Vec<&str> = slice.split("</span>").collect();
-
-                preview.append(StyledString::styled(
-                    split_slice[0],
-                    config::CONFIG.theme.search_match,
-                ));
-                preview.append_plain(split_slice[1]);
-            } else {
-                preview.append_plain(slice);
+        preview.append(styled_snippet(
+            snippet,
+            config::CONFIG.settings.search.preview_length,
+        ));
+        preview.append_plain("...");
+    }
+
+    // add when the article was edited and how long it is, if the search returned it
+    if let (Some(timestamp), Some(wordcount)) = (item.timestamp(), item.wordcount()) {
+        preview.append_plain(format!(
+            "\n\n{}",
+            t_args(
+                "search.result_info",
+                &[&format_date(timestamp), &format_number(wordcount)]
+            )
+        ));
+    }
+
+    preview
+}
+
+/// Helper function that returns the label of a search result in the results view. The parts of
+/// the title matching the query are highlighted. With columns, the title is cut off so the
+/// columns of every result line up
+fn result_label(search_result: &SearchResult) -> StyledString {
+    let columns = &CONFIG.settings.search.columns;
+    let length = if columns.is_empty() {
+        0
+    } else {
+        RESULT_TITLE_WIDTH
+    };
+
+    let mut label = match search_result.title_snippet() {
+        Some(title_snippet) if !title_snippet.trim().is_empty() => {
+            styled_snippet(title_snippet, length)
+        }
+        _ => styled_snippet(search_result.title(), length),
+    };
+    if columns.is_empty() {
+        return label;
+    }
+
+    let padding = RESULT_TITLE_WIDTH.saturating_sub(label.width());
+    label.append_plain(" ".repeat(padding));
+    for column in columns {
+        let (text, width) = column_text(search_result, *column);
+        label.append_styled(
+            format!("  {:>1$}", text, width),
+            Style::from(CONFIG.theme.text).combine(Effect::Italic),
+        );
+    }
+    label
+}
+
+/// Helper function that returns the text of a column of a search result together with the width
+/// of the column. Missing metadata leaves the column empty
+fn column_text(search_result: &SearchResult, column: SearchColumn) -> (String, usize) {
+    match column {
+        SearchColumn::WORDCOUNT => (
+            search_result
+                .wordcount()
+                .map(|wordcount| t_args("search.result_words", &[&format_number(wordcount)]))
+                .unwrap_or_default(),
+            13,
+        ),
+        SearchColumn::EDITED => (
+            search_result
+                .timestamp()
+                .map(format_date)
+                .unwrap_or_default(),
+            10,
+        ),
+        SearchColumn::SIZE => (
+            search_result
+                .size()
+                .map(|size| format_size((*size).max(0) as u64))
+                .unwrap_or_default(),
+            8,
+        ),
+    }
+}
+
+/// Helper function that converts a snippet into styled text, highlighting the search matches with
+/// the search_match color. The text is cut after a given amount of characters, 0 means no limit
+fn styled_snippet(snippet: &str, length: usize) -> StyledString {
+    let mut styled = StyledString::new();
+    for (text, is_match) in snippet_parts(snippet, length) {
+        if is_match {
+            styled.append_styled(
+                text,
+                Style::from(config::CONFIG.theme.search_match).combine(Effect::Bold),
+            );
+        } else {
+            styled.append_plain(text);
+        }
+    }
+    styled
+}
+
+/// Helper function that splits the snippet of a search result into its text and whether the text
+/// is a search match. Other markup is removed, entities are decoded and whitespace is collapsed.
+/// The text is cut after a given amount of characters, 0 means no limit
+fn snippet_parts(snippet: &str, length: usize) -> Vec<(String, bool)> {
+    let mut parts: Vec<(String, bool)> = Vec::new();
+    let mut is_match = false;
+    let mut last_whitespace = true;
+    let mut count = 0;
+
+    let mut chars = snippet.chars();
+    while let Some(char) = chars.next() {
+        let char = match char {
+            '<' => {
+                let tag = chars
+                    .by_ref()
+                    .take_while(|&char| char != '>')
+                    .collect::<String>();
+                if tag.starts_with("span") && tag.contains("searchmatch") {
+                    is_match = true;
+                } else if tag == "/span" {
+                    is_match = false;
+                }
+                continue;
             }
+            '&' => {
+                // entities are short, anything longer is a plain '&'
+                let entity = chars
+                    .clone()
+                    .take(10)
+                    .take_while(|&char| char != ';')
+                    .collect::<String>();
+                match decode_entity(&entity) {
+                    Some(decoded) if chars.clone().nth(entity.chars().count()) == Some(';') => {
+                        chars.nth(entity.chars().count());
+                        decoded
+                    }
+                    _ => '&',
+                }
+            }
+            char => char,
+        };
+
+        if char.is_whitespace() {
+            if last_whitespace {
+                continue;
+            }
+            last_whitespace = true;
+        } else {
+            last_whitespace = false;
+        }
+
+        if length != 0 && count == length {
+            break;
+        }
+        count += 1;
+
+        let char = if char.is_whitespace() { ' ' } else { char };
+        match parts.last_mut() {
+            Some((text, part_is_match)) if *part_is_match == is_match => text.push(char),
+            _ => parts.push((char.to_string(), is_match)),
         }
-        preview.append_plain("...");
     }
 
-    // set the content of the preview view to the generated preview
-    log::debug!("displaying the generated preivew");
-    let result = siv.call_on_name("search_results_preview", |view: &mut TextView| {
-        view.set_content(preview);
-    });
-    if result.is_none() {
-        log::warn!("couldn't find the search results preview view");
-        log::info!("on_result_select failed to finish");
-        return;
+    // remove the whitespace at the end
+    if let Some((text, _)) = parts.last_mut() {
+        let trimmed = text.trim_end().len();
+        text.truncate(trimmed);
+        if text.is_empty() {
+            parts.pop();
+        }
     }
+    parts
+}
 
-    log::info!("on_result_select finished successfully");
+/// Helper function that decodes an html entity without its '&' and ';'
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = entity.strip_prefix('#')?;
+            match code.strip_prefix('x').or_else(|| code.strip_prefix('X')) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => code.parse().ok(),
+            }
+            .and_then(char::from_u32)
+        }
+    }
 }
 
-/// Searches for more results at a given offset and adds them to the results view. It's a callback
-/// for the continue button and returns an error if something went wrong
-fn continue_search(siv: &mut Cursive, search_query: &str, search_offset: &usize) -> Result<()> {
-    log::info!(
-        "continue_search was called for the query '{}' with the offset '{}'",
-        search_query,
-        search_offset
-    );
+/// Continues the search when the last result is selected, so scrolling through the results loads
+/// more of them. Nothing happens while another search is running
+fn on_results_scroll(siv: &mut Cursive) {
+    let is_last_selected = siv
+        .call_on_name(
+            "search_results_view",
+            |view: &mut SelectView<SearchResult>| {
+                view.selected_id().map(|id| id + 1) == Some(view.len())
+            },
+        )
+        .unwrap_or_default();
+    let is_loading = siv
+        .screen_mut()
+        .find_layer_from_name("loading_view")
+        .is_some();
 
-    // fetch more results
+    if is_last_selected && !is_loading {
+        log::debug!("the last result is selected, continuing the search");
+        continue_search(siv);
+    }
+}
+
+/// Searches for more results of the displayed search and adds them to the results view. If there
+/// are no more results, nothing happens. It's the callback for the continue button
+fn continue_search(siv: &mut Cursive) {
+    let continuation = match SEARCH_CONTINUATION.lock().unwrap().clone() {
+        Some(continuation) => continuation,
+        None => {
+            log::info!("there are no more results to load");
+            return;
+        }
+    };
+
+    // fetch more results in the background
     log::info!("fetching more results");
-    let search = build_search()
-        .query(search_query.to_string())
-        .offset(*search_offset)
-        .search()?;
+    match continuation {
+        SearchContinuation::Search(search_query, search_mode, search_offset, wiki) => {
+            log::info!(
+                "continue_search was called for the query '{}' with the offset '{}'",
+                search_query,
+                search_offset
+            );
+            let search_builder = build_search_in(&wiki)
+                .mode(search_mode)
+                .query(search_query.clone())
+                .offset(search_offset);
+            run_search(
+                siv,
+                move || search_builder.search(),
+                t("search.loading_more"),
+                move |s, search| {
+                    let continuation =
+                        search_continuation(&search_query, search_mode, &wiki, &search);
+                    if let Err(error) = add_search_results(s, search, continuation) {
+                        log::warn!("{:?}", error);
+                    }
+                },
+            );
+        }
+        SearchContinuation::Category(category, token, wiki) => {
+            log::info!(
+                "continue_search was called for the category '{}' with the token '{}'",
+                category,
+                token
+            );
+            let fetch_category = category.clone();
+            let fetch_wiki = wiki.clone();
+            run_search(
+                siv,
+                move || category_members(&fetch_wiki, &fetch_category, Some(&token)),
+                t("search.loading_more"),
+                move |s, (search, continuation)| {
+                    let continuation = continuation
+                        .map(|token| SearchContinuation::Category(category, token, wiki));
+                    if let Err(error) = add_search_results(s, search, continuation) {
+                        log::warn!("{:?}", error);
+                    }
+                },
+            );
+        }
+    }
+}
 
+/// Helper function that adds the results of a continued search to the results view. Any errors it
+/// encounters are returned
+fn add_search_results(
+    siv: &mut Cursive,
+    search: Search,
+    continuation: Option<SearchContinuation>,
+) -> Result<()> {
     // get the results view so we can add some results to it
     log::debug!("getting the search results view");
     let mut search_results_views = siv
         .find_name::<SelectView<SearchResult>>("search_results_view")
         .with_context(|| {
-            log::info!("continue_search failed to finish");
+            log::info!("add_search_results failed to finish");
             "Couldn't find the search results view"
         })?;
 
@@ -235,36 +1250,302 @@ fn continue_search(siv: &mut Cursive, search_query: &str, search_offset: &usize)
         "adding '{}' results to the search results view",
         search.results().count()
     );
+    let filter = siv
+        .find_name::<EditView>("search_results_filter")
+        .map(|view| view.get_content().to_string())
+        .unwrap_or_default();
     for search_result in search.results() {
-        search_results_views.add_item(search_result.title(), search_result.clone())
+        SEARCH_RESULTS.lock().unwrap().push(search_result.clone());
+        if matches_filter(search_result, &filter) {
+            search_results_views.add_item(result_label(search_result), search_result.clone())
+        }
     }
+    if let Some(total_hits) = search.info().total_hits() {
+        *SEARCH_TOTAL_HITS.lock().unwrap() = Some(*total_hits);
+    }
+    update_search_info(siv, &filter);
 
-    // get the continue button so we can change its callback
-    log::debug!("modifying the callback of the search continue button");
+    // get the continue button so we can disable it when there are no more results
+    log::debug!("updating the search continue button");
     let mut search_continue_button = siv
         .find_name::<Button>("search_continue_button")
         .with_context(|| {
-            log::info!("continue_search failed to finish");
+            log::info!("add_search_results failed to finish");
             "Couldn't find the search continue button"
         })?;
 
-    // modify the callback of the continue button so we don't search for the same thing again
-    {
-        let query = search_query.to_string();
-        search_continue_button.set_callback(move |s| {
-            if let Err(error) = continue_search(s, &query, search.search_offset()) {
-                log::warn!("{:?}", error);
-            }
-        });
-    }
+    // remember where to continue, so we don't search for the same thing again
+    search_continue_button.set_enabled(continuation.is_some());
+    *SEARCH_CONTINUATION.lock().unwrap() = continuation;
 
     // focus the results view
     siv.focus_name("search_results_view").with_context(|| {
-        log::info!("continue_search failed to finish");
+        log::info!("add_search_results failed to finish");
         "Failed to focus the search results view"
     })?;
     log::debug!("focussed the search results view");
 
-    log::info!("continue_search finished successfully");
+    log::info!("add_search_results finished successfully");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        column_text, display_search_results, fuzzy_match, normalize_query, parse_search_mode,
+        search_info, snippet_parts, split_language_prefix, strip_category_prefix, styled_snippet,
+        suggested_query, SearchHistory, SearchMode,
+    };
+    use crate::config::Wiki;
+    use crate::config::{SearchColumn, CONFIG};
+    use crate::ui::wikis::{current_wiki, language_wiki};
+    use crate::wiki::search::{Search, SearchInfo, SearchResult};
+    use cursive::event::Event;
+    use cursive::theme::{Effect, Style};
+    use cursive::views::EditView;
+    use cursive::Cursive;
+
+    #[test]
+    fn suggested_queries() {
+        assert_eq!(
+            suggested_query("menaing", Some("meaning")).as_deref(),
+            Some("meaning")
+        );
+        assert_eq!(suggested_query("Meaning", Some("meaning")), None);
+        assert_eq!(suggested_query("meaning", Some(" ")), None);
+        assert_eq!(suggested_query("meaning", None), None);
+    }
+
+    #[test]
+    fn category_prefixes() {
+        assert_eq!(
+            strip_category_prefix("Category: Programming languages"),
+            Some("Programming languages")
+        );
+        assert_eq!(strip_category_prefix("category:Physics"), Some("Physics"));
+        assert_eq!(strip_category_prefix("category:"), None);
+        assert_eq!(strip_category_prefix("Physics"), None);
+    }
+
+    #[test]
+    fn column_texts() {
+        let search_result = |size, wordcount, timestamp: Option<&str>| {
+            SearchResult::new(
+                "Rust".to_string(),
+                0,
+                1,
+                size,
+                wordcount,
+                timestamp.map(str::to_string),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        };
+
+        let rust = search_result(Some(2500), Some(1200), Some("2022-05-01T10:00:00Z"));
+        assert_eq!(
+            column_text(&rust, SearchColumn::WORDCOUNT),
+            ("1,200 words".to_string(), 13)
+        );
+        assert_eq!(
+            column_text(&rust, SearchColumn::EDITED),
+            ("2022-05-01".to_string(), 10)
+        );
+        assert_eq!(
+            column_text(&rust, SearchColumn::SIZE),
+            ("2.5 KB".to_string(), 8)
+        );
+        assert_eq!(
+            column_text(&search_result(None, None, None), SearchColumn::SIZE),
+            (String::new(), 8)
+        );
+    }
+
+    #[test]
+    fn normalize_queries() {
+        assert_eq!(
+            normalize_query("  rust \t programming\n language "),
+            "rust programming language"
+        );
+        assert_eq!(normalize_query(" \t\n "), "");
+    }
+
+    #[test]
+    fn search_infos() {
+        assert_eq!(
+            search_info(20, Some(232618), None),
+            "Showing 1–20 of 232,618 results"
+        );
+        assert_eq!(search_info(40, None, None), "Showing 1–40 results");
+        assert_eq!(
+            search_info(20, Some(25), Some(3)),
+            "Showing 1–20 of 25 results, 3 match the filter"
+        );
+        assert_eq!(search_info(0, Some(0), None), "No results");
+    }
+
+    #[test]
+    fn language_prefixes() {
+        assert_eq!(split_language_prefix("de:Berlin"), Some(("de", "Berlin")));
+        assert_eq!(
+            split_language_prefix("zh-yue: 香港"),
+            Some(("zh-yue", "香港"))
+        );
+        assert_eq!(split_language_prefix("title:Rust"), None);
+        assert_eq!(split_language_prefix("Help:Contents"), None);
+        assert_eq!(split_language_prefix("Berlin"), None);
+    }
+
+    #[test]
+    fn language_wikis() {
+        let wiki = |base_url: &str| Wiki {
+            name: String::new(),
+            base_url: base_url.to_string(),
+            script_path: "w/".to_string(),
+        };
+        assert_eq!(
+            language_wiki(&wiki("https://en.wikipedia.org/"), "de")
+                .map(|wiki| wiki.base_url)
+                .as_deref(),
+            Some("https://de.wikipedia.org/")
+        );
+        assert!(language_wiki(&wiki("https://wiki.archlinux.org/"), "de").is_none());
+        assert!(language_wiki(&wiki("https://en.localhost/"), "de").is_none());
+    }
+
+    #[test]
+    fn search_mode_prefixes() {
+        assert_eq!(
+            parse_search_mode("title: Rust"),
+            (SearchMode::TitlePrefix, "Rust")
+        );
+        assert_eq!(
+            parse_search_mode("exact:rust lang"),
+            (SearchMode::Exact, "rust lang")
+        );
+        assert_eq!(parse_search_mode("text:"), (SearchMode::FullText, ""));
+        assert_eq!(
+            parse_search_mode("intitle:Rust"),
+            (SearchMode::default(), "intitle:Rust")
+        );
+    }
+
+    #[test]
+    fn fuzzy_matching() {
+        assert!(fuzzy_match("rst", "Rust (programming language)"));
+        assert!(fuzzy_match("prog lang", "Rust (programming language)"));
+        assert!(fuzzy_match("", "Rust"));
+        assert!(!fuzzy_match("tsur", "Rust"));
+        assert!(!fuzzy_match("rusty", "Rust"));
+    }
+
+    #[test]
+    fn older_and_newer() {
+        let mut search_history = SearchHistory::new(10);
+        search_history.push("rust");
+        search_history.push("linux");
+
+        assert_eq!(search_history.newer(), None);
+        assert_eq!(search_history.older(), Some("linux"));
+        assert_eq!(search_history.older(), Some("rust"));
+        assert_eq!(search_history.older(), None);
+        assert_eq!(search_history.newer(), Some("linux"));
+        assert_eq!(search_history.newer(), Some(""));
+        assert_eq!(search_history.newer(), None);
+    }
+
+    #[test]
+    fn deduplicate_consecutive() {
+        let mut search_history = SearchHistory::new(10);
+        search_history.push("rust");
+        search_history.push("rust");
+        search_history.push("linux");
+        search_history.push("rust");
+        search_history.push("  ");
+
+        assert_eq!(search_history.last(10), vec!["rust", "linux", "rust"]);
+    }
+
+    #[test]
+    fn size_cap() {
+        let mut search_history = SearchHistory::new(2);
+        search_history.push("rust");
+        search_history.push("linux");
+        search_history.push("wikipedia");
+
+        assert_eq!(search_history.last(10), vec!["wikipedia", "linux"]);
+        assert_eq!(search_history.last(1), vec!["wikipedia"]);
+    }
+
+    #[test]
+    fn snippets() {
+        let snippet = r#"<span class="searchmatch">Rust</span> is a   multi-paradigm,&#32;<b>general</b>-purpose
+            &quot;language&quot; &amp; more "#;
+        assert_eq!(
+            snippet_parts(snippet, 0),
+            vec![
+                ("Rust".to_string(), true),
+                (
+                    " is a multi-paradigm, general-purpose \"language\" & more".to_string(),
+                    false
+                ),
+            ]
+        );
+        assert_eq!(
+            snippet_parts(snippet, 12),
+            vec![("Rust".to_string(), true), (" is a mu".to_string(), false)]
+        );
+        assert_eq!(
+            snippet_parts("Q&A &unknown; 1 &lt 2", 0),
+            vec![("Q&A &unknown; 1 &lt 2".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn styled_snippets() {
+        let styled = styled_snippet(
+            r#"The <span class="searchmatch">Rust</span> <i>language</i>"#,
+            0,
+        );
+        assert_eq!(styled.source(), "The Rust language");
+
+        let spans = styled.spans().collect::<Vec<_>>();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[1].content, "Rust");
+        assert_eq!(
+            *spans[1].attr,
+            Style::from(CONFIG.theme.search_match).combine(Effect::Bold)
+        );
+        assert_eq!(*spans[2].attr, Style::none());
+    }
+
+    #[test]
+    fn filter_keys() {
+        let mut siv = Cursive::new();
+        let search = Search::new(None, SearchInfo::new(None, None, None), Vec::new());
+        display_search_results(
+            &mut siv,
+            "rust".to_string(),
+            current_wiki(),
+            search,
+            None,
+            None,
+        );
+
+        // 'H' goes back in the history of the article, but it's typed into the filter
+        siv.focus_name("search_results_filter").unwrap();
+        siv.on_event(Event::Char('H'));
+        let filter = siv
+            .call_on_name("search_results_filter", |view: &mut EditView| {
+                view.get_content()
+            })
+            .unwrap();
+        assert_eq!(filter.as_str(), "H");
+    }
+}
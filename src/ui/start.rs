@@ -0,0 +1,82 @@
+use crate::{
+    config::{StartAction, CONFIG},
+    ui::{
+        self,
+        messages::t,
+        reading_log::{self, ReadingLogEntry},
+    },
+    LOGO,
+};
+
+use cursive::align::HAlign;
+use cursive::view::{Nameable, Resizable};
+use cursive::views::{Dialog, DummyView, LinearLayout, SelectView, TextView};
+use cursive::{Cursive, View};
+
+/// Creates the start screen that is displayed until the first article is opened. It contains the
+/// logo, the quick actions and the articles read last, as far as they are enabled. If nothing of
+/// it is enabled, there is no start screen
+pub fn start_view() -> Option<impl View> {
+    let mut start_layout = LinearLayout::vertical();
+    if CONFIG.features.logo {
+        start_layout.add_child(TextView::new(LOGO).h_align(HAlign::Center));
+    }
+
+    if !CONFIG.settings.start_screen.actions.is_empty() {
+        let mut actions_view = SelectView::<StartAction>::new()
+            .h_align(HAlign::Center)
+            .on_submit(on_action_submit);
+        for action in CONFIG.settings.start_screen.actions.iter() {
+            actions_view.add_item(action_name(action), *action);
+        }
+        start_layout.add_child(DummyView);
+        start_layout.add_child(actions_view);
+    }
+
+    let recent_articles =
+        reading_log::recent_articles(CONFIG.settings.start_screen.recent_articles);
+    if !recent_articles.is_empty() {
+        let mut recent_view = SelectView::<ReadingLogEntry>::new()
+            .h_align(HAlign::Center)
+            .on_submit(reading_log::open_entry);
+        for entry in recent_articles {
+            recent_view.add_item(entry.title().to_string(), entry);
+        }
+        start_layout.add_child(DummyView);
+        start_layout.add_child(TextView::new(t("start.recent_title")).h_align(HAlign::Center));
+        start_layout.add_child(recent_view);
+    }
+
+    if start_layout.is_empty() {
+        log::debug!("the start screen is empty, it isn't displayed");
+        return None;
+    }
+    Some(Dialog::around(
+        start_layout.with_name("start_view").full_screen(),
+    ))
+}
+
+/// Runs a quick action. It's the on_submit callback for the actions of the start screen
+fn on_action_submit(siv: &mut Cursive, action: &StartAction) {
+    log::info!("running the quick action '{:?}'", action);
+    match action {
+        StartAction::SEARCH => {
+            if let Err(error) = siv.focus_name("search_bar") {
+                log::info!("couldn't focus the search bar: {:?}", error);
+            }
+        }
+        StartAction::RANDOM => ui::article::on_random_article(siv),
+        StartAction::RECENT => ui::reading_log::on_reading_log_show(siv),
+        StartAction::BOOKMARKS => ui::bookmarks::on_bookmarks_show(siv),
+    }
+}
+
+/// Helper function that returns the label of a quick action
+fn action_name(action: &StartAction) -> String {
+    t(match action {
+        StartAction::SEARCH => "start.search",
+        StartAction::RANDOM => "start.random",
+        StartAction::RECENT => "start.recent",
+        StartAction::BOOKMARKS => "start.bookmarks",
+    })
+}
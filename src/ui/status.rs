@@ -0,0 +1,275 @@
+use crate::{
+    config::{keybinding_name, CONFIG},
+    ui::{messages::t, root},
+};
+
+use cursive::direction::Direction;
+use cursive::event::{Event, EventResult};
+use cursive::theme::{ColorStyle, Effect, Style};
+use cursive::utils::markup::StyledString;
+use cursive::view::{CannotFocus, View, ViewWrapper};
+use cursive::{wrap_impl, Cursive, Printer, Vec2};
+use std::cell::{Cell, RefCell};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a message is displayed in the status bar
+const MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+thread_local! {
+    /// The kind of view that was focused last, it decides which keybindings the status bar shows
+    static FOCUSED: Cell<StatusContext> = const { Cell::new(StatusContext::SearchBar) };
+    /// The message the status bar shows instead of the keybindings and when it was displayed
+    static MESSAGE: RefCell<Option<(String, Instant)>> = const { RefCell::new(None) };
+}
+
+/// The kinds of views the status bar shows the keybindings of
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum StatusContext {
+    SearchBar,
+    SearchResults,
+    Article,
+    Toc,
+    /// Any other dialog on top of the main view
+    Dialog,
+}
+
+/// A wrapper that tells the status bar when its view is focused
+pub struct StatusTracker<T> {
+    /// The kind of the view
+    context: StatusContext,
+    /// The view
+    view: T,
+}
+
+impl<T> StatusTracker<T> {
+    /// Creates a new StatusTracker for a given kind of view
+    pub fn new(context: StatusContext, view: T) -> Self {
+        StatusTracker { context, view }
+    }
+
+    /// Helper function that remembers the view as the focused one
+    fn set_focused(&self) {
+        FOCUSED.with(|focused| focused.set(self.context));
+    }
+}
+
+impl<T: View> ViewWrapper for StatusTracker<T> {
+    wrap_impl!(self.view: T);
+
+    // the view is drawn as focused before the status bar is drawn, so the status bar is always
+    // up to date for the views of the main layer. Layers on top are focused when they're added
+    fn wrap_draw(&self, printer: &Printer) {
+        if printer.focused {
+            self.set_focused();
+        }
+        self.view.draw(printer);
+    }
+
+    fn wrap_take_focus(&mut self, source: Direction) -> Result<EventResult, CannotFocus> {
+        let result = self.view.take_focus(source);
+        if result.is_ok() {
+            self.set_focused();
+        }
+        result
+    }
+
+    fn wrap_on_event(&mut self, event: Event) -> EventResult {
+        if event != Event::FocusLost {
+            self.set_focused();
+        }
+        self.view.on_event(event)
+    }
+}
+
+/// A bar showing the most important keybindings of the focused view
+pub struct StatusBar;
+
+impl StatusBar {
+    /// Creates a new StatusBar
+    pub fn new() -> Self {
+        StatusBar
+    }
+}
+
+impl Default for StatusBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View for StatusBar {
+    fn draw(&self, printer: &Printer) {
+        // without the focus, a dialog is displayed on top of the main layer. Only the search
+        // results tell the status bar about themselves
+        let context = match FOCUSED.with(Cell::get) {
+            StatusContext::SearchResults => StatusContext::SearchResults,
+            _ if !printer.focused => StatusContext::Dialog,
+            context => context,
+        };
+        printer.with_color(ColorStyle::secondary(), |printer| {
+            printer.print_hline((0, 0), printer.size.x, " ");
+            match current_message() {
+                Some(message) => printer.print((1, 0), &message),
+                None => printer.print_styled((1, 0), (&status_text(context)).into()),
+            }
+        });
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        Vec2::new(constraint.x, 1)
+    }
+}
+
+/// Displays a message in the status bar for a few seconds, without taking the focus from the
+/// focused view. Without the status bar, the message is only logged
+pub fn display_message(siv: &mut Cursive, message: String) {
+    log::info!("displaying the message '{}'", message);
+    MESSAGE.with(|current| *current.borrow_mut() = Some((message, Instant::now())));
+
+    // the status bar only removes the message when it's drawn again
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        thread::sleep(MESSAGE_DURATION);
+        if let Err(error) = cb_sink.send(Box::new(|_| {})) {
+            log::warn!("{:?}", error);
+        }
+    });
+}
+
+/// Helper function that returns the displayed message, if there is one that hasn't expired yet
+fn current_message() -> Option<String> {
+    MESSAGE.with(|current| {
+        let mut current = current.borrow_mut();
+        match current.as_ref() {
+            Some((message, displayed_at)) if displayed_at.elapsed() < MESSAGE_DURATION => {
+                Some(message.clone())
+            }
+            _ => {
+                current.take();
+                None
+            }
+        }
+    })
+}
+
+/// Helper function that returns the text of the status bar for a given kind of view
+fn status_text(context: StatusContext) -> StyledString {
+    let mut text = StyledString::new();
+    for (i, (key, id)) in status_hints(context).into_iter().enumerate() {
+        if i > 0 {
+            text.append_plain("  ");
+        }
+        text.append_styled(
+            key,
+            Style::from(ColorStyle::secondary()).combine(Effect::Bold),
+        );
+        text.append_plain(format!(" {}", t(id)));
+    }
+    text
+}
+
+/// Helper function that returns the keys and the ids of the descriptions of the keybindings shown
+/// for a given kind of view. The keys are read from the configuration, so remapped keybindings are
+/// shown correctly
+fn status_hints(context: StatusContext) -> Vec<(String, &'static str)> {
+    let keybindings = root::keybindings();
+    let keys = |first: &Event, second: &Event| {
+        format!("{}/{}", keybinding_name(first), keybinding_name(second))
+    };
+
+    let mut hints = match context {
+        StatusContext::SearchBar => vec![
+            ("Enter".to_string(), "status.search"),
+            ("Up/Down".to_string(), "status.search_history"),
+            (
+                keybinding_name(&CONFIG.keybindings.search_mode),
+                "status.search_mode",
+            ),
+            (
+                keybinding_name(&keybindings.focus_next),
+                "status.focus_next",
+            ),
+        ],
+        StatusContext::SearchResults => vec![
+            ("Enter".to_string(), "status.open"),
+            (keys(&keybindings.up, &keybindings.down), "status.select"),
+            (
+                keybinding_name(&keybindings.focus_next),
+                "status.focus_next",
+            ),
+        ],
+        StatusContext::Article => vec![
+            (keys(&keybindings.up, &keybindings.down), "status.scroll"),
+            (
+                keys(&keybindings.left, &keybindings.right),
+                "status.select_link",
+            ),
+            ("Enter".to_string(), "status.open_link"),
+            (
+                keybinding_name(&CONFIG.keybindings.link_hints),
+                "status.link_hints",
+            ),
+            (
+                keybinding_name(&CONFIG.keybindings.search_article),
+                "status.search_article",
+            ),
+            (
+                keybinding_name(&CONFIG.keybindings.toggle_bookmark),
+                "status.bookmark",
+            ),
+            (
+                keybinding_name(&keybindings.history_back),
+                "status.history_back",
+            ),
+        ],
+        StatusContext::Toc => vec![
+            ("Enter".to_string(), "status.jump"),
+            (
+                keybinding_name(&CONFIG.keybindings.toggle_toc_section),
+                "status.toggle_section",
+            ),
+            (
+                keybinding_name(&CONFIG.keybindings.open_section),
+                "status.open_section",
+            ),
+        ],
+        StatusContext::Dialog => vec![
+            ("Enter".to_string(), "status.select"),
+            ("Esc".to_string(), "status.close"),
+        ],
+    };
+    hints.push((
+        keybinding_name(&CONFIG.keybindings.command_palette),
+        "status.commands",
+    ));
+    hints.push(("q".to_string(), "status.quit"));
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_message, status_hints, StatusContext, MESSAGE};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn hints() {
+        let hints = status_hints(StatusContext::Article);
+        assert!(hints.contains(&("Up/Down".to_string(), "status.scroll")));
+        assert!(hints.contains(&("f".to_string(), "status.link_hints")));
+        assert_eq!(hints.last(), Some(&("q".to_string(), "status.quit")));
+    }
+
+    #[test]
+    fn messages() {
+        assert_eq!(current_message(), None);
+
+        MESSAGE
+            .with(|message| *message.borrow_mut() = Some(("Opened".to_string(), Instant::now())));
+        assert_eq!(current_message(), Some("Opened".to_string()));
+
+        let expired = Instant::now() - Duration::from_secs(60);
+        MESSAGE.with(|message| *message.borrow_mut() = Some(("Opened".to_string(), expired)));
+        assert_eq!(current_message(), None);
+    }
+}
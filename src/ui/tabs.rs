@@ -0,0 +1,411 @@
+use crate::config::CONFIG;
+use crate::ui::{
+    article::History,
+    messages::t,
+    reading_log,
+    toc::{self, TocState},
+    RootLayout,
+};
+
+use cursive::direction::Direction;
+use cursive::event::{AnyCb, Event, EventResult};
+use cursive::theme::ColorStyle;
+use cursive::view::{CannotFocus, IntoBoxedView, Nameable, Selector, View, ViewNotFound};
+use cursive::views::NamedView;
+use cursive::{Cursive, Printer, Rect, Vec2};
+
+/// The longest title of a tab in the tab bar, longer titles are shortened
+const MAX_TITLE_WIDTH: usize = 20;
+
+/// A tab with its own article layout and history
+struct Tab {
+    /// The title of the displayed article
+    title: String,
+    /// The layout containing the article layout of the tab and, if a link was opened in a split,
+    /// the split view next to it
+    layout: RootLayout,
+    /// The articles that have been displayed in the tab
+    history: History,
+    /// The table of contents displayed in the tab
+    toc: TocState,
+}
+
+impl Tab {
+    /// Creates a new Tab around a given article layout
+    fn new(article_layout: NamedView<RootLayout>) -> Self {
+        Tab {
+            title: t("tabs.new"),
+            layout: RootLayout::new(CONFIG.settings.links.split.orientation())
+                .child(article_layout),
+            history: History::new(),
+            toc: TocState::default(),
+        }
+    }
+}
+
+/// A view holding several article layouts in tabs. Only the active tab is displayed and only it
+/// can be found by its name, so the article views of the other tabs keep their links and
+/// scrolling untouched. A tab bar is shown as soon as there is more than one tab
+pub struct TabView {
+    /// The tabs in the order they are displayed in the tab bar
+    tabs: Vec<Tab>,
+    /// The index of the active tab
+    active: usize,
+}
+
+impl TabView {
+    /// Creates a new TabView with a single tab containing the given layout
+    pub fn new(layout: NamedView<RootLayout>) -> Self {
+        TabView {
+            tabs: vec![Tab::new(layout)],
+            active: 0,
+        }
+    }
+
+    /// Adds a new tab with the given layout right after the active one and activates it
+    pub fn add_tab(&mut self, layout: NamedView<RootLayout>) {
+        self.active += 1;
+        self.tabs.insert(self.active, Tab::new(layout));
+    }
+
+    /// Displays a given view next to the article layout of the active tab, replacing the split
+    /// view that is already displayed. The view has to contain a view named 'split_view'
+    pub fn open_split<V: IntoBoxedView + 'static>(&mut self, view: V) {
+        self.close_split();
+        self.active_tab_mut().layout.add_child(view);
+    }
+
+    /// Closes the split view of the active tab. If there is none, false is returned
+    pub fn close_split(&mut self) -> bool {
+        let layout = &mut self.active_tab_mut().layout;
+        match layout.find_child_from_name("split_view") {
+            Some(i) => layout.remove_child(i).is_some(),
+            None => false,
+        }
+    }
+
+    /// Closes the active tab and activates the one before it. The last tab can't be closed, in
+    /// that case false is returned
+    pub fn close_tab(&mut self) -> bool {
+        if self.tabs.len() == 1 {
+            return false;
+        }
+        self.tabs.remove(self.active);
+        self.active = self.active.saturating_sub(1);
+        true
+    }
+
+    /// Activates the next tab. After the last tab, the first one is activated
+    pub fn select_next(&mut self) {
+        self.active = (self.active + 1) % self.tabs.len();
+    }
+
+    /// Activates the previous tab. Before the first tab, the last one is activated
+    pub fn select_previous(&mut self) {
+        self.active = (self.active + self.tabs.len() - 1) % self.tabs.len();
+    }
+
+    /// Returns the index of the active tab
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Activates the tab at a given index. Indices after the last tab are ignored
+    pub fn select(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active = index;
+        }
+    }
+
+    /// Changes the title of the active tab
+    pub fn set_title(&mut self, title: String) {
+        self.tabs[self.active].title = title;
+    }
+
+    /// Returns the history of the active tab
+    pub fn history_mut(&mut self) -> &mut History {
+        &mut self.tabs[self.active].history
+    }
+
+    /// Calls a given function with the layout and the history of every tab, not only the active one
+    pub fn for_each_tab<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(&mut RootLayout, &mut History),
+    {
+        for tab in self.tabs.iter_mut() {
+            callback(&mut tab.layout, &mut tab.history);
+        }
+    }
+
+    /// Returns the table of contents of the active tab
+    pub fn toc_mut(&mut self) -> &mut TocState {
+        &mut self.tabs[self.active].toc
+    }
+
+    /// Helper function that returns the height of the tab bar
+    fn bar_height(&self) -> usize {
+        if self.tabs.len() > 1 {
+            1
+        } else {
+            0
+        }
+    }
+
+    /// Helper function that returns the active tab
+    fn active_tab(&self) -> &Tab {
+        &self.tabs[self.active]
+    }
+
+    /// Helper function that returns the active tab mutably
+    fn active_tab_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active]
+    }
+}
+
+impl View for TabView {
+    fn draw(&self, printer: &Printer) {
+        if self.bar_height() > 0 {
+            let mut x = 0;
+            for (i, tab) in self.tabs.iter().enumerate() {
+                let label = format!(" {}: {} ", i + 1, shorten_title(&tab.title));
+                let style = if i == self.active {
+                    ColorStyle::highlight()
+                } else {
+                    ColorStyle::secondary()
+                };
+                printer.with_color(style, |printer| printer.print((x, 0), &label));
+                x += label.chars().count() + 1;
+            }
+        }
+
+        self.active_tab()
+            .layout
+            .draw(&printer.offset((0, self.bar_height())));
+    }
+
+    fn layout(&mut self, size: Vec2) {
+        let bar_height = self.bar_height();
+        self.active_tab_mut()
+            .layout
+            .layout(size.saturating_sub((0, bar_height)));
+    }
+
+    fn required_size(&mut self, constraint: Vec2) -> Vec2 {
+        let bar_height = self.bar_height();
+        self.active_tab_mut()
+            .layout
+            .required_size(constraint.saturating_sub((0, bar_height)))
+            + (0, bar_height)
+    }
+
+    fn on_event(&mut self, event: Event) -> EventResult {
+        let bar_height = self.bar_height();
+        self.active_tab_mut()
+            .layout
+            .on_event(event.relativized((0, bar_height)))
+    }
+
+    fn call_on_any<'a>(&mut self, selector: &Selector<'_>, callback: AnyCb<'a>) {
+        self.active_tab_mut().layout.call_on_any(selector, callback);
+    }
+
+    fn focus_view(&mut self, selector: &Selector<'_>) -> Result<EventResult, ViewNotFound> {
+        self.active_tab_mut().layout.focus_view(selector)
+    }
+
+    fn take_focus(&mut self, source: Direction) -> Result<EventResult, CannotFocus> {
+        self.active_tab_mut().layout.take_focus(source)
+    }
+
+    fn important_area(&self, view_size: Vec2) -> Rect {
+        let bar_height = self.bar_height();
+        self.active_tab()
+            .layout
+            .important_area(view_size.saturating_sub((0, bar_height)))
+            + (0, bar_height)
+    }
+}
+
+/// Opens a new, empty tab after the active one. The article displayed next is displayed in it
+pub fn open_tab(siv: &mut Cursive) {
+    log::info!("opening a new tab");
+    let result = siv.call_on_name("article_tabs", |view: &mut TabView| {
+        view.add_tab(toc::article_layout().with_name("article_layout"))
+    });
+    if result.is_none() {
+        log::warn!("couldn't find the article tabs");
+    }
+}
+
+/// Activates the next tab. It's the callback for the next_tab keybinding
+pub fn on_next_tab(siv: &mut Cursive) {
+    log::info!("on_next_tab was called");
+    siv.call_on_name("article_tabs", |view: &mut TabView| view.select_next());
+    on_tab_switch(siv);
+}
+
+/// Activates the previous tab. It's the callback for the previous_tab keybinding
+pub fn on_previous_tab(siv: &mut Cursive) {
+    log::info!("on_previous_tab was called");
+    siv.call_on_name("article_tabs", |view: &mut TabView| view.select_previous());
+    on_tab_switch(siv);
+}
+
+/// Closes the split view of the active tab or, without one, the tab itself and returns to the
+/// previous one. It's the callback for the close_tab keybinding
+pub fn on_tab_close(siv: &mut Cursive) {
+    log::info!("on_tab_close was called");
+    if siv
+        .call_on_name("article_tabs", |view: &mut TabView| view.close_split())
+        .unwrap_or_default()
+    {
+        focus_article(siv);
+        return;
+    }
+
+    let closed = siv
+        .call_on_name("article_tabs", |view: &mut TabView| view.close_tab())
+        .unwrap_or_default();
+    if !closed {
+        log::info!("the last tab can't be closed");
+        return;
+    }
+    on_tab_switch(siv);
+}
+
+/// Calls a given function and activates the tab that was active before again. Tabs opened by the
+/// function stay in the background, while the active tab keeps the focus
+pub fn in_background<F, R>(siv: &mut Cursive, callback: F) -> R
+where
+    F: FnOnce(&mut Cursive) -> R,
+{
+    let active = siv.call_on_name("article_tabs", |view: &mut TabView| view.active());
+    let result = callback(siv);
+    if let Some(active) = active {
+        siv.call_on_name("article_tabs", |view: &mut TabView| view.select(active));
+        focus_article(siv);
+    }
+    result
+}
+
+/// Displays a given view next to the article of the active tab. See TabView::open_split
+pub fn open_split<V: IntoBoxedView + 'static>(siv: &mut Cursive, view: V) {
+    let result = siv.call_on_name("article_tabs", |tabs: &mut TabView| tabs.open_split(view));
+    if result.is_none() {
+        log::warn!("couldn't find the article tabs");
+    }
+}
+
+/// Changes the title of the active tab
+pub fn set_title(siv: &mut Cursive, title: String) {
+    siv.call_on_name("article_tabs", |view: &mut TabView| view.set_title(title));
+}
+
+/// Calls a given function with the history of the active tab. If there are no tabs, nothing is
+/// called and None is returned
+pub fn with_history<F, R>(siv: &mut Cursive, callback: F) -> Option<R>
+where
+    F: FnOnce(&mut History) -> R,
+{
+    siv.call_on_name("article_tabs", |view: &mut TabView| {
+        callback(view.history_mut())
+    })
+}
+
+/// Calls a given function with the table of contents of the active tab. If there are no tabs,
+/// nothing is called and None is returned
+pub fn with_toc<F, R>(siv: &mut Cursive, callback: F) -> Option<R>
+where
+    F: FnOnce(&mut TocState) -> R,
+{
+    siv.call_on_name("article_tabs", |view: &mut TabView| {
+        callback(view.toc_mut())
+    })
+}
+
+/// Helper function that is called after another tab was activated. The article of the tab that
+/// was left has been read and the one of the new tab is read from now on
+fn on_tab_switch(siv: &mut Cursive) {
+    reading_log::finish_reading();
+    if let Some(article) = with_history(siv, |history| history.current().cloned()).flatten() {
+        reading_log::on_article_opened(&article);
+    }
+    focus_article(siv);
+}
+
+/// Helper function that focuses the article view of the active tab, if it displays one
+fn focus_article(siv: &mut Cursive) {
+    if let Err(error) = siv.focus_name("article_view") {
+        log::info!("the tab doesn't display an article: {:?}", error);
+    }
+}
+
+/// Helper function that shortens a title to the maximum width of the tab bar
+fn shorten_title(title: &str) -> String {
+    if title.chars().count() <= MAX_TITLE_WIDTH {
+        return title.to_string();
+    }
+    let mut title: String = title.chars().take(MAX_TITLE_WIDTH - 1).collect();
+    title.push('…');
+    title
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{shorten_title, TabView};
+    use crate::ui::RootLayout;
+    use crate::Orientation;
+    use cursive::view::Nameable;
+    use cursive::views::TextView;
+
+    fn layout() -> cursive::views::NamedView<RootLayout> {
+        RootLayout::new(Orientation::Horizontal).with_name("article_layout")
+    }
+
+    #[test]
+    fn tabs() {
+        let mut tabs = TabView::new(layout());
+        assert!(!tabs.close_tab());
+
+        tabs.add_tab(layout());
+        tabs.add_tab(layout());
+        assert_eq!(tabs.tabs.len(), 3);
+        assert_eq!(tabs.active, 2);
+
+        tabs.select_next();
+        assert_eq!(tabs.active, 0);
+        tabs.select_previous();
+        assert_eq!(tabs.active, 2);
+
+        tabs.select(1);
+        assert_eq!(tabs.active(), 1);
+        tabs.select(3);
+        assert_eq!(tabs.active(), 1);
+
+        tabs.select(2);
+        tabs.select_previous();
+        assert!(tabs.close_tab());
+        assert_eq!(tabs.tabs.len(), 2);
+        assert_eq!(tabs.active, 0);
+    }
+
+    #[test]
+    fn splits() {
+        let mut tabs = TabView::new(layout());
+        assert!(!tabs.close_split());
+
+        tabs.open_split(TextView::new("").with_name("split_view"));
+        tabs.open_split(TextView::new("").with_name("split_view"));
+        assert!(tabs.close_split());
+        assert!(!tabs.close_split());
+    }
+
+    #[test]
+    fn shorten_titles() {
+        assert_eq!(shorten_title("Rust"), "Rust");
+        assert_eq!(
+            shorten_title("Rust (programming language)"),
+            "Rust (programming l…"
+        );
+    }
+}
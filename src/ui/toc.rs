@@ -1,39 +1,150 @@
-use crate::config;
-use crate::ui::{self, article::ArticleView, RootLayout};
+use crate::config::{self, TocPosition};
+use crate::ui::{
+    self,
+    article::ArticleView,
+    messages::{t, t_args},
+    status::{StatusContext, StatusTracker},
+    utils::remove_view_from_layout,
+    RootLayout,
+};
 use crate::view_with_theme;
 use crate::wiki::article::TableOfContents;
 use crate::wiki::article::TableOfContentsItem;
 
+use cursive::direction::Orientation;
 use cursive::event::{Event, Key};
 use cursive::traits::Scrollable;
-use cursive::view::{Nameable, Resizable};
-use cursive::views::{Dialog, SelectView};
-use cursive::Cursive;
+use cursive::view::{Nameable, Resizable, View};
+use cursive::views::{BoxedView, Dialog, HideableView, NamedView, OnEventView, SelectView};
+use cursive::{Cursive, Vec2};
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Mutex;
 
-pub fn add_table_of_contents(siv: &mut Cursive, toc: &TableOfContents) {
+lazy_static! {
+    /// Where the table of contents is displayed, it's hidden when there is no position
+    static ref TOC_POSITION: Mutex<Option<TocPosition>> =
+        Mutex::new(Some(config::CONFIG.settings.toc.position));
+}
+
+/// The table of contents displayed in a tab, together with its collapsed sections. Every tab has
+/// its own, so toggling a section only affects the table of contents it's in
+#[derive(Clone, Default)]
+pub struct TocState {
+    /// The top level items of the table of contents
+    items: Vec<TableOfContentsItem>,
+    /// The texts of the collapsed items, their sub items are hidden
+    collapsed: HashSet<String>,
+}
+
+/// Returns where the table of contents is displayed right now, if it isn't hidden
+pub fn toc_position() -> Option<TocPosition> {
+    *TOC_POSITION.lock().unwrap()
+}
+
+/// Moves the table of contents of the displayed article to the other side of it, or hides it when
+/// it was moved already. The next time it's shown at the configured position again. It's the
+/// callback for the cycle_toc_position keybinding
+pub fn on_toc_position_cycle(siv: &mut Cursive) {
+    log::info!("on_toc_position_cycle was called");
+
+    let position = next_toc_position(toc_position(), config::CONFIG.settings.toc.position);
+    *TOC_POSITION.lock().unwrap() = position;
+    ui::status::display_message(siv, t_args("toc.position", &[&toc_position_name(position)]));
+
+    // the table of contents is displayed again, like when the article was displayed
+    remove_view_from_layout(siv, "toc_container", "article_layout");
+    let article = ui::tabs::with_history(siv, |history| history.current().cloned()).flatten();
+    let (position, article) = match (position, article) {
+        (Some(position), Some(article)) if config::CONFIG.features.toc => (position, article),
+        _ => return,
+    };
+    let toc = match article.toc() {
+        Some(toc) => toc,
+        None => return,
+    };
+    add_table_of_contents(siv, toc, article.base_url());
+
+    // the toc is added after the article, in front of it it has to be moved
+    if position == TocPosition::LEFT || position == TocPosition::TOP {
+        siv.call_on_name("article_layout", |view: &mut RootLayout| {
+            if let Some(toc_view) = view
+                .find_child_from_name("toc_container")
+                .and_then(|index| view.remove_child(index))
+            {
+                view.insert_child(0, toc_view);
+            }
+        });
+    }
+    log::info!("on_toc_position_cycle finished successfully");
+}
+
+/// Helper function that returns the position following a given one. The table of contents moves
+/// from the configured position to the opposite one and is hidden after that
+fn next_toc_position(
+    position: Option<TocPosition>,
+    configured: TocPosition,
+) -> Option<TocPosition> {
+    match position {
+        Some(position) if position == configured => Some(position.opposite()),
+        Some(_) => None,
+        None => Some(configured),
+    }
+}
+
+/// Helper function that returns the name of a position for displaying it
+fn toc_position_name(position: Option<TocPosition>) -> String {
+    match position {
+        Some(TocPosition::LEFT) => t("toc.left"),
+        Some(TocPosition::RIGHT) => t("toc.right"),
+        Some(TocPosition::TOP) => t("toc.top"),
+        Some(TocPosition::BOTTOM) => t("toc.bottom"),
+        None => t("toc.hidden"),
+    }
+}
+
+/// Creates an empty layout for an article and its table of contents. The table of contents is
+/// hidden while the layout is smaller than configured, so the article stays readable
+pub fn article_layout() -> RootLayout {
+    RootLayout::new(config::CONFIG.settings.toc.position.orientation()).hide_below(
+        "toc_container",
+        Vec2::new(
+            config::CONFIG.settings.toc.hide_below_width,
+            config::CONFIG.settings.toc.hide_below_height,
+        ),
+    )
+}
+
+/// Helper function that wraps the table of contents, so the article layout can hide it
+fn hideable_toc<V: View>(toc: V) -> NamedView<HideableView<BoxedView>> {
+    HideableView::new(BoxedView::boxed(toc)).with_name("toc_container")
+}
+
+/// Adds the table of contents of an article to the article layout. It uses the theme of the wiki
+/// with the given url, if there is one
+pub fn add_table_of_contents(siv: &mut Cursive, toc: &TableOfContents, base_url: Option<&str>) {
     // get the article_layout and create an empty select view
 
-    let mut article_layout = siv.find_name::<RootLayout>("article_layout").unwrap();
+    let mut article_layout = match siv.find_name::<RootLayout>("article_layout") {
+        Some(article_layout) => article_layout,
+        None => {
+            log::warn!("couldn't find the article layout, the table of contents isn't displayed");
+            return;
+        }
+    };
     let mut toc_view = SelectView::<TableOfContentsItem>::new().on_submit(|siv, item| {
         log::info!("jumping to '{}'", item.text());
-        let item_index = match siv.find_name::<SelectView<TableOfContentsItem>>("toc_view") {
-            Some(view) => {
-                let mut index: usize = 0;
-                for (idx, _item) in view.iter().enumerate() {
-                    if _item.1.text() == item.text() {
-                        index = idx;
-                        break;
-                    }
-                }
-                index
+        let header_id = match item.header_id() {
+            Some(header_id) => header_id,
+            None => {
+                log::warn!("couldn't find the header of '{}'", item.text());
+                return;
             }
-            None => 0_usize,
         };
 
-        log::trace!("item_index: {}", item_index);
-
         if let Some(mut view) = siv.find_name::<ArticleView>("article_view") {
-            view.select_header(item_index)
+            view.scroll_to_header(header_id)
         }
 
         if let Err(error) = siv.focus_name("article_view") {
@@ -52,35 +163,258 @@ pub fn add_table_of_contents(siv: &mut Cursive, toc: &TableOfContents) {
         };
     });
 
-    // now go through every item
+    // now go through every item, every section of a new table of contents is expanded
     log::debug!("adding the table of content to the toc_view");
-    for item in toc.items() {
-        add_item_to_toc(&mut toc_view, item);
+    let toc_state = TocState {
+        items: toc.items().cloned().collect(),
+        collapsed: HashSet::new(),
+    };
+    for item in toc_state.items.iter() {
+        add_item_to_toc(&mut toc_view, item, &toc_state.collapsed);
     }
+    ui::tabs::with_toc(siv, |state| *state = toc_state);
 
-    article_layout.add_child(
-        view_with_theme!(
-            config::CONFIG.theme.toc_view,
-            Dialog::around(
-                toc_view
-                    .with_name("toc_view")
-                    .scrollable()
-                    .scroll_x(config::CONFIG.settings.toc.scroll_x)
-                    .scroll_y(config::CONFIG.settings.toc.scroll_y)
-                    .full_height()
-            )
-            .title(toc.title())
-        )
-        .min_width(config::CONFIG.settings.toc.min_width)
-        .max_width(config::CONFIG.settings.toc.max_width),
+    let toc_view = OnEventView::new(
+        StatusTracker::new(StatusContext::Toc, toc_view.with_name("toc_view"))
+            .scrollable()
+            .scroll_x(config::CONFIG.settings.toc.scroll_x)
+            .scroll_y(config::CONFIG.settings.toc.scroll_y),
+    )
+    .on_event(
+        config::CONFIG.keybindings.toggle_toc_section.clone(),
+        on_toc_section_toggle,
+    )
+    .on_event(
+        config::CONFIG.keybindings.open_section.clone(),
+        on_toc_section_open,
     );
 
+    let theme = config::CONFIG.theme.toc_view_for(base_url);
+
+    // next to the article, the toc fills the height of the screen. Above or below it, the toc is
+    // a band filling the width of the screen
+    match config::CONFIG.settings.toc.position.orientation() {
+        Orientation::Horizontal => article_layout.add_child(hideable_toc(
+            view_with_theme!(
+                theme,
+                Dialog::around(toc_view.full_height()).title(toc.title())
+            )
+            .min_width(config::CONFIG.settings.toc.min_width)
+            .max_width(config::CONFIG.settings.toc.max_width),
+        )),
+        Orientation::Vertical => article_layout.add_child(hideable_toc(
+            view_with_theme!(
+                theme,
+                Dialog::around(toc_view.full_width()).title(toc.title())
+            )
+            .max_height(config::CONFIG.settings.toc.max_height),
+        )),
+    }
+
     log::debug!("added the toc_view to the article_layout");
 }
 
-fn add_item_to_toc(toc_view: &mut SelectView<TableOfContentsItem>, item: &TableOfContentsItem) {
-    // add the item to the select_view
-    let label = format!("{}{}", " ".repeat(*item.number() as usize), item.text());
+/// Collapses the selected section of the table of contents, hiding its sub items, or expands it
+/// again. It's the callback for the toggle_toc_section keybinding
+fn on_toc_section_toggle(siv: &mut Cursive) {
+    let selected_item = match selected_toc_item(siv) {
+        Some(item) if item.sub_items().is_some() => item,
+        _ => return,
+    };
+
+    let toc_state = ui::tabs::with_toc(siv, |state| {
+        if !state.collapsed.remove(selected_item.text()) {
+            log::info!("collapsing the section '{}'", selected_item.text());
+            state.collapsed.insert(selected_item.text().to_string());
+        }
+        state.clone()
+    });
+    let toc_state = match toc_state {
+        Some(toc_state) => toc_state,
+        None => return,
+    };
+
+    // add the items again and select the toggled one
+    siv.call_on_name(
+        "toc_view",
+        |toc_view: &mut SelectView<TableOfContentsItem>| {
+            toc_view.clear();
+            for item in toc_state.items.iter() {
+                add_item_to_toc(toc_view, item, &toc_state.collapsed);
+            }
+            let index = toc_view
+                .iter()
+                .position(|(_, item)| item.text() == selected_item.text());
+            if let Some(index) = index {
+                toc_view.set_selection(index);
+            }
+        },
+    );
+}
+
+/// Helper function that returns the selected item of the displayed table of contents
+fn selected_toc_item(siv: &mut Cursive) -> Option<Rc<TableOfContentsItem>> {
+    siv.call_on_name(
+        "toc_view",
+        |toc_view: &mut SelectView<TableOfContentsItem>| toc_view.selection(),
+    )
+    .flatten()
+}
+
+/// Fetches only the selected section of the article and displays it as its own article. It's the
+/// callback for the open_section keybinding
+fn on_toc_section_open(siv: &mut Cursive) {
+    let selected_item = match selected_toc_item(siv) {
+        Some(item) => item,
+        None => return,
+    };
+
+    // the sections are numbered in the order of their headers, starting with 1 after the lead
+    let section =
+        ui::tabs::with_toc(siv, |state| section_index(&state.items, &selected_item)).flatten();
+    match section {
+        Some(section) => ui::article::open_section(siv, section, selected_item.text().to_string()),
+        None => log::warn!("couldn't find the section '{}'", selected_item.text()),
+    }
+}
+
+/// Helper function that returns the index of an item in the table of contents, counting every
+/// item before it (including the sub items) and starting with 1
+fn section_index(items: &[TableOfContentsItem], item: &TableOfContentsItem) -> Option<u32> {
+    fn find<'a>(
+        items: impl Iterator<Item = &'a TableOfContentsItem>,
+        item: &TableOfContentsItem,
+        index: &mut u32,
+    ) -> bool {
+        for other in items {
+            *index += 1;
+            if other == item {
+                return true;
+            }
+            if let Some(sub_items) = other.sub_items() {
+                if find(sub_items, item, index) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    let mut index = 0;
+    find(items.iter(), item, &mut index).then_some(index)
+}
+
+fn add_item_to_toc(
+    toc_view: &mut SelectView<TableOfContentsItem>,
+    item: &TableOfContentsItem,
+    collapsed: &HashSet<String>,
+) {
+    let is_collapsed = collapsed.contains(item.text());
+
+    // add the item to the select_view, collapsed items are marked
+    let label = format!(
+        "{}{}{}",
+        " ".repeat(*item.number() as usize),
+        item.text(),
+        if is_collapsed { " [+]" } else { "" }
+    );
     log::debug!("added the item: {} to the toc_view", label);
     toc_view.add_item(label, item.clone());
+
+    // add the sub items below their parent, unless it's collapsed
+    if let Some(sub_items) = item.sub_items().filter(|_| !is_collapsed) {
+        for sub_item in sub_items {
+            add_item_to_toc(toc_view, sub_item, collapsed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        add_table_of_contents, article_layout, next_toc_position, on_toc_section_toggle,
+        section_index,
+    };
+    use crate::config::TocPosition;
+    use crate::ui::tabs::{self, TabView};
+    use crate::wiki::article::{TableOfContents, TableOfContentsItem};
+    use cursive::views::SelectView;
+    use cursive::{view::Nameable, Cursive};
+
+    #[test]
+    fn next_toc_positions() {
+        let configured = TocPosition::RIGHT;
+        let position = next_toc_position(Some(configured), configured);
+        assert_eq!(position, Some(TocPosition::LEFT));
+        let position = next_toc_position(position, configured);
+        assert_eq!(position, None);
+        assert_eq!(next_toc_position(position, configured), Some(configured));
+        assert_eq!(
+            next_toc_position(Some(TocPosition::TOP), TocPosition::TOP),
+            Some(TocPosition::BOTTOM)
+        );
+    }
+
+    #[test]
+    fn section_indices() {
+        let item =
+            |text: &str, sub_items| TableOfContentsItem::new(0, text.to_string(), None, sub_items);
+        let founding = item("Founding", None);
+        let items = vec![
+            item(
+                "History",
+                Some(vec![founding.clone(), item("Growth", None)]),
+            ),
+            item("Products", None),
+        ];
+
+        assert_eq!(section_index(&items, &items[0]), Some(1));
+        assert_eq!(section_index(&items, &founding), Some(2));
+        assert_eq!(section_index(&items, &items[1]), Some(4));
+        assert_eq!(section_index(&items, &item("Legal", None)), None);
+    }
+
+    #[test]
+    fn toggle_after_tab_switch() {
+        let item =
+            |text: &str, sub_items| TableOfContentsItem::new(0, text.to_string(), None, sub_items);
+        let toc = |title: &str, section: &str| {
+            TableOfContents::new(
+                title.to_string(),
+                vec![item(section, Some(vec![item("Details", None)]))],
+            )
+        };
+        let toc_texts = |siv: &mut Cursive| {
+            siv.call_on_name(
+                "toc_view",
+                |toc_view: &mut SelectView<TableOfContentsItem>| {
+                    toc_view
+                        .iter()
+                        .map(|(_, item)| item.text().to_string())
+                        .collect::<Vec<_>>()
+                },
+            )
+            .unwrap()
+        };
+
+        let mut siv = Cursive::new();
+        siv.add_layer(
+            TabView::new(article_layout().with_name("article_layout")).with_name("article_tabs"),
+        );
+        add_table_of_contents(&mut siv, &toc("First", "History"), None);
+        tabs::open_tab(&mut siv);
+        add_table_of_contents(&mut siv, &toc("Second", "Products"), None);
+        siv.call_on_name("article_tabs", |view: &mut TabView| view.select_previous());
+
+        // collapsing a section of the first tab only uses the items of the first tab
+        on_toc_section_toggle(&mut siv);
+        assert_eq!(toc_texts(&mut siv), vec!["History"]);
+        on_toc_section_toggle(&mut siv);
+        assert_eq!(toc_texts(&mut siv), vec!["History", "Details"]);
+
+        // and the second tab still has its own table of contents
+        siv.call_on_name("article_tabs", |view: &mut TabView| view.select_next());
+        on_toc_section_toggle(&mut siv);
+        assert_eq!(toc_texts(&mut siv), vec!["Products"]);
+    }
 }
@@ -1,5 +1,18 @@
-use crate::ui::RootLayout;
+use crate::config::CONFIG;
+use crate::ui::{
+    messages::{t, t_args},
+    LoadingView, RootLayout,
+};
+use crate::wiki::error;
+use anyhow::{Context, Result};
+use cursive::align::HAlign;
+use cursive::event::Key;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, OnEventView, TextView};
 use cursive::Cursive;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs;
+use std::path::Path;
 
 /// Removes a given view from a given layout. If the view or the layout couldn't be found, the
 /// function fails silently
@@ -15,6 +28,35 @@ pub fn remove_view_from_layout(siv: &mut Cursive, view_name: &str, layout_name:
     }
 }
 
+/// Shows a loading layer with a given message. Pressing Esc hides the layer and calls on_cancel.
+/// The screen is refreshed regularly while the layer is shown, so its spinner can move
+pub fn show_loading_view<F>(siv: &mut Cursive, message: String, on_cancel: F)
+where
+    F: Fn(&mut Cursive) + 'static,
+{
+    siv.add_layer(
+        OnEventView::new(
+            Dialog::around(LoadingView::new(message))
+                .title(t("dialog.loading"))
+                .title_position(HAlign::Center),
+        )
+        .on_event(Key::Esc, move |s| {
+            hide_loading_view(s);
+            on_cancel(s);
+        })
+        .with_name("loading_view"),
+    );
+    siv.set_autorefresh(true);
+}
+
+/// Hides the loading layer, if it's shown, and stops refreshing the screen
+pub fn hide_loading_view(siv: &mut Cursive) {
+    siv.set_autorefresh(false);
+    if let Some(position) = siv.screen_mut().find_layer_from_name("loading_view") {
+        siv.screen_mut().remove_layer(position);
+    }
+}
+
 /// Wraps a view into a ThemedView with the given theme. If the macro is used without a theme,
 /// it'll just apply the default one to the view
 #[macro_export]
@@ -27,3 +69,122 @@ macro_rules! view_with_theme {
         }
     };
 }
+
+/// Shows the problems found in the config file, if there are any. wiki-tui can be used with the
+/// defaults of the affected settings or be closed, so the config file can be fixed first
+pub fn show_config_errors(siv: &mut Cursive) {
+    let errors = CONFIG.errors();
+    if errors.is_empty() {
+        return;
+    }
+
+    let errors = errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n");
+    siv.add_layer(
+        Dialog::around(TextView::new(t_args("config.errors", &[&errors])).scrollable())
+            .title(t("config.errors_title"))
+            .title_position(HAlign::Center)
+            .button(t("config.continue"), |siv| {
+                siv.pop_layer();
+            })
+            .button(t("dialog.quit"), Cursive::quit)
+            .max_width(80),
+    );
+}
+
+/// Shows an error message for an error that occurred while doing a given action. If the cause of
+/// the error is known, it's described in the message
+pub fn show_error(siv: &mut Cursive, action: &str, error: &anyhow::Error) {
+    siv.add_layer(
+        Dialog::info(error_message(action, error))
+            .title(t("dialog.error"))
+            .title_position(HAlign::Center),
+    );
+}
+
+/// Shows an error message like show_error. If trying again could help, the message has a button
+/// calling retry
+pub fn show_error_with_retry<F>(siv: &mut Cursive, action: &str, error: &anyhow::Error, retry: F)
+where
+    F: Fn(&mut Cursive) + 'static,
+{
+    if !error::is_transient(error) {
+        show_error(siv, action, error);
+        return;
+    }
+
+    siv.add_layer(
+        Dialog::text(error_message(action, error))
+            .title(t("dialog.error"))
+            .title_position(HAlign::Center)
+            .button(t("dialog.retry"), move |s| {
+                s.pop_layer();
+                retry(s);
+            })
+            .dismiss_button(t("dialog.dismiss")),
+    );
+}
+
+/// Helper function that creates the message for an error that occurred while doing a given action
+fn error_message(action: &str, error: &anyhow::Error) -> String {
+    match error::describe(error) {
+        Some(cause) => t_args("error.cause", &[&cause]),
+        None => t_args("error.problem", &[&action]),
+    }
+}
+
+/// Reads a value stored as json in a given file, like the bookmarks. Nothing is returned if there
+/// is no file or it can't be read. Invalid content is logged with the name of what is stored
+pub fn load_json<T: DeserializeOwned>(path: Option<&Path>, name: &str) -> Option<T> {
+    let content = fs::read_to_string(path?).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            log::warn!("couldn't read the {}: {}", name, error);
+            None
+        }
+    }
+}
+
+/// Writes a value as json to a given file, creating its directory if needed. Without a file,
+/// nothing is stored. The errors mention the name of what is stored
+pub fn save_json<T: Serialize>(path: Option<&Path>, value: &T, name: &str) -> Result<()> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("couldn't create the {} directory", name))?;
+    }
+    let json = serde_json::to_string_pretty(value)
+        .with_context(|| format!("couldn't serialize the {}", name))?;
+    fs::write(path, json).with_context(|| format!("couldn't write the {}", name))?;
+    log::debug!("saved the {}", name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_json, save_json};
+
+    #[test]
+    fn json_files() {
+        let dir = std::env::temp_dir().join(format!("wiki-tui-test-json-{}", std::process::id()));
+        let path = dir.join("values.json");
+
+        assert_eq!(load_json::<Vec<i32>>(Some(&path), "values"), None);
+        save_json(Some(&path), &vec![1, 2], "values").unwrap();
+        assert_eq!(load_json(Some(&path), "values"), Some(vec![1, 2]));
+        save_json::<Vec<i32>>(None, &vec![3], "values").unwrap();
+
+        std::fs::write(&path, "[1,").unwrap();
+        assert_eq!(load_json::<Vec<i32>>(Some(&path), "values"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    config::{self, VisitedLinksScope, CONFIG},
+    ui::utils::{load_json, save_json},
+    wiki::article::Article,
+};
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The articles that have been opened. They're only loaded from disk when they're remembered
+    /// between sessions
+    static ref VISITED_PAGES: Mutex<VisitedPages> =
+        Mutex::new(match CONFIG.settings.links.visited {
+            VisitedLinksScope::SESSION => VisitedPages::new(),
+            VisitedLinksScope::PERSISTENT =>
+                VisitedPages::load(CONFIG.settings.links.visited_path.clone()),
+        });
+}
+
+/// The pages that have been opened, by the host of their wiki and their title. They're stored on
+/// disk as json when they're remembered between sessions
+pub struct VisitedPages {
+    /// The pages, as 'host|title'
+    pages: HashSet<String>,
+    /// The file the pages are stored in
+    path: Option<PathBuf>,
+}
+
+impl VisitedPages {
+    /// Creates a new, empty VisitedPages that isn't stored on disk
+    pub fn new() -> Self {
+        VisitedPages {
+            pages: HashSet::new(),
+            path: None,
+        }
+    }
+
+    /// Loads the VisitedPages from a given file. If the file can't be read, no page has been
+    /// visited
+    pub fn load(path: Option<PathBuf>) -> Self {
+        log::debug!("loading the visited pages");
+        let mut visited_pages = VisitedPages::new();
+        if let Some(pages) = load_json(path.as_deref(), "visited links") {
+            visited_pages.pages = pages;
+        }
+        visited_pages.path = path;
+        visited_pages
+    }
+
+    /// Marks the page with a given title from the wiki at base_url as visited. The pages are only
+    /// stored when the page wasn't visited before
+    pub fn visit(&mut self, base_url: &str, title: &str) {
+        if self.pages.insert(page_key(base_url, title)) {
+            if let Err(error) = self.save() {
+                log::warn!("{:?}", error);
+            }
+        }
+    }
+
+    /// Checks if the page with a given title from the wiki at base_url has been visited
+    pub fn is_visited(&self, base_url: &str, title: &str) -> bool {
+        self.pages.contains(&page_key(base_url, title))
+    }
+
+    /// Writes the pages to the visited links file
+    fn save(&self) -> Result<()> {
+        save_json(self.path.as_deref(), &self.pages, "visited links")
+    }
+}
+
+impl Default for VisitedPages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Marks a displayed article as visited, together with the redirect that led to it. Nothing
+/// happens if dimming visited links is disabled
+pub fn on_article_opened(article: &Article) {
+    if !CONFIG.features.visited_links {
+        return;
+    }
+    let base_url = match article.base_url() {
+        Some(base_url) => base_url.to_string(),
+        None => crate::ui::wikis::current_wiki().base_url,
+    };
+
+    let mut visited_pages = VISITED_PAGES.lock().unwrap();
+    for title in article
+        .title()
+        .iter()
+        .chain(article.redirected_from().iter())
+    {
+        visited_pages.visit(&base_url, title);
+    }
+}
+
+/// Checks if the article a given link target of the wiki at base_url points to has been opened.
+/// Links are never visited if dimming visited links is disabled
+pub fn is_visited(base_url: &str, target: &str) -> bool {
+    if !CONFIG.features.visited_links {
+        return false;
+    }
+    match target_title(target) {
+        Some(title) => VISITED_PAGES.lock().unwrap().is_visited(base_url, &title),
+        None => false,
+    }
+}
+
+/// Helper function that returns the title of the page a link target points to. Only links to
+/// articles of the same wiki have one
+fn target_title(target: &str) -> Option<String> {
+    let title = target.strip_prefix("/wiki/")?;
+    let title = title.split('#').next().unwrap_or_default();
+    if title.is_empty() {
+        return None;
+    }
+    Some(decode_percents(title))
+}
+
+/// Helper function that decodes the percent-encoded characters of a link target, invalid
+/// sequences are kept as they are
+fn decode_percents(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) if bytes[index] == b'%' => {
+                decoded.push(byte);
+                index += 3;
+            }
+            _ => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Helper function that generates the key of a page. Titles are compared like the wikis do, so
+/// underscores are spaces and the first letter is case-insensitive
+fn page_key(base_url: &str, title: &str) -> String {
+    let title = title.trim().replace('_', " ");
+    let mut chars = title.chars();
+    let title = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+    format!("{}|{}", config::wiki_host(base_url), title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_percents, page_key, target_title, VisitedPages};
+
+    #[test]
+    fn target_titles() {
+        assert_eq!(
+            target_title("/wiki/Rust_(programming_language)#History").as_deref(),
+            Some("Rust_(programming_language)")
+        );
+        assert_eq!(target_title("/wiki/Caf%C3%A9").as_deref(), Some("Café"));
+        assert_eq!(target_title("#History"), None);
+        assert_eq!(target_title("https://example.org"), None);
+        assert_eq!(decode_percents("100%_sure%2"), "100%_sure%2");
+    }
+
+    #[test]
+    fn visited_pages() {
+        let mut visited_pages = VisitedPages::new();
+        visited_pages.visit("https://en.wikipedia.org/", "Rust (programming language)");
+
+        assert!(visited_pages.is_visited("https://en.wikipedia.org", "rust_(programming_language)"));
+        assert!(
+            !visited_pages.is_visited("https://de.wikipedia.org/", "Rust (programming language)")
+        );
+        assert_eq!(
+            page_key("https://en.wikipedia.org/", " éclair"),
+            "en.wikipedia.org|Éclair"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    config::{self, Wiki, CONFIG},
+    ui::{
+        self,
+        messages::{t, t_args},
+        RootLayout,
+    },
+    view_with_theme,
+    wiki::{request::DEFAULT_SCRIPT_PATH, site},
+    Orientation,
+};
+
+use anyhow::{Context, Result};
+use cursive::align::HAlign;
+use cursive::view::{Nameable, Resizable, Scrollable};
+use cursive::views::{Dialog, SelectView};
+use cursive::Cursive;
+use lazy_static::lazy_static;
+use std::fs;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+lazy_static! {
+    /// The wiki that is searched and whose articles are opened
+    static ref CURRENT_WIKI: Mutex<Wiki> =
+        Mutex::new(load_last_wiki().unwrap_or_else(|| CONFIG.api_config.default_wiki()));
+}
+
+/// Returns the wiki that is currently used
+pub fn current_wiki() -> Wiki {
+    CURRENT_WIKI.lock().unwrap().clone()
+}
+
+/// Returns every wiki the user can switch between. The first one is the configured base_url
+pub fn wikis() -> Vec<Wiki> {
+    let mut wikis = vec![CONFIG.api_config.default_wiki()];
+    for wiki in CONFIG.api_config.wikis.iter() {
+        if !wikis
+            .iter()
+            .any(|known| is_same_url(&known.base_url, &wiki.base_url))
+        {
+            wikis.push(wiki.clone());
+        }
+    }
+    wikis
+}
+
+/// Returns the wiki with a given url. Wikis that aren't configured are assumed to be Wikimedia
+/// projects, like the other language editions of wikipedia
+pub fn find_wiki(base_url: &str) -> Wiki {
+    wikis()
+        .into_iter()
+        .find(|wiki| is_same_url(&wiki.base_url, base_url))
+        .unwrap_or_else(|| Wiki {
+            name: config::wiki_host(base_url).to_string(),
+            base_url: base_url.to_string(),
+            script_path: DEFAULT_SCRIPT_PATH.to_string(),
+        })
+}
+
+/// Returns the edition of a wiki in another language. Only wikis whose host starts with a
+/// language code, like 'en.wikipedia.org', have editions in other languages
+pub fn language_wiki(wiki: &Wiki, language: &str) -> Option<Wiki> {
+    let host = config::wiki_host(&wiki.base_url);
+    let (wiki_language, domain) = host.split_once('.')?;
+    if !is_language_code(wiki_language) || !domain.contains('.') {
+        return None;
+    }
+
+    let scheme = wiki.base_url.split("//").next().unwrap_or("https:");
+    Some(find_wiki(&format!("{}//{}.{}/", scheme, language, domain)))
+}
+
+/// Checks if a given text looks like a language code, two or three lowercase letters optionally
+/// followed by a variant like in 'zh-yue'
+pub fn is_language_code(text: &str) -> bool {
+    let mut parts = text.split('-');
+    let language = parts.next().unwrap_or_default();
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_lowercase()))
+}
+
+/// Checks if a given host belongs to one of the configured wikis
+pub fn is_configured_host(host: &str) -> bool {
+    wikis()
+        .iter()
+        .any(|wiki| config::wiki_host(&wiki.base_url) == host)
+}
+
+/// Checks if a given url is the url of the current wiki
+pub fn is_current_wiki(base_url: &str) -> bool {
+    is_same_url(&CURRENT_WIKI.lock().unwrap().base_url, base_url)
+}
+
+/// Returns the title of the search bar with the active search mode, it contains the name of the
+/// current wiki when it isn't the configured base_url
+pub fn search_title() -> String {
+    let wiki = current_wiki();
+    let title = if is_same_url(&wiki.base_url, &CONFIG.api_config.base_url) {
+        t("search.title")
+    } else {
+        t_args("search.title_wiki", &[&wiki.name])
+    };
+    t_args(
+        "search.title_mode",
+        &[
+            &title,
+            &ui::search::search_mode_name(ui::search::search_mode()),
+        ],
+    )
+}
+
+/// Helper function that loads the wiki used last time, if it's still configured
+fn load_last_wiki() -> Option<Wiki> {
+    if !CONFIG.api_config.remember_wiki {
+        return None;
+    }
+
+    let path = CONFIG.api_config.last_wiki_path.as_ref()?;
+    let base_url = fs::read_to_string(path).ok()?;
+    let wiki = wikis()
+        .into_iter()
+        .find(|wiki| is_same_url(&wiki.base_url, base_url.trim()));
+    if wiki.is_none() {
+        log::info!(
+            "the last used wiki '{}' isn't configured anymore",
+            base_url.trim()
+        );
+    }
+    wiki
+}
+
+/// Helper function that remembers the current wiki for the next start
+fn save_last_wiki(wiki: &Wiki) -> Result<()> {
+    let path = match CONFIG.api_config.last_wiki_path {
+        Some(ref path) if CONFIG.api_config.remember_wiki => path,
+        _ => return Ok(()),
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("couldn't create the directory of the last wiki")?;
+    }
+    fs::write(path, &wiki.base_url).context("couldn't remember the last wiki")?;
+    Ok(())
+}
+
+/// Helper function that compares two urls, ignoring trailing slashes
+fn is_same_url(first: &str, second: &str) -> bool {
+    first.trim_end_matches('/') == second.trim_end_matches('/')
+}
+
+/// Displays the configured wikis in a new layer. Selecting one of them switches to it. It's the
+/// callback for the switch_wiki keybinding
+pub fn on_wiki_select(siv: &mut Cursive) {
+    log::info!("on_wiki_select was called");
+
+    // don't open the selection twice
+    if siv.find_name::<SelectView<Wiki>>("wikis_view").is_some() {
+        return;
+    }
+
+    let current_wiki = current_wiki();
+    let mut wikis_view = SelectView::<Wiki>::new().on_submit(on_wiki_submit);
+    for wiki in wikis() {
+        let is_current = is_same_url(&wiki.base_url, &current_wiki.base_url);
+        let label = format!(
+            "{} ({}){}",
+            wiki.name,
+            config::wiki_host(&wiki.base_url),
+            if is_current { " *" } else { "" }
+        );
+        wikis_view.add_item(label, wiki);
+        if is_current {
+            wikis_view.set_selection(wikis_view.len() - 1);
+        }
+    }
+
+    siv.add_layer(
+        Dialog::around(
+            RootLayout::new(Orientation::Vertical).child(view_with_theme!(
+                config::CONFIG.theme.search_results,
+                Dialog::around(wikis_view.with_name("wikis_view").scrollable())
+            )),
+        )
+        .title(t("wikis.title"))
+        .dismiss_button(t("dialog.back"))
+        .max_height(20),
+    );
+}
+
+/// Switches to a given wiki. It's the on_submit callback for the wikis view
+fn on_wiki_submit(siv: &mut Cursive, wiki: &Wiki) {
+    log::info!(
+        "switching to the wiki '{}' at '{}'",
+        wiki.name,
+        wiki.base_url
+    );
+
+    // hide the selection
+    siv.pop_layer();
+
+    // the open article stays untouched, its links are still opened in the wiki it's from
+    *CURRENT_WIKI.lock().unwrap() = wiki.clone();
+    ui::search::clear_search_cache();
+    if let Err(error) = save_last_wiki(wiki) {
+        log::warn!("{:?}", error);
+    }
+    siv.call_on_name("search_dialog", |view: &mut Dialog| {
+        view.set_title(search_title());
+    });
+
+    check_wiki(siv, wiki.clone());
+}
+
+/// Checks in the background that the api of a given wiki can be reached and displays an error
+/// when it can't
+pub fn check_wiki(siv: &mut Cursive, wiki: Wiki) {
+    // without the network, there is nothing to check
+    if CONFIG.features.offline {
+        return;
+    }
+
+    let cb_sink = siv.cb_sink().clone();
+    thread::spawn(move || {
+        let error = match site::check_api(
+            &wiki.base_url,
+            &wiki.script_path,
+            Duration::from_millis(CONFIG.api_config.timeout_ms),
+            CONFIG.api_config.max_retries,
+            &CONFIG.api_config.user_agent,
+        ) {
+            Ok(name) => {
+                log::info!("the api of '{}' can be reached", name);
+                return;
+            }
+            Err(error) => error,
+        };
+
+        log::error!("{:?}", error);
+        let message = t_args("wikis.error", &[&wiki.name, &format!("{:#}", error)]);
+        if let Err(error) = cb_sink.send(Box::new(move |s: &mut Cursive| {
+            s.add_layer(
+                Dialog::info(message)
+                    .title(t("dialog.error"))
+                    .title_position(HAlign::Center),
+            );
+        })) {
+            log::warn!("{:?}", error);
+        }
+    });
+}
@@ -1,9 +1,50 @@
-use crate::wiki::article::{compiled_article::Article, parser::Parser};
+use crate::wiki::article::{
+    cache::ArticleCache,
+    compiled_article::Article,
+    image::ArticleImage,
+    parser::{Parser, SourceFormat},
+    wikitext::redirect_target,
+};
+use crate::wiki::error::WikiError;
+use crate::wiki::request::{
+    self, DEFAULT_MAX_RETRIES, DEFAULT_SCRIPT_PATH, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT,
+};
 
-use anyhow::Result;
-use reqwest::blocking::{get, Response};
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Response;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// How many redirects are followed one after the other, before giving up
+const MAX_REDIRECTS: usize = 5;
+/// How many images are downloaded at the same time
+const IMAGE_WORKERS: usize = 4;
+
+// NOTE: The following structs are only used for deserializing the json response of a section
+#[derive(Deserialize)]
+struct SectionResponse {
+    parse: ParsedSection,
+}
+
+#[derive(Deserialize)]
+struct ParsedSection {
+    title: String,
+    pageid: i32,
+    text: String,
+    #[serde(default)]
+    redirects: Vec<SectionRedirect>,
+}
+
+#[derive(Deserialize)]
+struct SectionRedirect {
+    from: String,
+}
 
 /// A Builder which fetches and parses an article. Can work with either an article id or a link
+#[derive(Clone)]
 pub struct ArticleBuilder {
     /// The id of the article to be fetched
     page_id: i32,
@@ -11,6 +52,26 @@ pub struct ArticleBuilder {
     target: Option<String>,
     /// The url of wikipedia
     base_url: String,
+    /// The path of the MediaWiki scripts, relative to base_url
+    script_path: String,
+    /// The optional cache, fetched articles are stored in and retrieved from
+    cache: Option<ArticleCache>,
+    /// How long the request can take before it's cancelled
+    timeout: Duration,
+    /// How often the request is retried when it fails
+    max_retries: u32,
+    /// The User-Agent the request identifies itself with
+    user_agent: String,
+    /// Are the images of the article downloaded?
+    images: bool,
+    /// Is the network unavailable? Only cached articles can be built then
+    offline: bool,
+    /// The index of the only section to be fetched, if there is one
+    section: Option<u32>,
+    /// Is the article fetched again, even if it's cached?
+    refresh: bool,
+    /// How many redirects have been followed to get to the article
+    redirects: usize,
 }
 
 impl ArticleBuilder {
@@ -21,54 +82,444 @@ impl ArticleBuilder {
             page_id,
             target,
             base_url: base_url.to_string(),
+            script_path: DEFAULT_SCRIPT_PATH.to_string(),
+            cache: None,
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            images: false,
+            offline: false,
+            section: None,
+            refresh: false,
+            redirects: 0,
         }
     }
 
-    /// Fetches the article and parses it with a given parser. Any errors it encounters will be returned
+    /// The path of the MediaWiki scripts, relative to the url of wikipedia
+    #[must_use]
+    pub fn script_path(mut self, script_path: &str) -> Self {
+        self.script_path = script_path.to_string();
+        self
+    }
+
+    /// Use a cache for storing and retrieving the article
+    #[must_use]
+    pub fn cache(mut self, cache: ArticleCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// How long the request can take before it's cancelled
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How often the request is retried when it fails
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// The User-Agent the requests identify themselves with
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Download the images of the article
+    #[must_use]
+    pub fn images(mut self, images: bool) -> Self {
+        self.images = images;
+        self
+    }
+
+    /// Don't use the network. Only cached articles can be built, no matter how old they are
+    #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Fetch the article again, even if it's cached. The cache is updated with the fetched version
+    #[must_use]
+    pub fn refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// Fetch only the section with the given index instead of the whole article, which is a lot
+    /// faster for huge articles. The lead of the article is the section 0
+    #[must_use]
+    pub fn section(mut self, section: Option<u32>) -> Self {
+        self.section = section;
+        self
+    }
+
+    /// Fetches the article and parses it with a given parser. If the article is cached, the cached
+    /// version is used instead. Any errors it encounters will be returned
     pub fn build(&self, parser: &mut impl Parser) -> Result<Article> {
         log::info!("beginning the build process");
-        let url = self.build_url();
+        let url = self.build_url(parser.format())?;
+
+        let cached = self.cache.as_ref().and_then(|cache| {
+            if self.refresh && !self.offline {
+                None
+            } else if self.offline {
+                cache.get_stale(&url)
+            } else {
+                cache.get(&url)
+            }
+        });
+        let html = match cached {
+            Some(html) => {
+                log::info!("found the article '{}' in the cache", url);
+                html
+            }
+            None if self.offline => {
+                log::info!(
+                    "the article '{}' isn't cached, it can't be fetched offline",
+                    url
+                );
+                bail!(WikiError::Offline);
+            }
+            None => {
+                log::info!("making the request to '{}'", url);
+                let html = self
+                    .make_request(&url)
+                    .map_err(|error| {
+                        // a missing article is reported by the wiki with a 404
+                        let is_not_found = error
+                            .chain()
+                            .filter_map(|error| error.downcast_ref::<reqwest::Error>())
+                            .any(|error| error.status() == Some(StatusCode::NOT_FOUND));
+                        if is_not_found {
+                            return error.context(WikiError::ArticleNotFound);
+                        }
+                        error
+                    })?
+                    .text()?;
+
+                if let Some(ref cache) = self.cache {
+                    if let Err(error) = cache.put(&url, &html) {
+                        log::warn!("{:?}", error);
+                    }
+                }
+                html
+            }
+        };
+
+        // the wikitext of a redirect only links to its target, which is displayed instead. The html
+        // of a redirect is already the one of its target
+        if parser.format() == SourceFormat::Wikitext {
+            if let Some(target) = redirect_target(&html) {
+                return self.follow_redirect(parser, target);
+            }
+        }
 
-        log::info!("making the request to '{}'", url);
-        let response = self.make_request(&url)?;
+        // a single section of the html is requested from the api, its response has to be
+        // unwrapped first
+        let html = match self.section {
+            Some(_) if parser.format() == SourceFormat::Html => section_html(&html)?,
+            _ => html,
+        };
 
         log::info!("parsing the article");
-        self.parse_response(parser, response)
+        let mut article = self.parse_response(parser, &html)?;
+
+        // remember where the article is from, so its links can be opened in the same wikipedia
+        article.set_base_url(self.base_url.clone());
+        if article.page_id().is_none() && self.page_id != 0 {
+            article.set_page_id(self.page_id);
+        }
+        Ok(article)
+    }
+
+    /// Fetches the article in the given format and stores it in the cache without parsing it, so
+    /// building it later doesn't need the network. Articles that are already cached are skipped
+    pub fn prefetch(&self, format: SourceFormat) -> Result<()> {
+        let cache = self
+            .cache
+            .as_ref()
+            .context("articles can only be prefetched into a cache")?;
+        let url = self.build_url(format)?;
+        if self.offline || cache.get(&url).is_some() {
+            return Ok(());
+        }
+
+        log::debug!("prefetching the article '{}'", url);
+        let html = self.make_request(&url)?.text()?;
+        cache.put(&url, &html)
+    }
+
+    /// Builds the article a redirect links to. The article remembers the title of the redirect, if
+    /// it's known
+    fn follow_redirect(&self, parser: &mut impl Parser, target: String) -> Result<Article> {
+        if self.redirects >= MAX_REDIRECTS {
+            bail!("gave up after following '{}' redirects", self.redirects);
+        }
+        log::info!("following the redirect to '{}'", target);
+
+        let mut builder = self.clone();
+        builder.page_id = 0;
+        builder.target = Some(format!("/wiki/{}", target.replace(' ', "_")));
+        builder.redirects += 1;
+        parser.set_title(target);
+        let mut article = builder.build(parser)?;
+
+        // the title of the first redirect is kept, when redirects follow each other
+        if let Some(title) = self
+            .target
+            .as_deref()
+            .and_then(|target| target.strip_prefix("/wiki/"))
+        {
+            article.set_redirected_from(title.replace('_', " "));
+        }
+        Ok(article)
     }
 
-    /// Creates a url from the link
-    fn build_url(&self) -> String {
-        match self.target {
-            Some(ref target) => format!("{}{}", self.base_url, target),
-            None => format!("{}?curid={}", self.base_url, self.page_id),
+    /// Creates a url from the link, requesting the source in the given format
+    fn build_url(&self, format: SourceFormat) -> Result<String> {
+        if let (Some(section), SourceFormat::Html) = (self.section, format) {
+            return self.build_section_url(section);
+        }
+
+        let url = match self.target {
+            Some(ref target) => request::join_url(&self.base_url, target)?,
+            None => request::join_url(
+                &self.base_url,
+                &format!("{}index.php?curid={}", self.script_path, self.page_id),
+            )?,
+        };
+
+        let url = match format {
+            SourceFormat::Html => url,
+            SourceFormat::Wikitext if url.contains('?') => format!("{}&action=raw", url),
+            SourceFormat::Wikitext => format!("{}?action=raw", url),
+        };
+        match self.section {
+            Some(section) => Ok(format!("{}&section={}", url, section)),
+            None => Ok(url),
         }
     }
 
+    /// Creates the url of the api request for the html of a single section
+    fn build_section_url(&self, section: u32) -> Result<String> {
+        let page = match self
+            .target
+            .as_deref()
+            .and_then(|target| target.strip_prefix("/wiki/"))
+        {
+            Some(title) => format!("page={}", title),
+            None if self.page_id != 0 => format!("pageid={}", self.page_id),
+            None => bail!("a section can only be fetched by the title or the id of its article"),
+        };
+        request::join_url(
+            &self.base_url,
+            &format!(
+                "{}api.php?action=parse&format=json&formatversion=2&prop=text&redirects=1&section={}&{}",
+                self.script_path, section, page
+            ),
+        )
+    }
+
+    /// Downloads the images of a built article with a few workers and returns them by the id of
+    /// their element. Images that can't be downloaded or decoded are skipped, only their caption
+    /// is displayed then. Nothing is downloaded offline or when images are disabled
+    pub fn fetch_images(&self, article: &Article) -> Vec<(i32, ArticleImage)> {
+        if !self.images || self.offline {
+            return Vec::new();
+        }
+        let sources = article
+            .elements()
+            .filter(|element| element.get_attribute("type") == Some("image"))
+            .filter_map(|element| Some((*element.id(), element.get_attribute("src")?.to_string())))
+            .collect::<Vec<(i32, String)>>();
+        log::info!("downloading '{}' images", sources.len());
+
+        // every worker takes the next image that isn't downloaded yet, until there are none left
+        let next_source = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            let handles = (0..IMAGE_WORKERS.min(sources.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut images = Vec::new();
+                        while let Some((id, src)) =
+                            sources.get(next_source.fetch_add(1, Ordering::SeqCst))
+                        {
+                            match self.fetch_image(src) {
+                                Ok(image) => images.push((*id, image)),
+                                Err(error) => log::warn!("{:?}", error),
+                            }
+                        }
+                        images
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok())
+                .flatten()
+                .collect()
+        })
+    }
+
+    /// Downloads and decodes a single image
+    fn fetch_image(&self, src: &str) -> Result<ArticleImage> {
+        let url = request::join_url(&self.base_url, src)?;
+        log::debug!("downloading the image '{}'", url);
+        let bytes = self.make_request(&url)?.bytes()?;
+        ArticleImage::decode(&bytes)
+    }
+
     /// Makes the request to wikipedia and checks the response for errors
     fn make_request(&self, url: &str) -> Result<Response> {
-        Ok(get(url)?.error_for_status()?)
+        request::get(url, self.timeout, self.max_retries, &self.user_agent)
     }
 
-    /// Parses the response with a given parser
-    fn parse_response(&self, parser: &mut impl Parser, response: Response) -> Result<Article> {
-        parser.parse(response)
+    /// Parses the html of the response with a given parser
+    fn parse_response(&self, parser: &mut impl Parser, html: &str) -> Result<Article> {
+        parser
+            .parse(html.as_bytes())
+            .context(WikiError::ArticleParse)
     }
 }
 
+/// Helper function that converts the api response for a single section into a html document the
+/// parser can read. The document only contains the title, the id, the redirect that led to it and
+/// the content of the article
+fn section_html(json: &str) -> Result<String> {
+    let section = serde_json::from_str::<SectionResponse>(json)
+        .context("the response doesn't contain the section")?
+        .parse;
+    let title = escape_html(&section.title);
+    let redirected_from = match section.redirects.first() {
+        Some(redirect) => format!(
+            ",\"wgRedirectedFrom\":{}",
+            serde_json::to_string(&redirect.from)?
+        ),
+        None => String::new(),
+    };
+    Ok(format!(
+        "<html><head><script>RLCONF={{\"wgArticleId\":{}{}}};</script></head><body><h1 class=\"mw-first-heading\">{}</h1>{}</body></html>",
+        section.pageid, redirected_from, title, section.text
+    ))
+}
+
+/// Helper function that escapes the characters of a text that have a meaning in html
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 #[cfg(test)]
 mod tests {
     const BASE_URL: &str = "https://en.wikipedia.org/";
 
     #[test]
     fn correct_url() {
-        use super::ArticleBuilder;
+        use super::{ArticleBuilder, SourceFormat};
+        assert_eq!(
+            ArticleBuilder::new(1234, None, BASE_URL)
+                .build_url(SourceFormat::Html)
+                .unwrap(),
+            format!("{}w/index.php?curid=1234", BASE_URL)
+        );
+        assert_eq!(
+            ArticleBuilder::new(1234, Some("/wiki/Software".to_string()), BASE_URL)
+                .build_url(SourceFormat::Html)
+                .unwrap(),
+            format!("{}wiki/Software", BASE_URL)
+        );
+        assert_eq!(
+            ArticleBuilder::new(1234, None, "https://wiki.example.org/")
+                .script_path("")
+                .build_url(SourceFormat::Html)
+                .unwrap(),
+            "https://wiki.example.org/index.php?curid=1234"
+        );
         assert_eq!(
-            ArticleBuilder::new(1234, None, BASE_URL).build_url(),
-            format!("{}?curid=1234", BASE_URL)
+            ArticleBuilder::new(1234, Some("/wiki/Software".to_string()), BASE_URL)
+                .build_url(SourceFormat::Wikitext)
+                .unwrap(),
+            format!("{}wiki/Software?action=raw", BASE_URL)
         );
         assert_eq!(
-            ArticleBuilder::new(1234, Some("/wiki/Software".to_string()), BASE_URL).build_url(),
-            format!("{}/wiki/Software", BASE_URL)
+            ArticleBuilder::new(1234, None, BASE_URL)
+                .build_url(SourceFormat::Wikitext)
+                .unwrap(),
+            format!("{}w/index.php?curid=1234&action=raw", BASE_URL)
+        );
+    }
+
+    #[test]
+    fn section_url() {
+        use super::{ArticleBuilder, SourceFormat};
+        assert_eq!(
+            ArticleBuilder::new(1234, None, BASE_URL)
+                .section(Some(2))
+                .build_url(SourceFormat::Html)
+                .unwrap(),
+            format!(
+                "{}w/api.php?action=parse&format=json&formatversion=2&prop=text&redirects=1&section=2&pageid=1234",
+                BASE_URL
+            )
         );
+        assert_eq!(
+            ArticleBuilder::new(0, Some("/wiki/Software".to_string()), BASE_URL)
+                .section(Some(0))
+                .build_url(SourceFormat::Html)
+                .unwrap(),
+            format!(
+                "{}w/api.php?action=parse&format=json&formatversion=2&prop=text&redirects=1&section=0&page=Software",
+                BASE_URL
+            )
+        );
+        assert_eq!(
+            ArticleBuilder::new(1234, None, BASE_URL)
+                .section(Some(2))
+                .build_url(SourceFormat::Wikitext)
+                .unwrap(),
+            format!("{}w/index.php?curid=1234&action=raw&section=2", BASE_URL)
+        );
+        assert!(ArticleBuilder::new(0, None, BASE_URL)
+            .section(Some(2))
+            .build_url(SourceFormat::Html)
+            .is_err());
+    }
+
+    #[test]
+    fn parse_section() {
+        use super::section_html;
+        use crate::config::CONFIG;
+        use crate::wiki::article::parser::{DefaultParser, Parser};
+
+        let html = section_html(
+            r#"{"parse":{"title":"Rust","pageid":29414838,"text":"<div class=\"mw-parser-output\"><h2><span class=\"mw-headline\" id=\"History\">History</span></h2><p>Rust began</p></div>"}}"#,
+        )
+        .unwrap();
+        let article = DefaultParser::new(&CONFIG.settings.toc)
+            .parse(html.as_bytes())
+            .unwrap();
+        assert_eq!(article.title(), Some("Rust"));
+        assert_eq!(article.page_id(), Some(29414838));
+        assert!(article
+            .elements()
+            .any(|element| element.content() == "Rust began"));
+        assert_eq!(article.redirected_from(), None);
+        assert!(section_html(r#"{"error":{"code":"nosuchsection"}}"#).is_err());
+
+        let html = section_html(
+            r#"{"parse":{"title":"Rust (programming language)","pageid":29414838,"redirects":[{"from":"Rust lang","to":"Rust (programming language)"}],"text":"<div class=\"mw-parser-output\"><p>Rust began</p></div>"}}"#,
+        )
+        .unwrap();
+        let article = DefaultParser::new(&CONFIG.settings.toc)
+            .parse(html.as_bytes())
+            .unwrap();
+        assert_eq!(article.redirected_from(), Some("Rust lang"));
     }
 }
@@ -0,0 +1,419 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, FileTimes, Metadata};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// A place the html of cached articles is stored in. Every backend remembers when an article was
+/// stored and when it was used last, so the ArticleCache can drop stale and unused articles
+pub trait CacheBackend: Send + Sync {
+    /// Retrieves the html stored with a given key and when it was stored. Retrieving an article
+    /// counts as using it
+    fn get(&self, key: &str) -> Option<(String, SystemTime)>;
+    /// Stores the html of an article with a given key, replacing what was stored with it before
+    fn put(&self, key: &str, html: &str) -> Result<()>;
+    /// Removes the articles used least recently until there are no more than max_entries left
+    fn evict(&self, max_entries: usize) -> Result<()>;
+    /// Returns the number of stored articles and how many bytes they use
+    fn size(&self) -> (usize, u64);
+    /// Removes every stored article
+    fn clear(&self) -> Result<()>;
+}
+
+/// A cache that stores the raw html of fetched articles in a CacheBackend, on disk unless another
+/// backend is used. Clones of an ArticleCache share the same backend
+#[derive(Clone)]
+pub struct ArticleCache {
+    /// Where the cached articles are stored
+    backend: Arc<dyn CacheBackend>,
+    /// How long a cached article is valid before it has to be fetched again
+    ttl: Duration,
+    /// How many articles are cached at most, zero means there is no limit. When there are more,
+    /// the articles used least recently are removed
+    max_entries: usize,
+}
+
+impl ArticleCache {
+    /// Creates a new ArticleCache storing its articles in the given directory
+    pub fn new(path: PathBuf, ttl: Duration) -> Self {
+        ArticleCache::with_backend(Arc::new(DiskBackend::new(path)), ttl)
+    }
+
+    /// Creates a new ArticleCache storing its articles in a given backend
+    pub fn with_backend(backend: Arc<dyn CacheBackend>, ttl: Duration) -> Self {
+        log::debug!("creating a new instance of ArticleCache");
+        ArticleCache {
+            backend,
+            ttl,
+            max_entries: 0,
+        }
+    }
+
+    /// Limit the number of cached articles. Zero means there is no limit
+    #[must_use]
+    pub fn max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Retrieves the cached html for a given key. If the article isn't cached or is older than
+    /// the ttl, None will be returned
+    pub fn get(&self, key: &str) -> Option<String> {
+        let (html, stored_at) = self.backend.get(key)?;
+        let age = SystemTime::now()
+            .duration_since(stored_at)
+            .unwrap_or_default();
+        if age >= self.ttl {
+            log::debug!("the cached article '{}' is stale", key);
+            return None;
+        }
+        Some(html)
+    }
+
+    /// Retrieves the cached html for a given key, no matter how old it is. It's used when the
+    /// article can't be fetched again
+    pub fn get_stale(&self, key: &str) -> Option<String> {
+        self.backend.get(key).map(|(html, _)| html)
+    }
+
+    /// Stores the html of an article with a given key. Any errors it encounters will be returned
+    pub fn put(&self, key: &str, html: &str) -> Result<()> {
+        self.backend.put(key, html)?;
+        log::debug!("cached the article '{}'", key);
+
+        if self.max_entries == 0 {
+            return Ok(());
+        }
+        self.backend.evict(self.max_entries)
+    }
+
+    /// Returns the number of cached articles and how many bytes they use
+    pub fn size(&self) -> (usize, u64) {
+        self.backend.size()
+    }
+
+    /// Removes every cached article. Any errors it encounters will be returned
+    pub fn clear(&self) -> Result<()> {
+        self.backend.clear()?;
+        log::info!("cleared the article cache");
+        Ok(())
+    }
+}
+
+/// A CacheBackend storing every article in its own file. The time an article was stored at is the
+/// modification time of its file and the time it was used last is its access time. Files are named
+/// after a hash of their key, which is stored on the first line of the file
+pub struct DiskBackend {
+    /// The directory the articles are stored in
+    path: PathBuf,
+}
+
+impl DiskBackend {
+    /// Creates a new DiskBackend storing its articles in the given directory
+    pub fn new(path: PathBuf) -> Self {
+        DiskBackend { path }
+    }
+
+    /// A helper function that returns the files of the stored articles with their metadata. If
+    /// the directory can't be read, there are none
+    fn entries(&self) -> Vec<(PathBuf, Metadata)> {
+        let dir = match fs::read_dir(&self.path) {
+            Ok(dir) => dir,
+            Err(_) => return Vec::new(),
+        };
+        dir.filter_map(|entry| entry.ok())
+            .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+            .filter(|(_, metadata)| metadata.is_file())
+            .collect()
+    }
+
+    /// A helper function that converts a key into the path of its file. The name has the same
+    /// length for every key, so long keys don't exceed the limits of the file system
+    fn file_path(&self, key: &str) -> PathBuf {
+        self.path.join(format!("{:016x}", key_hash(key)))
+    }
+}
+
+/// Helper function that hashes a key with FNV-1a. Unlike the hasher of the standard library, its
+/// values stay the same between versions of Rust, so the cached files can still be found
+fn key_hash(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+impl CacheBackend for DiskBackend {
+    fn get(&self, key: &str) -> Option<(String, SystemTime)> {
+        let file_path = self.file_path(key);
+        let stored_at = fs::metadata(&file_path).ok()?.modified().ok()?;
+        let content = fs::read_to_string(&file_path).ok()?;
+
+        // another key with the same hash doesn't return this article
+        let (stored_key, html) = content.split_once('\n')?;
+        if stored_key != key {
+            return None;
+        }
+        mark_accessed(&file_path);
+        Some((html.to_string(), stored_at))
+    }
+
+    fn put(&self, key: &str, html: &str) -> Result<()> {
+        fs::create_dir_all(&self.path).context("couldn't create the cache directory")?;
+        let file_path = self.file_path(key);
+        fs::write(&file_path, format!("{}\n{}", key, html))
+            .context("couldn't write the article to the cache")?;
+        mark_accessed(&file_path);
+        Ok(())
+    }
+
+    fn evict(&self, max_entries: usize) -> Result<()> {
+        let mut entries = self.entries();
+        if entries.len() <= max_entries {
+            return Ok(());
+        }
+
+        entries.sort_by_key(|(_, metadata)| last_access(metadata));
+        let count = entries.len() - max_entries;
+        for (file_path, _) in entries.into_iter().take(count) {
+            fs::remove_file(&file_path).with_context(|| {
+                format!("couldn't remove '{}' from the cache", file_path.display())
+            })?;
+        }
+        log::debug!("removed '{}' articles from the cache", count);
+        Ok(())
+    }
+
+    fn size(&self) -> (usize, u64) {
+        let entries = self.entries();
+        let bytes = entries.iter().map(|(_, metadata)| metadata.len()).sum();
+        (entries.len(), bytes)
+    }
+
+    fn clear(&self) -> Result<()> {
+        for (file_path, _) in self.entries() {
+            fs::remove_file(&file_path).with_context(|| {
+                format!("couldn't remove '{}' from the cache", file_path.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// A CacheBackend keeping the articles in memory, they are gone once wiki-tui is closed
+#[derive(Default)]
+pub struct MemoryBackend {
+    /// The stored articles by their key
+    entries: Mutex<HashMap<String, MemoryEntry>>,
+}
+
+/// An article stored in a MemoryBackend
+struct MemoryEntry {
+    /// The html of the article
+    html: String,
+    /// When the article was stored
+    stored_at: SystemTime,
+    /// The position of the last use of the article, counted over every article
+    used: u64,
+}
+
+impl MemoryBackend {
+    /// Creates a new, empty MemoryBackend
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Helper function that returns the position a use of an article gets, after every use so far
+fn next_use(entries: &HashMap<String, MemoryEntry>) -> u64 {
+    entries
+        .values()
+        .map(|entry| entry.used + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+impl CacheBackend for MemoryBackend {
+    fn get(&self, key: &str) -> Option<(String, SystemTime)> {
+        let mut entries = self.entries.lock().unwrap();
+        let used = next_use(&entries);
+        let entry = entries.get_mut(key)?;
+        entry.used = used;
+        Some((entry.html.clone(), entry.stored_at))
+    }
+
+    fn put(&self, key: &str, html: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        let used = next_use(&entries);
+        entries.insert(
+            key.to_string(),
+            MemoryEntry {
+                html: html.to_string(),
+                stored_at: SystemTime::now(),
+                used,
+            },
+        );
+        Ok(())
+    }
+
+    fn evict(&self, max_entries: usize) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        while entries.len() > max_entries {
+            let least_used = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone());
+            if let Some(key) = least_used {
+                entries.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> (usize, u64) {
+        let entries = self.entries.lock().unwrap();
+        let bytes = entries.values().map(|entry| entry.html.len() as u64).sum();
+        (entries.len(), bytes)
+    }
+
+    fn clear(&self) -> Result<()> {
+        self.entries.lock().unwrap().clear();
+        Ok(())
+    }
+}
+
+/// Helper function that sets the access time of a cached article to now. Access times aren't
+/// reliably updated by reading a file, so they're set explicitly. Errors are only logged
+fn mark_accessed(file_path: &Path) {
+    let result = fs::File::options()
+        .write(true)
+        .open(file_path)
+        .and_then(|file| file.set_times(FileTimes::new().set_accessed(SystemTime::now())));
+    if let Err(error) = result {
+        log::debug!(
+            "couldn't update the access time of the cached article: {}",
+            error
+        );
+    }
+}
+
+/// Helper function that returns when a cached article was used last. Without an access time, the
+/// time it was fetched at is used
+fn last_access(metadata: &Metadata) -> SystemTime {
+    metadata
+        .accessed()
+        .or_else(|_| metadata.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArticleCache, CacheBackend, DiskBackend, MemoryBackend};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wiki-tui-test-{}-{}", name, std::process::id()))
+    }
+
+    #[test]
+    fn put_and_get() {
+        let dir = cache_dir("put_and_get");
+        let cache = ArticleCache::new(dir.clone(), Duration::from_secs(60));
+        cache.put("/wiki/Software", "<html></html>").unwrap();
+
+        assert_eq!(
+            cache.get("/wiki/Software"),
+            Some("<html></html>".to_string())
+        );
+        assert_eq!(cache.get("/wiki/Hardware"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn long_keys() {
+        let dir = cache_dir("long_keys");
+        let cache = ArticleCache::new(dir.clone(), Duration::from_secs(60));
+        let key = format!(
+            "/wiki/{}",
+            "%D0%9F%D1%80%D0%B8%D0%B2%D0%B5%D1%82_".repeat(10)
+        );
+        cache.put(&key, "<html></html>").unwrap();
+
+        assert_eq!(cache.get(&key), Some("<html></html>".to_string()));
+        assert_eq!(cache.get("/wiki/Software"), None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Checks that a cache using a given backend drops the articles used least recently and
+    /// the stale ones. The backend has to be empty
+    fn check_backend(backend: Arc<dyn CacheBackend>) {
+        let cache =
+            ArticleCache::with_backend(backend.clone(), Duration::from_secs(60)).max_entries(2);
+        cache.put("first", "1").unwrap();
+        cache.put("second", "2").unwrap();
+        assert_eq!(cache.get("first"), Some("1".to_string()));
+
+        // the second article is the one used least recently
+        cache.put("third", "3").unwrap();
+        assert_eq!(cache.get("second"), None);
+        assert_eq!(cache.get("first"), Some("1".to_string()));
+        assert_eq!(cache.size().0, 2);
+
+        // clones share the stored articles
+        cache.clone().clear().unwrap();
+        assert_eq!(cache.size(), (0, 0));
+
+        let cache = ArticleCache::with_backend(backend, Duration::from_secs(0));
+        cache.put("?curid=1234", "<html></html>").unwrap();
+        assert_eq!(cache.get("?curid=1234"), None);
+        assert_eq!(
+            cache.get_stale("?curid=1234"),
+            Some("<html></html>".to_string())
+        );
+    }
+
+    #[test]
+    fn disk_backend() {
+        let dir = cache_dir("disk_backend");
+        check_backend(Arc::new(DiskBackend::new(dir.clone())));
+
+        // the files contain the keys of the articles too
+        let cache = ArticleCache::new(dir.clone(), Duration::from_secs(60));
+        cache.clear().unwrap();
+        cache.put("first", "1").unwrap();
+        assert_eq!(cache.size(), (1, 7));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn memory_backend() {
+        check_backend(Arc::new(MemoryBackend::new()));
+
+        let cache =
+            ArticleCache::with_backend(Arc::new(MemoryBackend::new()), Duration::from_secs(60));
+        cache.put("first", "1").unwrap();
+        assert_eq!(cache.size(), (1, 1));
+    }
+
+    #[test]
+    fn file_path() {
+        let backend = DiskBackend::new(PathBuf::from("cache"));
+        assert_eq!(
+            backend.file_path("/wiki/C_(language)"),
+            backend.file_path("/wiki/C_(language)")
+        );
+        assert_ne!(
+            backend.file_path("/wiki/C_(language)"),
+            backend.file_path("/wiki/C_(programming_language)")
+        );
+
+        let long_key = format!("/wiki/{}", "%E6%97%A5%E6%9C%AC".repeat(40));
+        let file_name = backend.file_path(&long_key);
+        assert_eq!(file_name.file_name().unwrap().len(), 16);
+    }
+}
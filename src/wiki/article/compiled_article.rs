@@ -1,4 +1,6 @@
-use crate::wiki::article::{element::ArticleElement, toc::TableOfContents};
+use crate::wiki::article::{element::ArticleElement, image::ArticleImage, toc::TableOfContents};
+
+use std::collections::HashMap;
 
 /// A fully parsed article with an optional table of contents
 #[derive(PartialEq, Debug, Clone)]
@@ -7,6 +9,26 @@ pub struct Article {
     elements: Vec<ArticleElement>,
     /// The optional table of contents of the article
     toc: Option<TableOfContents>,
+    /// The url of the wikipedia the article was fetched from
+    base_url: Option<String>,
+    /// The title of the article
+    title: Option<String>,
+    /// The id of the article
+    page_id: Option<i32>,
+    /// The downloaded images, by the id of their element
+    images: HashMap<i32, ArticleImage>,
+    /// When the article was last edited
+    last_modified: Option<String>,
+    /// The canonical url of the article
+    canonical_url: Option<String>,
+    /// The title of the redirect that led to the article
+    redirected_from: Option<String>,
+    /// The references of the article with their anchor and text
+    references: Vec<(String, String)>,
+    /// The meanings of a disambiguation page with their description and target
+    disambiguation_links: Vec<(String, String)>,
+    /// The articles listed in the "See also" section with their description and target
+    see_also_links: Vec<(String, String)>,
 }
 
 impl Article {
@@ -14,7 +36,20 @@ impl Article {
     /// used directly, instead use the one the ArticleBuilder gives you
     pub fn new(elements: Vec<ArticleElement>, toc: Option<TableOfContents>) -> Self {
         log::debug!("creating a new instance of Article");
-        Self { elements, toc }
+        Self {
+            elements,
+            toc,
+            base_url: None,
+            title: None,
+            page_id: None,
+            images: HashMap::new(),
+            last_modified: None,
+            canonical_url: None,
+            redirected_from: None,
+            references: Vec::new(),
+            disambiguation_links: Vec::new(),
+            see_also_links: Vec::new(),
+        }
     }
 
     /// Iterate over all of the elements contained in this article
@@ -26,4 +61,132 @@ impl Article {
     pub fn toc(&self) -> Option<&TableOfContents> {
         self.toc.as_ref()
     }
+
+    /// The optional url of the wikipedia the article was fetched from
+    pub fn base_url(&self) -> Option<&str> {
+        self.base_url.as_deref()
+    }
+
+    /// Sets the url of the wikipedia the article was fetched from
+    pub fn set_base_url(&mut self, base_url: String) {
+        self.base_url = Some(base_url);
+    }
+
+    /// The optional title of the article
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Sets the title of the article
+    pub fn set_title(&mut self, title: String) {
+        self.title = Some(title);
+    }
+
+    /// The optional id of the article
+    pub fn page_id(&self) -> Option<i32> {
+        self.page_id
+    }
+
+    /// Sets the id of the article
+    pub fn set_page_id(&mut self, page_id: i32) {
+        self.page_id = Some(page_id);
+    }
+
+    /// The downloaded images, by the id of their element
+    pub fn images(&self) -> &HashMap<i32, ArticleImage> {
+        &self.images
+    }
+
+    /// Sets the image of the element with a given id
+    pub fn set_image(&mut self, id: i32, image: ArticleImage) {
+        self.images.insert(id, image);
+    }
+
+    /// When the article was last edited, if it's known
+    pub fn last_modified(&self) -> Option<&str> {
+        self.last_modified.as_deref()
+    }
+
+    /// Sets when the article was last edited
+    pub fn set_last_modified(&mut self, last_modified: String) {
+        self.last_modified = Some(last_modified);
+    }
+
+    /// The optional canonical url of the article
+    pub fn canonical_url(&self) -> Option<&str> {
+        self.canonical_url.as_deref()
+    }
+
+    /// Sets the canonical url of the article
+    pub fn set_canonical_url(&mut self, canonical_url: String) {
+        self.canonical_url = Some(canonical_url);
+    }
+
+    /// The title of the redirect that led to the article, if the article was reached through one
+    pub fn redirected_from(&self) -> Option<&str> {
+        self.redirected_from.as_deref()
+    }
+
+    /// Sets the title of the redirect that led to the article
+    pub fn set_redirected_from(&mut self, redirected_from: String) {
+        self.redirected_from = Some(redirected_from);
+    }
+
+    /// The references of the article with their anchor and text, in the order they are numbered
+    pub fn references(&self) -> &[(String, String)] {
+        &self.references
+    }
+
+    /// Returns the text of the reference with a given anchor
+    pub fn reference(&self, anchor: &str) -> Option<&str> {
+        self.references
+            .iter()
+            .find(|(reference_anchor, _)| reference_anchor == anchor)
+            .map(|(_, text)| text.as_str())
+    }
+
+    /// Sets the references of the article
+    pub fn set_references(&mut self, references: Vec<(String, String)>) {
+        self.references = references;
+    }
+
+    /// The meanings listed by a disambiguation page with their description and target. It's empty
+    /// for every other article
+    pub fn disambiguation_links(&self) -> &[(String, String)] {
+        &self.disambiguation_links
+    }
+
+    /// Is the article a disambiguation page?
+    pub fn is_disambiguation(&self) -> bool {
+        !self.disambiguation_links.is_empty()
+    }
+
+    /// Sets the meanings listed by a disambiguation page
+    pub fn set_disambiguation_links(&mut self, disambiguation_links: Vec<(String, String)>) {
+        self.disambiguation_links = disambiguation_links;
+    }
+
+    /// The articles listed in the "See also" section with their description and target
+    pub fn see_also_links(&self) -> &[(String, String)] {
+        &self.see_also_links
+    }
+
+    /// Sets the articles listed in the "See also" section
+    pub fn set_see_also_links(&mut self, see_also_links: Vec<(String, String)>) {
+        self.see_also_links = see_also_links;
+    }
+
+    /// Counts the words of the text in the article
+    pub fn word_count(&self) -> usize {
+        self.elements
+            .iter()
+            .filter(|element| {
+                !matches!(
+                    element.get_attribute("type"),
+                    Some("image" | "math" | "reference")
+                )
+            })
+            .map(|element| element.content().split_whitespace().count())
+            .sum()
+    }
 }
@@ -0,0 +1,78 @@
+use anyhow::{bail, Context, Result};
+
+/// A decoded image of an article, stored as rgb pixels
+#[derive(PartialEq, Debug, Clone)]
+pub struct ArticleImage {
+    /// The width of the image in pixels
+    width: usize,
+    /// The height of the image in pixels
+    height: usize,
+    /// The pixels of the image, row by row
+    pixels: Vec<(u8, u8, u8)>,
+}
+
+impl ArticleImage {
+    /// Creates a new image from its size and pixels. Fails if the number of pixels doesn't match the
+    /// size
+    pub fn new(width: usize, height: usize, pixels: Vec<(u8, u8, u8)>) -> Result<Self> {
+        if width == 0 || height == 0 || pixels.len() != width * height {
+            bail!(
+                "an image of '{}x{}' pixels can't have '{}' pixels",
+                width,
+                height,
+                pixels.len()
+            );
+        }
+
+        Ok(ArticleImage {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Decodes an image from the bytes of a png, jpeg or gif file
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let image = image::load_from_memory(bytes)
+            .context("couldn't decode the image")?
+            .to_rgb8();
+
+        let (width, height) = image.dimensions();
+        let pixels = image
+            .pixels()
+            .map(|pixel| (pixel[0], pixel[1], pixel[2]))
+            .collect();
+        ArticleImage::new(width as usize, height as usize, pixels)
+    }
+
+    /// The width of the image in pixels
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The height of the image in pixels
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the color of the pixel at the given position. Positions outside of the image return
+    /// the closest pixel
+    pub fn pixel(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        let x = x.min(self.width - 1);
+        let y = y.min(self.height - 1);
+        self.pixels[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArticleImage;
+
+    #[test]
+    fn pixels() {
+        let image = ArticleImage::new(2, 1, vec![(0, 0, 0), (255, 255, 255)]).unwrap();
+        assert_eq!(image.pixel(1, 0), (255, 255, 255));
+        assert_eq!(image.pixel(5, 5), (255, 255, 255));
+        assert!(ArticleImage::new(2, 2, vec![(0, 0, 0)]).is_err());
+    }
+}
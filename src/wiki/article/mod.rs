@@ -1,12 +1,17 @@
 mod builder;
+pub mod cache;
 mod compiled_article;
 mod element;
+mod image;
 pub mod parser;
 mod toc;
+pub mod wikitext;
 
 pub type Article = compiled_article::Article;
 pub type ArticleElement = element::ArticleElement;
 pub type ArticleBuilder = builder::ArticleBuilder;
+pub type ArticleCache = cache::ArticleCache;
+pub type ArticleImage = image::ArticleImage;
 
 pub type TableOfContents = toc::TableOfContents;
 pub type TableOfContentsItem = toc::TableOfContentsItem;
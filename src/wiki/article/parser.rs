@@ -10,14 +10,39 @@ use cursive::theme::{Effect, Style};
 use select::{
     document::Document,
     node::Node,
-    predicate::{Attr, Class, Name},
+    predicate::{Attr, Class, Name, Predicate},
 };
 use std::collections::HashMap;
 use std::io::Read;
 
-/// The Parser trait allows for generating an Article from a html source
+/// The format of the source a Parser can read
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SourceFormat {
+    /// The html of the rendered article
+    Html,
+    /// The raw wikitext of the article
+    Wikitext,
+}
+
+/// The title of a table of contents when its own title can't be found
+const DEFAULT_TOC_TITLE: &str = "Contents";
+/// How deep the items of a table of contents can be nested. Deeper items are left out
+const MAX_TOC_DEPTH: i32 = 10;
+/// What the tabs in code blocks are replaced with, so every character has the same width
+const CODE_TAB: &str = "    ";
+/// The part of the url of a formula image that makes it an svg
+const MATH_SVG_PATH: &str = "/render/svg/";
+/// The part of the url of a formula image that makes it a png
+const MATH_PNG_PATH: &str = "/render/png/";
+
+/// The Parser trait allows for generating an Article from a html or wikitext source
 pub trait Parser {
+    /// The format of the source the parser can read. It decides what is requested from the wiki
+    fn format(&self) -> SourceFormat;
     fn parse<R: Read>(&mut self, html: R) -> Result<Article>;
+    /// Changes the title of the article, for sources that don't contain it. It's called when a
+    /// redirect is followed
+    fn set_title(&mut self, _title: String) {}
 }
 
 /// The Default Parser. It can generate an Article from a given html source. Requires a
@@ -27,6 +52,10 @@ pub struct DefaultParser {
     elements: Vec<ArticleElement>,
     /// The toc configuration
     toc_settings: TocSettings,
+    /// The ids of the header elements, by the anchor of the header
+    header_ids: HashMap<String, i32>,
+    /// Is a quote being parsed right now?
+    in_quote: bool,
 }
 
 impl DefaultParser {
@@ -36,84 +65,135 @@ impl DefaultParser {
         Self {
             elements: Vec::new(),
             toc_settings: toc_settings.clone(),
+            header_ids: HashMap::new(),
+            in_quote: false,
         }
     }
 
     /// This function takes generates a TableOfContents from a given document. When no
-    /// TableOfContents can be found in the document, it returns Ok(None). Any errors it
-    /// encounters are returned
+    /// TableOfContents can be found in the document, it returns Ok(None). Malformed items are
+    /// left out with a warning, so the rest of the TableOfContents can still be displayed
     fn parse_toc(&self, document: &Document) -> Result<Option<TableOfContents>> {
         log::debug!("parse_toc was called");
 
         // get the toc node from the document if it exists
         log::debug!("retrieving the required nodes from the document");
-        let toc_node = document
-            .find(Attr("id", "toc"))
-            .next()
-            .context("No table of contents was found")?;
+        let toc_node = match document.find(Attr("id", "toc")).next() {
+            Some(toc_node) => toc_node,
+            None => {
+                log::debug!("the article has no table of contents");
+                return Ok(None);
+            }
+        };
 
         // get the title of the toc
         let toc_title = match self.toc_settings.title {
             TocTitle::DEFAULT => toc_node
                 .find(Class("toctitle"))
                 .next()
-                .context("No toc title was found")?
-                .text(),
-            TocTitle::ARTICLE => self.get_title(document)?,
-            TocTitle::CUSTOM => self
-                .toc_settings
-                .title_custom
-                .clone()
-                .unwrap_or_else(|| "NONE".to_string()),
-        };
+                .map(|title| title.text()),
+            TocTitle::ARTICLE => self.get_title(document).ok(),
+            TocTitle::CUSTOM => Some(
+                self.toc_settings
+                    .title_custom
+                    .clone()
+                    .unwrap_or_else(|| "NONE".to_string()),
+            ),
+        }
+        .unwrap_or_else(|| {
+            log::warn!("couldn't find the title of the table of contents");
+            DEFAULT_TOC_TITLE.to_string()
+        });
 
         log::debug!("parsing the toc now");
-        let mut toc_items: Vec<TableOfContentsItem> = Vec::new();
-
-        // parse every child of the toc node
-        for node in toc_node
+        let list = toc_node
             .find(Name("ul"))
             .next()
-            .context("No items were found inside of the table of contents")?
-            .find(Name("li"))
-        {
-            if let Ok(item) = self.parse_toc_item(node, 0) {
-                toc_items.push(item);
-                continue;
-            }
+            .context("No items were found inside of the table of contents")?;
+        let mut skipped = 0;
+        let toc_items = self.parse_toc_items(list, 0, &mut skipped);
+        if skipped > 0 {
+            log::warn!(
+                "left out '{}' malformed items of the table of contents",
+                skipped
+            );
+        }
+        if toc_items.is_empty() {
+            log::warn!("the table of contents has no items");
+            return Ok(None);
         }
 
         log::debug!("parse_toc finished successfully");
         Ok(Some(TableOfContents::new(toc_title, toc_items)))
     }
 
+    /// A helper function that parses the items of a list in the table of contents. Items that
+    /// can't be parsed are counted in skipped, their sub items take their place
+    fn parse_toc_items(
+        &self,
+        list: Node,
+        level: i32,
+        skipped: &mut usize,
+    ) -> Vec<TableOfContentsItem> {
+        let mut items: Vec<TableOfContentsItem> = Vec::new();
+        if level >= MAX_TOC_DEPTH {
+            *skipped += list.find(Name("li")).count();
+            return items;
+        }
+
+        for (i, node) in list
+            .children()
+            .filter(|node| node.is(Name("li")))
+            .enumerate()
+        {
+            match self.parse_toc_item(node, i + 1, level, skipped) {
+                Ok(item) => items.push(item),
+                Err(error) => {
+                    log::debug!("{}", error);
+                    *skipped += 1;
+                    if let Some(sub_list) = toc_sub_list(node) {
+                        items.extend(self.parse_toc_items(sub_list, level, skipped));
+                    }
+                }
+            }
+        }
+        items
+    }
+
     /// A helper function that parses a single node from a html document into a
-    /// TableOfContentsItem. Any errors it encounters are returned
-    fn parse_toc_item(&self, node: Node, level: i32) -> Result<TableOfContentsItem> {
+    /// TableOfContentsItem. Without a number, the position of the item is used. Any errors it
+    /// encounters are returned
+    fn parse_toc_item(
+        &self,
+        node: Node,
+        position: usize,
+        level: i32,
+        skipped: &mut usize,
+    ) -> Result<TableOfContentsItem> {
         // get the item number
-        let item_number = node
-            .find(Class("tocnumber"))
-            .next()
-            .context("Couldn't find the number for the current item")?
-            .text();
+        let item_number = toc_item_part(node, Class("tocnumber"))
+            .map(|number| number.text())
+            .unwrap_or_else(|| position.to_string());
 
-        // get the text
-        let item_text = node
-            .find(Class("toctext"))
-            .next()
-            .context("Couldn't find the text for the current item")?
-            .text();
+        // get the text, the link itself is used when it's missing
+        let item_text = toc_item_part(node, Class("toctext"))
+            .or_else(|| toc_item_part(node, Name("a")))
+            .map(|text| text.text().trim().to_string())
+            .filter(|text| !text.is_empty())
+            .context("Couldn't find the text for the current item")?;
+
+        // get the header this item links to
+        let header_id = toc_item_part(node, Name("a"))
+            .and_then(|link| link.attr("href"))
+            .and_then(|href| href.strip_prefix('#'))
+            .and_then(|anchor| self.header_ids.get(anchor))
+            .copied();
 
         // if there are any sub items, parse them
-        let mut sub_items: Vec<TableOfContentsItem> = Vec::new();
-        if let Some(items) = node.find(Name("ul")).next() {
-            for item in items.find(Name("li")) {
-                if let Ok(parsed_item) = self.parse_toc_item(item, level + 1) {
-                    sub_items.push(parsed_item);
-                    continue;
-                }
-            }
-        }
+        let sub_items = match toc_sub_list(node) {
+            Some(sub_list) => self.parse_toc_items(sub_list, level + 1, skipped),
+            None => Vec::new(),
+        };
 
         // put number and text into a hashmap
         let data = {
@@ -133,7 +213,7 @@ impl DefaultParser {
         };
 
         // return everything
-        Ok(TableOfContentsItem::new(level, text, {
+        Ok(TableOfContentsItem::new(level, text, header_id, {
             if sub_items.is_empty() {
                 None
             } else {
@@ -148,6 +228,10 @@ impl DefaultParser {
         match node.name().unwrap_or_default() {
             "h2" | "h3" | "h4" | "h5" => {
                 if let Some(headline_node) = node.find(Class("mw-headline")).next() {
+                    // remember the header so the table of contents can link to it
+                    if let Some(anchor) = headline_node.attr("id") {
+                        self.header_ids.insert(anchor.to_string(), self.get_id());
+                    }
                     self.push_header(headline_node.text(), true)
                 }
             }
@@ -194,16 +278,28 @@ impl DefaultParser {
                 // after every list we want a newline
                 self.push_newline()
             }
-            "pre" => {
-                // for the code blocks, we just parse it like normal but add a newline at the
-                // beginning and the end
-                self.push_newline();
-                if let Some(code_node) = node.find(Name("code")).next() {
-                    for child in code_node.children() {
-                        self.parse_node(child)
-                    }
+            "sup" if node.is(Class("reference")) => self.parse_reference(node),
+            "span" if node.is(Class("mwe-math-element")) => self.parse_math(node),
+            // formulas on their own lines are indented with a description list
+            "dl" if node.find(Class("mwe-math-element")).next().is_some() => {
+                for child in node.children().flat_map(|child| child.children()) {
+                    self.parse_node(child)
+                }
+                if !self.ends_with_newline() {
+                    self.push_newline();
+                }
+            }
+            "figure" if CONFIG.features.images => self.parse_image(node),
+            "div" if CONFIG.features.images && node.is(Class("thumb")) => self.parse_image(node),
+            "pre" => self.parse_code_block(node),
+            "div" if node.is(Class("mw-highlight")) => {
+                if let Some(code_node) = node.find(Name("pre")).next() {
+                    self.parse_code_block(code_node)
                 }
-                self.push_newline();
+            }
+            "blockquote" => self.parse_quote(node),
+            "table" if CONFIG.settings.article.infobox && node.is(Class("infobox")) => {
+                self.parse_infobox(node)
             }
             _ => {
                 // only if the node is raw text, we add it. we wont add any other nodes
@@ -214,6 +310,240 @@ impl DefaultParser {
         }
     }
 
+    /// A helper function that parses a code block. Its whitespace is preserved and every line of
+    /// code becomes its own element, so it's rendered on its own line
+    fn parse_code_block(&mut self, node: Node) {
+        self.push_block_start();
+        let style = Style::from(CONFIG.theme.code_block());
+        for line in node.text().trim_matches('\n').lines() {
+            let content = line.trim_end().replace('\t', CODE_TAB);
+            self.elements.push(
+                ArticleElement::new(self.get_id(), content.chars().count(), style, content)
+                    .attribute("type", "code"),
+            );
+        }
+        self.push_newline();
+    }
+
+    /// A helper function that parses a quote. Its elements are marked as quoted, so they are
+    /// indented when they are rendered
+    fn parse_quote(&mut self, node: Node) {
+        self.push_block_start();
+        let start = self.elements.len();
+        let was_in_quote = std::mem::replace(&mut self.in_quote, true);
+        for child in node.children() {
+            self.parse_node(child)
+        }
+        self.in_quote = was_in_quote;
+
+        for element in self.elements[start..]
+            .iter_mut()
+            .filter(|element| element.get_attribute("type") != Some("newline"))
+        {
+            element.set_attribute("quote", "true");
+        }
+        if !self.ends_with_newline() {
+            self.push_newline();
+        }
+    }
+
+    /// A helper function that parses an infobox. Its title, the headers of its sections and every
+    /// row with a label and data become their own elements, so it's rendered as a panel. Rows
+    /// without a label, like images, are left out
+    fn parse_infobox(&mut self, node: Node) {
+        self.push_block_start();
+        let start = self.elements.len();
+
+        let title = node
+            .find(Name("caption"))
+            .next()
+            .or_else(|| node.find(Class("infobox-above")).next())
+            .map(|title| collapse_whitespace(&title.text()))
+            .filter(|title| !title.is_empty());
+        if let Some(title) = title {
+            self.elements.push(
+                ArticleElement::new(
+                    self.get_id(),
+                    title.chars().count(),
+                    Style::from(CONFIG.theme.title).combine(Effect::Bold),
+                    title,
+                )
+                .attribute("type", "infobox_title"),
+            );
+        }
+
+        // the rows of nested tables belong to the cells they are in
+        for row in node.find(Name("tr")).filter(|row| {
+            std::iter::successors(row.parent(), Node::parent)
+                .find(|ancestor| ancestor.is(Name("table")))
+                .is_some_and(|table| table.index() == node.index())
+        }) {
+            let cell = |name| {
+                row.children()
+                    .find(|child| child.is(Name(name)))
+                    .map(|cell| {
+                        let mut text = String::new();
+                        push_cell_text(cell, &mut text);
+                        collapse_whitespace(&text)
+                    })
+                    .filter(|text| !text.is_empty())
+            };
+
+            match (cell("th"), cell("td")) {
+                (Some(label), Some(data)) => self.elements.push(
+                    ArticleElement::new(
+                        self.get_id(),
+                        data.chars().count(),
+                        Style::from(CONFIG.theme.text),
+                        data,
+                    )
+                    .attribute("type", "infobox")
+                    .attribute("label", &label),
+                ),
+                // the title is already displayed
+                (Some(header), None)
+                    if !row.is(Class("infobox-above"))
+                        && row.find(Class("infobox-above")).next().is_none() =>
+                {
+                    self.elements.push(
+                        ArticleElement::new(
+                            self.get_id(),
+                            header.chars().count(),
+                            Style::from(CONFIG.theme.title).combine(Effect::Bold),
+                            header,
+                        )
+                        .attribute("type", "infobox_header"),
+                    )
+                }
+                _ => (),
+            }
+        }
+
+        // an empty infobox isn't displayed
+        if self.elements.len() == start {
+            return;
+        }
+        self.push_newline();
+    }
+
+    /// A helper function that parses an image with its caption. Images are put on their own line,
+    /// with the caption below them
+    fn parse_image(&mut self, node: Node) {
+        let image = match node.find(Name("img")).next() {
+            Some(image) => image,
+            None => return,
+        };
+
+        let caption = node
+            .find(Name("figcaption"))
+            .next()
+            .or_else(|| node.find(Class("thumbcaption")).next())
+            .map(|caption| caption.text().trim().to_string())
+            .filter(|caption| !caption.is_empty())
+            .or_else(|| image.attr("alt").map(|alt| alt.trim().to_string()))
+            .filter(|caption| !caption.is_empty())
+            .unwrap_or_else(|| "Image".to_string());
+
+        self.push_newline();
+        let content = format!("[{}]", caption);
+        let mut element = ArticleElement::new(
+            self.get_id(),
+            content.chars().count(),
+            Style::from(CONFIG.theme.text).combine(Effect::Italic),
+            content,
+        )
+        .attribute("type", "image");
+        if let Some(src) = image.attr("src") {
+            element.set_attribute("src", src);
+        }
+        self.elements.push(element);
+        self.push_newline();
+    }
+
+    /// A helper function that parses a math formula. Its TeX source is displayed in the style of
+    /// formulas. A formula on its own line gets its own line too, and is displayed as an image
+    /// above its source when images are enabled
+    fn parse_math(&mut self, node: Node) {
+        let source = match node
+            .find(Name("annotation").and(Attr("encoding", "application/x-tex")))
+            .next()
+            .map(|annotation| annotation.text())
+            .or_else(|| {
+                node.find(Name("math"))
+                    .next()?
+                    .attr("alttext")
+                    .map(str::to_string)
+            })
+            .or_else(|| {
+                node.find(Name("img"))
+                    .next()?
+                    .attr("alt")
+                    .map(str::to_string)
+            }) {
+            Some(source) => math_source(&source),
+            None => return,
+        };
+
+        let is_display = node.is(Class("mwe-math-element-block"))
+            || node
+                .find(Class("mwe-math-fallback-image-display"))
+                .next()
+                .is_some()
+            || node.find(Class("mwe-math-mathml-display")).next().is_some();
+        if !is_display {
+            self.elements.push(
+                ArticleElement::new(self.get_id(), source.chars().count(), math_style(), source)
+                    .attribute("type", "math"),
+            );
+            return;
+        }
+
+        self.push_block_start();
+        let mut element =
+            ArticleElement::new(self.get_id(), source.chars().count(), math_style(), source)
+                .attribute("type", "math");
+        // the images of formulas are svgs, which can't be decoded. The same image is available
+        // as a png
+        if let Some(src) = node
+            .find(Name("img"))
+            .next()
+            .and_then(|image| image.attr("src"))
+            .filter(|src| src.contains(MATH_SVG_PATH))
+            .filter(|_| CONFIG.features.images)
+        {
+            element.set_attribute("type", "image");
+            element.set_attribute("src", &src.replace(MATH_SVG_PATH, MATH_PNG_PATH));
+        }
+        self.elements.push(element);
+        self.push_newline();
+    }
+
+    /// A helper function that parses the marker of a reference, like `[1]`. The marker links to
+    /// the reference by its anchor
+    fn parse_reference(&mut self, node: Node) {
+        let anchor = match node
+            .find(Name("a"))
+            .next()
+            .and_then(|link| link.attr("href"))
+            .and_then(|href| href.strip_prefix('#'))
+        {
+            Some(anchor) => anchor,
+            None => return,
+        };
+
+        let content = node.text();
+        self.elements.push(
+            ArticleElement::new(
+                self.get_id(),
+                content.chars().count(),
+                Style::from(CONFIG.theme.text).combine(Effect::Underline),
+                content,
+            )
+            .attribute("type", "reference")
+            .attribute("target", anchor),
+        );
+    }
+
     /// A helper function that adds a new link to the elements. It constructs an ArticleElement
     /// from the given content and target and then adds it to the array
     fn push_link(&mut self, content: String, target: &str) {
@@ -232,6 +562,12 @@ impl DefaultParser {
     /// A helper function that adds normal, optionally styled text to the elements. It constructs an
     /// ArticleElement from the given content and optional style and adds it to the array
     fn push_text(&mut self, content: String, style: Option<Style>) {
+        // quoted text has its own style
+        let style = style.or_else(|| {
+            self.in_quote
+                .then(|| Style::from(CONFIG.theme.quote()).combine(Effect::Italic))
+        });
+
         // if the content has a newline inside of it, we replace the newline with actual newlines
         // by splitting the content
         if content.contains('\n') {
@@ -291,11 +627,221 @@ impl DefaultParser {
         self.elements.push(ArticleElement::newline(self.get_id()));
     }
 
+    /// A helper function that starts a block, like a code block, on a new line. A newline is only
+    /// added when the elements don't end with one already
+    fn push_block_start(&mut self) {
+        if !self.ends_with_newline() {
+            self.push_newline();
+        }
+    }
+
+    /// A helper function that checks if the last element is a newline
+    fn ends_with_newline(&self) -> bool {
+        self.elements
+            .last()
+            .is_none_or(|element| element.get_attribute("type") == Some("newline"))
+    }
+
     /// A helper function that generates a new id for an element
     fn get_id(&self) -> i32 {
         self.elements.len() as i32
     }
 
+    /// A helper function that retrieves the id of the article from the configuration script
+    /// wikipedia embeds into every document. If it can't be found, None is returned
+    fn get_page_id(&self, document: &Document) -> Option<i32> {
+        const PAGE_ID_KEY: &str = "\"wgArticleId\":";
+
+        document.find(Name("script")).find_map(|script| {
+            let text = script.text();
+            let start = text.find(PAGE_ID_KEY)? + PAGE_ID_KEY.len();
+            text[start..]
+                .chars()
+                .take_while(|char| char.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .ok()
+        })
+    }
+
+    /// A helper function that retrieves the title of the redirect that led to the article from the
+    /// configuration script. If the article wasn't reached through a redirect, None is returned
+    fn get_redirected_from(&self, document: &Document) -> Option<String> {
+        const REDIRECTED_FROM_KEY: &str = "\"wgRedirectedFrom\":";
+
+        document.find(Name("script")).find_map(|script| {
+            let text = script.text();
+            let start = text.find(REDIRECTED_FROM_KEY)? + REDIRECTED_FROM_KEY.len();
+            // the value is a json string, everything following it is ignored
+            let title = serde_json::Deserializer::from_str(&text[start..])
+                .into_iter::<String>()
+                .next()?
+                .ok()?;
+            Some(title.replace('_', " "))
+        })
+    }
+
+    /// A helper function that retrieves when the article was last edited. The date of the
+    /// structured data wikipedia embeds is preferred, otherwise the text in the footer is used
+    fn get_last_modified(&self, document: &Document) -> Option<String> {
+        const DATE_MODIFIED_KEY: &str = "\"dateModified\":\"";
+
+        document
+            .find(Attr("type", "application/ld+json"))
+            .find_map(|script| {
+                let text = script.text();
+                let start = text.find(DATE_MODIFIED_KEY)? + DATE_MODIFIED_KEY.len();
+                let end = text[start..].find('"')? + start;
+                Some(text[start..end].to_string())
+            })
+            .or_else(|| {
+                let footer = document.find(Attr("id", "footer-info-lastmod")).next()?;
+                let text = footer.text();
+                let text = text.trim();
+                Some(
+                    text.strip_prefix("This page was last edited on ")
+                        .unwrap_or(text)
+                        .trim_end_matches('.')
+                        .to_string(),
+                )
+            })
+    }
+
+    /// A helper function that retrieves the canonical url of the article from the document
+    fn get_canonical_url(&self, document: &Document) -> Option<String> {
+        document
+            .find(Name("link"))
+            .find(|link| link.attr("rel") == Some("canonical"))
+            .and_then(|link| link.attr("href"))
+            .map(|href| href.to_string())
+    }
+
+    /// A helper function that retrieves the anchor and text of every reference in the document
+    fn get_references(&self, document: &Document) -> Vec<(String, String)> {
+        document
+            .find(Class("references"))
+            .flat_map(|references| references.children().filter(|node| node.is(Name("li"))))
+            .map(|reference| {
+                let anchor = reference.attr("id").unwrap_or_default().to_string();
+                let text = reference
+                    .find(Class("reference-text"))
+                    .next()
+                    .unwrap_or(reference)
+                    .text();
+                (anchor, text.trim().to_string())
+            })
+            .collect()
+    }
+
+    /// A helper function that checks if the document is a disambiguation page. Those pages have a
+    /// disambiguation box or are in the category of disambiguation pages
+    fn is_disambiguation(&self, document: &Document) -> bool {
+        document.find(Attr("id", "disambigbox")).next().is_some()
+            || document.find(Class("dmbox-disambig")).next().is_some()
+            || document
+                .find(Name("script"))
+                .any(|script| script.text().contains("\"Disambiguation pages\""))
+    }
+
+    /// A helper function that retrieves the meanings a disambiguation page lists. The items of the
+    /// table of contents only link to anchors, so they are skipped
+    fn get_disambiguation_links(&self, document: &Document) -> Vec<(String, String)> {
+        self.get_list_links(
+            document
+                .find(Class("mw-parser-output"))
+                .flat_map(|content| content.find(Name("li"))),
+        )
+    }
+
+    /// A helper function that retrieves the articles listed in the "See also" section. The section
+    /// ends with the next header of the same level
+    fn get_see_also_links(&self, document: &Document) -> Vec<(String, String)> {
+        let is_h2 = |node: &Node| node.is(Name("h2")) || node.is(Class("mw-heading2"));
+        let content = match document.find(Class("mw-parser-output")).next() {
+            Some(content) => content,
+            None => return Vec::new(),
+        };
+
+        let section = content
+            .children()
+            .skip_while(|node| {
+                !(is_h2(node)
+                    && (node.attr("id") == Some("See_also")
+                        || node.find(Attr("id", "See_also")).next().is_some()))
+            })
+            .skip(1)
+            .take_while(|node| !is_h2(node));
+        self.get_list_links(section.flat_map(|node| {
+            // the list items can be the node itself or be wrapped in columns
+            node.is(Name("li"))
+                .then_some(node)
+                .into_iter()
+                .chain(node.find(Name("li")))
+        }))
+    }
+
+    /// A helper function that retrieves the article every list item links to. Every item is
+    /// described by its text, the text of nested lists belongs to their own items
+    fn get_list_links<'a>(&self, items: impl Iterator<Item = Node<'a>>) -> Vec<(String, String)> {
+        let mut links: Vec<(String, String)> = Vec::new();
+        for item in items {
+            let target = match item
+                .find(Name("a"))
+                .filter_map(|link| link.attr("href"))
+                .find(|href| href.starts_with("/wiki/") && !href.contains(':'))
+            {
+                Some(target) => target.to_string(),
+                None => continue,
+            };
+            if links
+                .iter()
+                .any(|(_, other_target)| *other_target == target)
+            {
+                continue;
+            }
+
+            let description = item
+                .children()
+                .filter(|child| !child.is(Name("ul")) && !child.is(Name("ol")))
+                .map(|child| child.text())
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<&str>>()
+                .join(" ");
+            links.push((description, target));
+        }
+        links
+    }
+
+    /// A helper function that retrieves the node containing the content of the article. Documents
+    /// of a single section only contain the content without the rest of the page
+    fn get_content<'a>(&self, document: &'a Document) -> Result<Node<'a>> {
+        if document.find(Attr("id", "content")).next().is_none() {
+            return document
+                .find(Class("mw-parser-output"))
+                .next()
+                .context("Couldn't find the node 'mw-parser-output'");
+        }
+
+        document
+            .find(Attr("id", "content"))
+            .into_selection()
+            .first()
+            .context("Couldn't find the node 'content'")?
+            .find(Attr("id", "bodyContent"))
+            .into_selection()
+            .first()
+            .context("Couldn't find the node 'bodyContent")?
+            .find(Attr("id", "mw-content-text"))
+            .into_selection()
+            .first()
+            .context("Couldn't find the node 'mw-content-text'")?
+            .find(Class("mw-parser-output"))
+            .into_selection()
+            .first()
+            .context("Couldn't find the node 'mw-parser-output'")
+    }
+
     /// A helper function that retrieves the title of the article from the document
     fn get_title(&self, document: &Document) -> Result<String> {
         Ok(document
@@ -306,7 +852,79 @@ impl DefaultParser {
     }
 }
 
+/// Helper function that appends the text of a cell of an infobox to a string. Line breaks and
+/// Returns the readable TeX source of a formula. Wikipedia wraps most formulas into a
+/// `{\displaystyle ...}` block, which is removed
+pub fn math_source(tex: &str) -> String {
+    let tex = collapse_whitespace(tex);
+    let tex = tex.trim();
+    ["{\\displaystyle", "{\\textstyle"]
+        .iter()
+        .find_map(|prefix| tex.strip_prefix(prefix)?.strip_suffix('}'))
+        .unwrap_or(tex)
+        .trim()
+        .to_string()
+}
+
+/// Returns the style formulas are displayed in
+pub fn math_style() -> Style {
+    Style::from(CONFIG.theme.code_block())
+}
+
+/// nested cells separate their text with spaces, references and styles are left out
+fn push_cell_text(node: Node, text: &mut String) {
+    if let Some(content) = node.as_text() {
+        text.push_str(content);
+        return;
+    }
+    if node.is(Name("style")) || node.is(Class("reference")) {
+        return;
+    }
+
+    let is_separated = ["br", "li", "p", "div", "tr", "th", "td"]
+        .iter()
+        .any(|name| node.is(Name(*name)));
+    if is_separated {
+        text.push(' ');
+    }
+    for child in node.children() {
+        push_cell_text(child, text);
+    }
+    if is_separated {
+        text.push(' ');
+    }
+}
+
+/// Helper function that replaces every sequence of whitespace in a text with a single space
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Helper function that returns the list of sub items of an item in the table of contents. Only
+/// the list directly inside of the item counts, lists nested deeper belong to the sub items
+fn toc_sub_list(node: Node) -> Option<Node> {
+    node.children().find(|child| child.is(Name("ul")))
+}
+
+/// Helper function that finds the first node of an item in the table of contents matching a
+/// predicate. The sub items are skipped, so their parts aren't mistaken for the ones of the item
+fn toc_item_part<P: Predicate + Copy>(node: Node, predicate: P) -> Option<Node> {
+    node.children()
+        .filter(|child| !child.is(Name("ul")))
+        .find_map(|child| {
+            if child.is(predicate) {
+                Some(child)
+            } else {
+                child.find(predicate).next()
+            }
+        })
+}
+
 impl Parser for DefaultParser {
+    fn format(&self) -> SourceFormat {
+        SourceFormat::Html
+    }
+
     /// Tries to parse a given html document into an Article. Any errors it encounters will be
     /// returned
     fn parse<R: Read>(&mut self, html: R) -> Result<Article> {
@@ -319,26 +937,11 @@ impl Parser for DefaultParser {
         // retrieve the title of the article
         let title = self.get_title(&document)?;
         log::debug!("retrieved the title '{}' from the document", &title);
-        self.push_header(title, false);
+        self.push_header(title.clone(), false);
 
         // parse the article content
-        let parsed_count = document
-            .find(Attr("id", "content"))
-            .into_selection()
-            .first()
-            .context("Couldn't find the node 'content'")?
-            .find(Attr("id", "bodyContent"))
-            .into_selection()
-            .first()
-            .context("Couldn't find the node 'bodyContent")?
-            .find(Attr("id", "mw-content-text"))
-            .into_selection()
-            .first()
-            .context("Couldn't find the node 'mw-content-text'")?
-            .find(Class("mw-parser-output"))
-            .into_selection()
-            .first()
-            .context("Couldn't find the node 'mw-parser-output'")?
+        let parsed_count = self
+            .get_content(&document)?
             .children()
             .map(|child| {
                 log::debug!("parsing the node {:?}", child);
@@ -364,14 +967,37 @@ impl Parser for DefaultParser {
             };
         }
 
+        self.header_ids.clear();
+
+        let mut article = Article::new(std::mem::take(&mut self.elements), toc);
+        article.set_title(title);
+        if let Some(page_id) = self.get_page_id(&document) {
+            article.set_page_id(page_id);
+        }
+        if let Some(last_modified) = self.get_last_modified(&document) {
+            article.set_last_modified(last_modified);
+        }
+        if let Some(redirected_from) = self.get_redirected_from(&document) {
+            article.set_redirected_from(redirected_from);
+        }
+        if let Some(canonical_url) = self.get_canonical_url(&document) {
+            article.set_canonical_url(canonical_url);
+        }
+        article.set_references(self.get_references(&document));
+        if self.is_disambiguation(&document) {
+            log::debug!("the article is a disambiguation page");
+            article.set_disambiguation_links(self.get_disambiguation_links(&document));
+        }
+        article.set_see_also_links(self.get_see_also_links(&document));
+
         log::debug!("parse finished successfully");
-        Ok(Article::new(std::mem::take(&mut self.elements), toc))
+        Ok(article)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ArticleElement, DefaultParser, Parser};
+    use super::{math_source, ArticleElement, DefaultParser, Parser};
     use crate::config::CONFIG;
     use cursive::theme::{Effect, Style};
 
@@ -445,6 +1071,173 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_toc_header_id() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><div id=\"toc\"><div class=\"toctitle\"><h2>Contents</h2></div><ul><li><a href=\"#History\"><span class=\"tocnumber\">1</span><span class=\"toctext\">History</span></a><ul><li><a href=\"#Founding\"><span class=\"tocnumber\">1.1</span><span class=\"toctext\">Founding</span></a></li></ul></li></ul></div><h2><span class=\"mw-headline\" id=\"History\">History</span></h2><h3><span class=\"mw-headline\" id=\"Founding\">Founding</span></h3>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        let toc = article.toc().unwrap();
+        assert_eq!(toc.items().count(), 1);
+
+        let item = toc.items().next().unwrap();
+        assert_eq!(item.header_id(), Some(2));
+        assert_eq!(
+            article.elements().find(|x| x.id() == &2).unwrap().content(),
+            "History"
+        );
+
+        let sub_item = item.sub_items().unwrap().next().unwrap();
+        assert_eq!(*sub_item.number(), 1);
+        assert_eq!(sub_item.header_id(), Some(4));
+    }
+
+    #[test]
+    fn parse_malformed_toc() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        // the first item has no number, the second one no text and the third one no sub items
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><div id=\"toc\"><ul><li><a href=\"#History\"><span class=\"toctext\">History</span></a></li><li><span class=\"tocnumber\">2</span><ul><li><a href=\"#Founding\"><span class=\"tocnumber\">2.1</span><span class=\"toctext\">Founding</span></a></li></ul></li><li><a href=\"#Products\">Products</a><ol></ol></li></ul></div><h2><span class=\"mw-headline\" id=\"History\">History</span></h2>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        let toc = article.toc().unwrap();
+        assert_eq!(toc.title(), "Contents");
+        let items: Vec<(&str, Option<i32>, bool)> = toc
+            .items()
+            .map(|item| (item.text(), item.header_id(), item.sub_items().is_some()))
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                ("1 History", Some(2), false),
+                ("2.1 Founding", None, false),
+                ("3 Products", None, false),
+            ]
+        );
+
+        // a table of contents without any items isn't displayed
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><div id=\"toc\"><ul><li></li></ul></div>",
+        );
+        assert!(parser.parse(test_html.as_bytes()).unwrap().toc().is_none());
+    }
+
+    #[test]
+    fn parse_title_and_page_id() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<script>RLCONF={\"wgPageName\":\"GitHub\",\"wgArticleId\":18545292,\"wgIsArticle\":true};</script><h1 class=\"mw-first-heading\">Github</h1>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert_eq!(article.title(), Some("Github"));
+        assert_eq!(article.page_id(), Some(18545292));
+        assert_eq!(article.redirected_from(), None);
+
+        let test_html = generate_html(
+            "<script>RLCONF={\"wgArticleId\":18545292,\"wgRedirectedFrom\":\"Git_Hub\",\"wgIsRedirect\":false};</script><h1 class=\"mw-first-heading\">GitHub</h1>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+        assert_eq!(article.redirected_from(), Some("Git Hub"));
+    }
+
+    #[test]
+    fn parse_metadata() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = format!(
+            "<head><link rel=\"canonical\" href=\"https://en.wikipedia.org/wiki/GitHub\"><script type=\"application/ld+json\">{{\"name\":\"GitHub\",\"dateModified\":\"2022-05-01T10:00:00Z\"}}</script></head>{}",
+            generate_html(
+                "<h1 class=\"mw-first-heading\">Github</h1><p>GitHub is a provider</p><ol class=\"references\"><li>One</li><li>Two</li></ol>",
+            )
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert_eq!(article.last_modified(), Some("2022-05-01T10:00:00Z"));
+        assert_eq!(
+            article.canonical_url(),
+            Some("https://en.wikipedia.org/wiki/GitHub")
+        );
+        assert_eq!(article.references().len(), 2);
+        assert_eq!(article.word_count(), 5);
+    }
+
+    #[test]
+    fn parse_reference() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><p>GitHub<sup class=\"reference\"><a href=\"#cite_note-1\">[1]</a></sup></p><ol class=\"references\"><li id=\"cite_note-1\"><span class=\"mw-cite-backlink\">^</span> <span class=\"reference-text\">A provider </span></li></ol>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert_eq!(
+            article.elements().find(|x| x.id() == &3).unwrap(),
+            &ArticleElement::new(
+                3,
+                3,
+                Style::from(CONFIG.theme.text).combine(Effect::Underline),
+                "[1]".to_string(),
+            )
+            .attribute("type", "reference")
+            .attribute("target", "cite_note-1")
+        );
+        assert_eq!(article.reference("cite_note-1"), Some("A provider"));
+    }
+
+    #[test]
+    fn parse_image() {
+        use select::{document::Document, predicate::Name};
+
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+        let document = Document::from(
+            "<figure><a><img src=\"//upload.wikimedia.org/Octocat.png\" alt=\"\"></a><figcaption>The Octocat</figcaption></figure>",
+        );
+        parser.parse_image(document.find(Name("figure")).next().unwrap());
+
+        assert_eq!(
+            parser.elements[1],
+            ArticleElement::new(
+                1,
+                13,
+                Style::from(CONFIG.theme.text).combine(Effect::Italic),
+                "[The Octocat]".to_string(),
+            )
+            .attribute("type", "image")
+            .attribute("src", "//upload.wikimedia.org/Octocat.png")
+        );
+    }
+
+    #[test]
+    fn parse_math() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Energy</h1><p>is <span class=\"mwe-math-element\"><span class=\"mwe-math-mathml-inline\"><math alttext=\"{\\displaystyle E=mc^{2}}\"><semantics><annotation encoding=\"application/x-tex\">{\\displaystyle E=mc^{2}}</annotation></semantics></math></span><img src=\"https://wikimedia.org/api/rest_v1/media/math/render/svg/abc\" class=\"mwe-math-fallback-image-inline\"></span></p><dl><dd><span class=\"mwe-math-element\"><img src=\"https://wikimedia.org/api/rest_v1/media/math/render/svg/def\" class=\"mwe-math-fallback-image-display\" alt=\"{\\displaystyle a^{2}+b^{2}=c^{2}}\"></span></dd></dl>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        let math = article
+            .elements()
+            .filter(|x| x.get_attribute("type") == Some("math"))
+            .map(|x| x.content())
+            .collect::<Vec<&str>>();
+        assert_eq!(math, vec!["E=mc^{2}", "a^{2}+b^{2}=c^{2}"]);
+    }
+
+    #[test]
+    fn math_sources() {
+        assert_eq!(math_source(" {\\displaystyle \n x^2 }"), "x^2");
+        assert_eq!(math_source("{\\textstyle x}"), "x");
+        assert_eq!(math_source("{x}"), "{x}");
+    }
+
     #[test]
     fn parse_bold() {
         let mut parser = DefaultParser::new(&CONFIG.settings.toc);
@@ -520,18 +1313,83 @@ mod tests {
         let mut parser = DefaultParser::new(&CONFIG.settings.toc);
 
         let test_html = generate_html(
-            "<h1 class=\"mw-first-heading\">Github</h1><pre><code>inverse(a, n) t := 0</code></pre>",
+            "<h1 class=\"mw-first-heading\">Github</h1><pre><code>inverse(a, n)\n\tt := 0</code></pre>",
         );
         let article = parser.parse(test_html.as_bytes()).unwrap();
+        let code = |id, content: &str| {
+            ArticleElement::new(
+                id,
+                content.len(),
+                Style::from(CONFIG.theme.code_block()),
+                content.to_string(),
+            )
+            .attribute("type", "code")
+        };
 
         assert_eq!(
-            article.elements().filter(|x| x.id() == &3).next().unwrap(),
+            article.elements().skip(2).collect::<Vec<&ArticleElement>>(),
+            vec![
+                &code(2, "inverse(a, n)"),
+                &code(3, "    t := 0"),
+                &ArticleElement::newline(4)
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_quote() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><blockquote><p>to be or not</p></blockquote>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert_eq!(
+            article.elements().filter(|x| x.id() == &2).next().unwrap(),
             &ArticleElement::new(
-                3,
-                20,
-                Style::from(CONFIG.theme.text),
-                "inverse(a, n) t := 0".to_string(),
+                2,
+                12,
+                Style::from(CONFIG.theme.quote()).combine(Effect::Italic),
+                "to be or not".to_string(),
             )
+            .attribute("quote", "true")
+        );
+    }
+
+    #[test]
+    fn parse_infobox() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Github</h1><table class=\"infobox\"><tbody>\
+            <tr><th class=\"infobox-above\">GitHub, Inc.</th></tr>\
+            <tr><td><img src=\"logo.png\"></td></tr>\
+            <tr><th>Founded</th><td>February 8,\n 2008</td></tr>\
+            <tr><th colspan=\"2\">Ownership</th></tr>\
+            <tr><th>Parent</th><td><table><tr><th>Owner</th><td>Microsoft<sup class=\"reference\">[1]</sup></td></tr></table></td></tr>\
+            </tbody></table><p>text</p>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert_eq!(
+            article
+                .elements()
+                .skip(2)
+                .take(5)
+                .map(|element| (
+                    element.get_attribute("type").unwrap_or_default(),
+                    element.get_attribute("label").unwrap_or_default(),
+                    element.content()
+                ))
+                .collect::<Vec<(&str, &str, &str)>>(),
+            vec![
+                ("infobox_title", "", "GitHub, Inc."),
+                ("infobox", "Founded", "February 8, 2008"),
+                ("infobox_header", "", "Ownership"),
+                ("infobox", "Parent", "Owner Microsoft"),
+                ("newline", "", ""),
+            ]
         );
     }
 
@@ -542,4 +1400,73 @@ mod tests {
         let test_html = generate_html("nope");
         assert!(parser.parse(test_html.as_bytes()).is_err())
     }
+
+    #[test]
+    fn parse_disambiguation() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Mercury</h1><p><b>Mercury</b> may refer to:</p><ul><li><a href=\"/wiki/Mercury_(planet)\">Mercury (planet)</a>, the closest planet to the Sun</li><li><a href=\"/wiki/Mercury_(element)\">Mercury (element)</a><ul><li><a href=\"/wiki/Mercury_poisoning\">Mercury poisoning</a></li></ul></li><li><a href=\"/wiki/Help:Disambiguation\">Help</a></li></ul><div id=\"disambigbox\"></div>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+
+        assert!(article.is_disambiguation());
+        assert_eq!(
+            article.disambiguation_links(),
+            &[
+                (
+                    "Mercury (planet), the closest planet to the Sun".to_string(),
+                    "/wiki/Mercury_(planet)".to_string()
+                ),
+                (
+                    "Mercury (element)".to_string(),
+                    "/wiki/Mercury_(element)".to_string()
+                ),
+                (
+                    "Mercury poisoning".to_string(),
+                    "/wiki/Mercury_poisoning".to_string()
+                ),
+            ]
+        );
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Mercury</h1><ul><li><a href=\"/wiki/Mercury_(planet)\">Mercury</a></li></ul>",
+        );
+        assert!(!parser
+            .parse(test_html.as_bytes())
+            .unwrap()
+            .is_disambiguation());
+    }
+
+    #[test]
+    fn parse_see_also() {
+        let mut parser = DefaultParser::new(&CONFIG.settings.toc);
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Rust</h1><p><a href=\"/wiki/Iron\">Iron</a></p><h2><span class=\"mw-headline\" id=\"See_also\">See also</span></h2><div class=\"div-col\"><ul><li><a href=\"/wiki/Go_(programming_language)\">Go</a></li><li><a href=\"/wiki/Portal:Programming\">Portal</a></li></ul></div><ul><li><a href=\"/wiki/Zig\">Zig</a> – another language</li></ul><h2><span class=\"mw-headline\" id=\"References\">References</span></h2><ul><li><a href=\"/wiki/Book\">Book</a></li></ul>",
+        );
+        let article = parser.parse(test_html.as_bytes()).unwrap();
+        assert_eq!(
+            article.see_also_links(),
+            &[
+                (
+                    "Go".to_string(),
+                    "/wiki/Go_(programming_language)".to_string()
+                ),
+                (
+                    "Zig – another language".to_string(),
+                    "/wiki/Zig".to_string()
+                ),
+            ]
+        );
+
+        let test_html = generate_html(
+            "<h1 class=\"mw-first-heading\">Rust</h1><ul><li><a href=\"/wiki/Iron\">Iron</a></li></ul>",
+        );
+        assert!(parser
+            .parse(test_html.as_bytes())
+            .unwrap()
+            .see_also_links()
+            .is_empty());
+    }
 }
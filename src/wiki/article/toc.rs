@@ -31,16 +31,24 @@ pub struct TableOfContentsItem {
     number: i32,
     /// The title of the item
     text: String,
+    /// The id of the header element this item belongs to, if it could be found
+    header_id: Option<i32>,
     /// The sub items of this item, if there are any
     sub_items: Option<Vec<TableOfContentsItem>>,
 }
 
 impl TableOfContentsItem {
-    /// Create a new item from a given number, text and sub items
-    pub fn new(number: i32, text: String, sub_items: Option<Vec<TableOfContentsItem>>) -> Self {
+    /// Create a new item from a given number, text, header id and sub items
+    pub fn new(
+        number: i32,
+        text: String,
+        header_id: Option<i32>,
+        sub_items: Option<Vec<TableOfContentsItem>>,
+    ) -> Self {
         Self {
             number,
             text,
+            header_id,
             sub_items,
         }
     }
@@ -55,6 +63,11 @@ impl TableOfContentsItem {
         &self.text
     }
 
+    /// The id of the header element this item belongs to, if it could be found
+    pub fn header_id(&self) -> Option<i32> {
+        self.header_id
+    }
+
     /// Iterate over the sub items of this item if there are any
     pub fn sub_items(&self) -> Option<impl Iterator<Item = &TableOfContentsItem>> {
         self.sub_items.as_ref().map(|sub_items| sub_items.iter())
@@ -0,0 +1,635 @@
+use crate::config::{TocSettings, TocTitle, CONFIG};
+use crate::wiki::article::{
+    compiled_article::Article,
+    element::ArticleElement,
+    parser::{math_source, math_style, Parser, SourceFormat},
+    toc::{TableOfContents, TableOfContentsItem},
+};
+
+use anyhow::{Context, Result};
+use cursive::theme::{Effect, Style};
+use std::io::Read;
+
+/// The prefixes of links that don't link to articles and are removed from the wikitext
+const IGNORED_LINKS: [&str; 6] = [
+    "[[File:",
+    "[[file:",
+    "[[Image:",
+    "[[image:",
+    "[[Category:",
+    "[[category:",
+];
+
+/// Marks the start of a formula that was taken out of the wikitext, its index follows
+const MATH_START: char = '\u{E000}';
+/// Marks the end of a formula that was taken out of the wikitext
+const MATH_END: char = '\u{E001}';
+
+/// A Parser for the raw wikitext of an article. It understands headers, lists, links and bold
+/// or italic text. Everything else, like templates, tables and references, is removed
+pub struct WikitextParser {
+    /// The elements that have been parsed already
+    elements: Vec<ArticleElement>,
+    /// The toc configuration
+    toc_settings: TocSettings,
+    /// The title of the article, the wikitext itself doesn't contain it
+    title: String,
+    /// The level, text and id of every header, used for the table of contents
+    headers: Vec<(usize, String, i32)>,
+    /// The TeX sources of the formulas, they are replaced with markers while the markup is removed
+    formulas: Vec<String>,
+}
+
+impl WikitextParser {
+    /// Creates a new WikitextParser with a given toc configuration and the title of the article
+    pub fn new(toc_settings: &TocSettings, title: String) -> Self {
+        log::debug!("creating a new instance of WikitextParser");
+        Self {
+            elements: Vec::new(),
+            toc_settings: toc_settings.clone(),
+            title,
+            headers: Vec::new(),
+            formulas: Vec::new(),
+        }
+    }
+
+    /// Generates a TableOfContents from the headers of the article. If the article has no headers,
+    /// None is returned
+    fn parse_toc(&self) -> Option<TableOfContents> {
+        if self.headers.is_empty() {
+            return None;
+        }
+
+        let toc_title = match self.toc_settings.title {
+            TocTitle::DEFAULT => "Contents".to_string(),
+            TocTitle::ARTICLE => self.title.clone(),
+            TocTitle::CUSTOM => self
+                .toc_settings
+                .title_custom
+                .clone()
+                .unwrap_or_else(|| "NONE".to_string()),
+        };
+
+        Some(TableOfContents::new(
+            toc_title,
+            self.parse_toc_items(&self.headers, "", 0),
+        ))
+    }
+
+    /// A helper function that creates the toc items of the given headers. Every header following
+    /// another one with a higher level becomes one of its sub items
+    fn parse_toc_items(
+        &self,
+        headers: &[(usize, String, i32)],
+        prefix: &str,
+        level: i32,
+    ) -> Vec<TableOfContentsItem> {
+        let mut items = Vec::new();
+        let mut rest = headers;
+        let mut number = 0;
+
+        while let Some(((header_level, text, id), tail)) = rest.split_first() {
+            let sub_headers_count = tail
+                .iter()
+                .take_while(|(sub_level, _, _)| sub_level > header_level)
+                .count();
+
+            number += 1;
+            let item_number = format!("{}{}", prefix, number);
+            let sub_items = self.parse_toc_items(
+                &tail[..sub_headers_count],
+                &format!("{}.", item_number),
+                level + 1,
+            );
+
+            let text = self
+                .toc_settings
+                .item_format
+                .replace("{NUMBER}", &item_number)
+                .replace("{TEXT}", text);
+            items.push(TableOfContentsItem::new(
+                level,
+                text,
+                Some(*id),
+                if sub_items.is_empty() {
+                    None
+                } else {
+                    Some(sub_items)
+                },
+            ));
+
+            rest = &tail[sub_headers_count..];
+        }
+
+        items
+    }
+
+    /// A helper function that parses the inline markup of a text (links, bold and italic text)
+    /// and adds the resulting elements
+    fn parse_inline(&mut self, text: &str) {
+        let mut content = String::new();
+        let mut bold = false;
+        let mut italic = false;
+        let mut rest = text;
+
+        while let Some(char) = rest.chars().next() {
+            if let Some(after) = rest.strip_prefix("'''") {
+                self.push_text(std::mem::take(&mut content), bold, italic);
+                bold = !bold;
+                rest = after;
+                continue;
+            }
+
+            if let Some(after) = rest.strip_prefix("''") {
+                self.push_text(std::mem::take(&mut content), bold, italic);
+                italic = !italic;
+                rest = after;
+                continue;
+            }
+
+            // formulas were replaced with their index between two markers
+            if let Some((index, after)) = rest
+                .strip_prefix(MATH_START)
+                .and_then(|after| after.split_once(MATH_END))
+            {
+                self.push_text(std::mem::take(&mut content), bold, italic);
+                self.push_math(index);
+                rest = after;
+                continue;
+            }
+
+            // internal links look like [[Target]] or [[Target|Label]]
+            if let Some((link, after)) = rest
+                .strip_prefix("[[")
+                .and_then(|after| after.split_once("]]"))
+            {
+                let (target, label) = link.split_once('|').unwrap_or((link, link));
+                if target.starts_with('#') {
+                    content.push_str(label);
+                } else {
+                    self.push_text(std::mem::take(&mut content), bold, italic);
+                    let target = format!("/wiki/{}", target.trim().replace(' ', "_"));
+                    self.push_link(label.to_string(), &target);
+                }
+                rest = after;
+                continue;
+            }
+
+            // external links look like [https://example.org Label]
+            if let Some((link, after)) = rest
+                .strip_prefix('[')
+                .filter(|after| after.starts_with("http"))
+                .and_then(|after| after.split_once(']'))
+            {
+                let (target, label) = link.split_once(' ').unwrap_or((link, link));
+                self.push_text(std::mem::take(&mut content), bold, italic);
+                self.push_link(label.trim().to_string(), target);
+                rest = after;
+                continue;
+            }
+
+            content.push(char);
+            rest = &rest[char.len_utf8()..];
+        }
+
+        self.push_text(content, bold, italic);
+    }
+
+    /// A helper function that adds a new link to the elements. It constructs an ArticleElement
+    /// from the given content and target and then adds it to the array
+    fn push_link(&mut self, content: String, target: &str) {
+        self.elements.push(
+            ArticleElement::new(
+                self.get_id(),
+                content.chars().count(),
+                Style::from(CONFIG.theme.text).combine(Effect::Underline),
+                content,
+            )
+            .attribute("type", "link")
+            .attribute("target", target),
+        );
+    }
+
+    /// A helper function that adds the formula with a given index to the elements
+    fn push_math(&mut self, index: &str) {
+        let source = match index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| self.formulas.get(index))
+        {
+            Some(source) => source.clone(),
+            None => return,
+        };
+        self.elements.push(
+            ArticleElement::new(self.get_id(), source.chars().count(), math_style(), source)
+                .attribute("type", "math"),
+        );
+    }
+
+    /// A helper function that adds text to the elements, optionally bold or italic. Empty text is
+    /// skipped
+    fn push_text(&mut self, content: String, bold: bool, italic: bool) {
+        if content.is_empty() {
+            return;
+        }
+
+        let mut style = Style::from(CONFIG.theme.text);
+        if bold {
+            style = style.combine(Effect::Bold);
+        }
+        if italic {
+            style = style.combine(Effect::Italic);
+        }
+
+        self.elements.push(ArticleElement::new(
+            self.get_id(),
+            content.chars().count(),
+            style,
+            content,
+        ));
+    }
+
+    /// A helper function that add a header to the elements. Only toc headers can be jumped to
+    fn push_header(&mut self, content: String, is_toc_header: bool) {
+        self.elements.push(
+            ArticleElement::new(
+                self.get_id(),
+                content.chars().count(),
+                Style::from(CONFIG.theme.title).combine(Effect::Bold),
+                content,
+            )
+            .attribute("type", "header")
+            .attribute(
+                "is_toc_header",
+                if is_toc_header { "true" } else { "false" },
+            ),
+        );
+        self.push_newline();
+    }
+
+    /// A helper function that adds the collected lines of a paragraph and clears them
+    fn push_paragraph(&mut self, paragraph: &mut String) {
+        if paragraph.is_empty() {
+            return;
+        }
+        self.parse_inline(&std::mem::take(paragraph));
+        self.push_newline();
+    }
+
+    /// A helper function that adds a newline to the elements
+    fn push_newline(&mut self) {
+        self.elements.push(ArticleElement::newline(self.get_id()));
+    }
+
+    /// A helper function that generates a new id for an element
+    fn get_id(&self) -> i32 {
+        self.elements.len() as i32
+    }
+}
+
+impl Parser for WikitextParser {
+    fn format(&self) -> SourceFormat {
+        SourceFormat::Wikitext
+    }
+
+    fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    /// Tries to parse the wikitext of an article into an Article. Any errors it encounters will be
+    /// returned
+    fn parse<R: Read>(&mut self, mut wikitext: R) -> Result<Article> {
+        log::debug!("parse was called");
+
+        let mut source = String::new();
+        wikitext
+            .read_to_string(&mut source)
+            .context("failed reading the wikitext")?;
+        let (source, formulas) = extract_math(&source);
+        self.formulas = formulas;
+        let source = strip_markup(&source);
+
+        self.push_header(self.title.clone(), false);
+
+        let mut paragraph = String::new();
+        let mut in_list = false;
+        for line in source.lines().map(str::trim) {
+            if in_list && !line.starts_with(['*', '#']) {
+                self.push_newline();
+                in_list = false;
+            }
+
+            if let Some((level, text)) = parse_header(line) {
+                self.push_paragraph(&mut paragraph);
+                self.headers.push((level, text.clone(), self.get_id()));
+                self.push_header(text, true);
+                continue;
+            }
+
+            if line.starts_with(['*', '#']) {
+                self.push_paragraph(&mut paragraph);
+                self.push_newline();
+                self.push_text("\t- ".to_string(), false, false);
+                self.parse_inline(line.trim_start_matches(['*', '#', ':']).trim());
+                in_list = true;
+                continue;
+            }
+
+            if line.is_empty() {
+                self.push_paragraph(&mut paragraph);
+                continue;
+            }
+
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(line);
+        }
+        if in_list {
+            self.push_newline();
+        }
+        self.push_paragraph(&mut paragraph);
+
+        log::debug!(
+            "parsed the wikitext into '{}' elements",
+            self.elements.len()
+        );
+
+        let toc = if CONFIG.features.toc {
+            self.parse_toc()
+        } else {
+            None
+        };
+        self.headers.clear();
+        self.formulas.clear();
+
+        let mut article = Article::new(std::mem::take(&mut self.elements), toc);
+        article.set_title(self.title.clone());
+
+        log::debug!("parse finished successfully");
+        Ok(article)
+    }
+}
+
+/// Returns the level and the text of a header line like `== Text ==`
+fn parse_header(line: &str) -> Option<(usize, String)> {
+    let level = line.chars().take_while(|char| *char == '=').count();
+    let closing = line.chars().rev().take_while(|char| *char == '=').count();
+    if level < 2 || closing < level || line.len() <= level * 2 {
+        return None;
+    }
+
+    let text = line[level..line.len() - level].trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some((level, text.to_string()))
+}
+
+/// Returns the title of the article a redirect links to, if the wikitext is the one of a redirect.
+/// The section the redirect may link to is left out
+pub fn redirect_target(wikitext: &str) -> Option<String> {
+    const REDIRECT_KEYWORD: &str = "#redirect";
+
+    let wikitext = wikitext.trim_start();
+    let keyword = wikitext.get(..REDIRECT_KEYWORD.len())?;
+    if !keyword.eq_ignore_ascii_case(REDIRECT_KEYWORD) {
+        return None;
+    }
+    let link = wikitext[REDIRECT_KEYWORD.len()..]
+        .trim_start_matches(|char: char| char == ':' || char.is_whitespace())
+        .strip_prefix("[[")?;
+    let link = &link[..link.find("]]")?];
+    let title = link.split(['|', '#']).next()?.trim();
+    if title.is_empty() {
+        return None;
+    }
+    Some(title.replace('_', " "))
+}
+
+/// Removes the markup that can't be displayed: comments, references, templates, tables, files,
+/// categories and html tags
+fn strip_markup(wikitext: &str) -> String {
+    let mut text = remove_blocks(wikitext, "<!--", "<!--", "-->");
+    text = remove_references(&text);
+    text = remove_blocks(&text, "{{", "{{", "}}");
+    text = remove_blocks(&text, "{|", "{|", "|}");
+    for prefix in IGNORED_LINKS {
+        text = remove_blocks(&text, prefix, "[[", "]]");
+    }
+    remove_tags(&text).replace("&nbsp;", " ")
+}
+
+/// Replaces every formula, like `<math>E = mc^2</math>`, with its index between two markers.
+/// Otherwise removing the markup would break their TeX source. The sources are returned too
+fn extract_math(wikitext: &str) -> (String, Vec<String>) {
+    let mut result = String::with_capacity(wikitext.len());
+    let mut formulas = Vec::new();
+    let mut rest = wikitext;
+
+    while let Some(begin) = rest.find("<math") {
+        result.push_str(&rest[..begin]);
+        let tag = &rest[begin..];
+
+        let (tag_end, end) = match (tag.find('>'), tag.find("</math>")) {
+            (Some(tag_end), Some(end))
+                if tag["<math".len()..].starts_with([' ', '>'])
+                    && tag_end < end
+                    && !tag[..tag_end].ends_with('/') =>
+            {
+                (tag_end, end)
+            }
+            _ => {
+                result.push_str("<math");
+                rest = &tag["<math".len()..];
+                continue;
+            }
+        };
+        result.push(MATH_START);
+        result.push_str(&formulas.len().to_string());
+        result.push(MATH_END);
+        formulas.push(math_source(&tag[tag_end + 1..end]));
+        rest = &tag[end + "</math>".len()..];
+    }
+
+    result.push_str(rest);
+    (result, formulas)
+}
+
+/// Removes every block beginning with `start`. A block ends at the `close` matching its `open`,
+/// nested blocks are removed with it. Blocks that aren't closed are removed until the end
+fn remove_blocks(text: &str, start: &str, open: &str, close: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(begin) = rest.find(start) {
+        result.push_str(&rest[..begin]);
+
+        let mut depth = 0usize;
+        let mut position = begin;
+        while let Some(char) = rest[position..].chars().next() {
+            if rest[position..].starts_with(open) {
+                depth += 1;
+                position += open.len();
+            } else if rest[position..].starts_with(close) {
+                depth = depth.saturating_sub(1);
+                position += close.len();
+                if depth == 0 {
+                    break;
+                }
+            } else {
+                position += char.len_utf8();
+            }
+        }
+        rest = &rest[position..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Removes every reference, like `<ref>...</ref>` or `<ref name="a" />`
+fn remove_references(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(begin) = rest.find("<ref") {
+        result.push_str(&rest[..begin]);
+        let tag = &rest[begin..];
+
+        // only remove ref tags, not other tags like <references />
+        if !tag[4..].starts_with([' ', '>', '/']) {
+            result.push_str("<ref");
+            rest = &tag[4..];
+            continue;
+        }
+
+        let tag_end = match tag.find('>') {
+            Some(tag_end) => tag_end,
+            None => return result,
+        };
+        rest = if tag[..tag_end].ends_with('/') {
+            &tag[tag_end + 1..]
+        } else {
+            match tag.find("</ref>") {
+                Some(end) => &tag[end + "</ref>".len()..],
+                None => return result,
+            }
+        };
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Removes the html tags, but keeps their content
+fn remove_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(begin) = rest.find('<') {
+        result.push_str(&rest[..begin]);
+        let tag = &rest[begin + 1..];
+        match tag.find('>') {
+            Some(end) if tag.starts_with(|char: char| char.is_alphabetic() || char == '/') => {
+                rest = &tag[end + 1..];
+            }
+            _ => {
+                result.push('<');
+                rest = tag;
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redirect_target, strip_markup, ArticleElement, Parser, WikitextParser};
+    use crate::config::CONFIG;
+    use cursive::theme::{Effect, Style};
+
+    #[test]
+    fn redirect_targets() {
+        assert_eq!(
+            redirect_target("#REDIRECT [[United States]]\n{{R from abbreviation}}").as_deref(),
+            Some("United States")
+        );
+        assert_eq!(
+            redirect_target("#redirect:[[Rust_(programming language)#History|Rust]]").as_deref(),
+            Some("Rust (programming language)")
+        );
+        assert_eq!(redirect_target("'''Rust''' is a [[language]]"), None);
+        assert_eq!(redirect_target("#REDIRECT [[]]"), None);
+    }
+
+    #[test]
+    fn strip() {
+        assert_eq!(
+            strip_markup("A{{Infobox|a={{b}}}}<ref name=\"x\">{{cite}}</ref> b<ref name=\"y\" />[[File:A.png|thumb|[[c]]]]<!-- c --> <small>c</small>"),
+            "A b c"
+        );
+        assert_eq!(strip_markup("{|\n| a\n|}\n1 < 2"), "\n1 < 2");
+    }
+
+    #[test]
+    fn parse() {
+        let mut parser = WikitextParser::new(&CONFIG.settings.toc, "Rust".to_string());
+        let article = parser
+            .parse(
+                "'''Rust''' is a [[programming language|language]].\n\n== History ==\n* [https://example.org Site]"
+                    .as_bytes(),
+            )
+            .unwrap();
+
+        let contents = article
+            .elements()
+            .map(|element| element.content())
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            contents,
+            vec![
+                "Rust", "", "Rust", " is a ", "language", ".", "", "History", "", "", "\t- ",
+                "Site", ""
+            ]
+        );
+
+        assert_eq!(
+            article.elements().nth(2).unwrap(),
+            &ArticleElement::new(
+                2,
+                4,
+                Style::from(CONFIG.theme.text).combine(Effect::Bold),
+                "Rust".to_string()
+            )
+        );
+        assert_eq!(
+            article.elements().nth(4).unwrap().get_attribute("target"),
+            Some("/wiki/programming_language")
+        );
+        assert_eq!(
+            article.elements().nth(11).unwrap().get_attribute("target"),
+            Some("https://example.org")
+        );
+        assert_eq!(article.title(), Some("Rust"));
+        assert_eq!(article.toc().unwrap().items().count(), 1);
+    }
+
+    #[test]
+    fn parse_math() {
+        let mut parser = WikitextParser::new(&CONFIG.settings.toc, "Energy".to_string());
+        let article = parser
+            .parse("It is <math>E = {{mc}^2}</math> or <math display=\"inline\">\\textstyle x</math>.<mathematics>".as_bytes())
+            .unwrap();
+
+        let math = article
+            .elements()
+            .filter(|element| element.get_attribute("type") == Some("math"))
+            .map(|element| element.content())
+            .collect::<Vec<&str>>();
+        assert_eq!(math, vec!["E = {{mc}^2}", "\\textstyle x"]);
+        assert!(article.elements().any(|element| element.content() == "."));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::wiki::{request, search::SearchResult};
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// The namespace of the category pages
+const CATEGORY_NAMESPACE: &str = "Category:";
+
+// NOTE: The following structs are only used for deserializing the json response
+#[derive(Deserialize)]
+struct CategoryResponse {
+    #[serde(rename = "continue")]
+    continue_code: Option<CategoryContinue>,
+    query: CategoryQuery,
+}
+
+#[derive(Deserialize)]
+struct CategoryContinue {
+    cmcontinue: String,
+}
+
+#[derive(Deserialize)]
+struct CategoryQuery {
+    categorymembers: Vec<CategoryMember>,
+}
+
+#[derive(Deserialize)]
+struct CategoryMember {
+    pageid: i32,
+    ns: usize,
+    title: String,
+    timestamp: Option<String>,
+}
+
+/// The pages of a category, as far as they were requested
+pub struct CategoryMembers {
+    /// The pages, as search results so they can be displayed like them
+    pub members: Vec<SearchResult>,
+    /// Use this token to request the following pages, if there are more
+    pub continuation: Option<String>,
+}
+
+/// Requests up to limit pages of a category from the wiki at base_url, beginning with the page of
+/// a continuation token if there is one. Subcategories and files are left out. The category can
+/// be given with or without its namespace
+#[allow(clippy::too_many_arguments)]
+pub fn category_members(
+    base_url: &str,
+    script_path: &str,
+    category: &str,
+    limit: usize,
+    continuation: Option<&str>,
+    timeout: Duration,
+    max_retries: u32,
+    user_agent: &str,
+) -> Result<CategoryMembers> {
+    let api_url = request::join_url(base_url, &format!("{}api.php", script_path))?;
+    let title = category_title(category);
+    let limit = limit.to_string();
+    let mut params = vec![
+        ("action", "query"),
+        ("format", "json"),
+        ("list", "categorymembers"),
+        ("cmtitle", title.as_str()),
+        ("cmtype", "page"),
+        ("cmprop", "ids|title|timestamp"),
+        ("cmlimit", limit.as_str()),
+    ];
+    if let Some(continuation) = continuation {
+        params.push(("cmcontinue", continuation));
+    }
+    let url = Url::parse_with_params(&api_url, &params)
+        .with_context(|| format!("couldn't build the url for the category '{}'", title))?;
+
+    log::debug!("requesting the members of '{}' from '{}'", title, api_url);
+    let response = request::get(url.as_str(), timeout, max_retries, user_agent)?;
+    let json = response.text()?;
+    parse_category_members(&json)
+}
+
+/// Helper function that returns the title of the page of a category
+fn category_title(category: &str) -> String {
+    let category = category.trim();
+    match category.get(..CATEGORY_NAMESPACE.len()) {
+        Some(namespace) if namespace.eq_ignore_ascii_case(CATEGORY_NAMESPACE) => format!(
+            "{}{}",
+            CATEGORY_NAMESPACE,
+            category[CATEGORY_NAMESPACE.len()..].trim_start()
+        ),
+        _ => format!("{}{}", CATEGORY_NAMESPACE, category),
+    }
+}
+
+/// Helper function that retrieves the pages and the continuation token from a categorymembers
+/// response
+fn parse_category_members(json: &str) -> Result<CategoryMembers> {
+    let response: CategoryResponse =
+        serde_json::from_str(json).context("the response doesn't contain any category members")?;
+    let members = response
+        .query
+        .categorymembers
+        .into_iter()
+        .map(|member| {
+            SearchResult::new(
+                member.title,
+                member.ns,
+                member.pageid,
+                None,
+                None,
+                member.timestamp,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+        })
+        .collect();
+    Ok(CategoryMembers {
+        members,
+        continuation: response
+            .continue_code
+            .map(|continue_code| continue_code.cmcontinue),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{category_title, parse_category_members};
+
+    #[test]
+    fn category_titles() {
+        assert_eq!(category_title("Physics"), "Category:Physics");
+        assert_eq!(category_title(" category: Physics"), "Category:Physics");
+        assert_eq!(category_title("Category:Physics"), "Category:Physics");
+    }
+
+    #[test]
+    fn category_members() {
+        let members = parse_category_members(
+            r#"{"batchcomplete":"","continue":{"cmcontinue":"page|4b|23","continue":"-||"},"query":{"categorymembers":[{"pageid":22939,"ns":0,"title":"Physics","timestamp":"2022-05-01T10:00:00Z"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            members
+                .members
+                .iter()
+                .map(|member| (*member.page_id(), member.title()))
+                .collect::<Vec<(i32, &str)>>(),
+            vec![(22939, "Physics")]
+        );
+        assert_eq!(members.continuation.as_deref(), Some("page|4b|23"));
+
+        let members = parse_category_members(r#"{"query":{"categorymembers":[]}}"#).unwrap();
+        assert!(members.members.is_empty());
+        assert!(members.continuation.is_none());
+        assert!(parse_category_members("<html></html>").is_err());
+    }
+}
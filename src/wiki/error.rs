@@ -0,0 +1,106 @@
+use std::fmt;
+
+/// Errors of a wiki the user should know about. They are attached as context to the errors they
+/// describe, so the cause of an error can be shown to the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WikiError {
+    /// The requested article doesn't exist
+    ArticleNotFound,
+    /// The article was fetched, but couldn't be parsed
+    ArticleParse,
+    /// The search results were fetched, but couldn't be parsed
+    SearchParse,
+    /// The request needs the network, but only cached articles can be used
+    Offline,
+    /// The wiki refuses more requests for the given amount of seconds
+    RateLimited(u64),
+}
+
+impl fmt::Display for WikiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WikiError::ArticleNotFound => write!(f, "Article not found"),
+            WikiError::ArticleParse => write!(f, "Failed to parse the article"),
+            WikiError::SearchParse => write!(f, "Failed to parse the search results"),
+            WikiError::Offline => write!(f, "Not available offline"),
+            WikiError::RateLimited(seconds) => {
+                write!(f, "Too many requests, try again in {}s", seconds)
+            }
+        }
+    }
+}
+
+/// Describes the cause of an error in a way the user can understand. If the cause isn't known,
+/// None is returned
+pub fn describe(error: &anyhow::Error) -> Option<String> {
+    if let Some(wiki_error) = error.downcast_ref::<WikiError>() {
+        return Some(wiki_error.to_string());
+    }
+
+    let request_error = error
+        .chain()
+        .find_map(|error| error.downcast_ref::<reqwest::Error>())?;
+    if request_error.is_connect() {
+        return Some("No network connection".to_string());
+    }
+    if request_error.is_timeout() {
+        return Some("The request timed out".to_string());
+    }
+    request_error
+        .status()
+        .map(|status| format!("The wiki responded with '{}'", status))
+}
+
+/// Checks if an error is likely to go away when trying again, like a lost connection, an
+/// overloaded server or too many requests
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<WikiError>() {
+        Some(WikiError::RateLimited(_)) => return true,
+        Some(_) => return false,
+        None => (),
+    }
+
+    error
+        .chain()
+        .filter_map(|error| error.downcast_ref::<reqwest::Error>())
+        .any(|error| {
+            error.is_connect()
+                || error.is_timeout()
+                || error
+                    .status()
+                    .is_some_and(|status| status.is_server_error())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{describe, is_transient, WikiError};
+    use anyhow::{anyhow, Context, Result};
+
+    #[test]
+    fn describe_errors() {
+        let error: Result<()> = Err(anyhow!("the request failed"))
+            .context(WikiError::ArticleNotFound)
+            .context("failed fetching the article");
+        assert_eq!(
+            describe(&error.unwrap_err()),
+            Some("Article not found".to_string())
+        );
+        assert_eq!(
+            describe(&anyhow!(WikiError::Offline)),
+            Some("Not available offline".to_string())
+        );
+        assert_eq!(describe(&anyhow!("something went wrong")), None);
+    }
+
+    #[test]
+    fn transient_errors() {
+        let error: Result<()> = Err(anyhow!("the request failed")).context(WikiError::ArticleParse);
+        assert!(!is_transient(&error.unwrap_err()));
+        assert!(!is_transient(&anyhow!("something went wrong")));
+
+        let error: Result<()> =
+            Err(anyhow!(WikiError::RateLimited(30))).context("the request failed");
+        assert!(is_transient(&error.unwrap_err()));
+    }
+}
@@ -1,2 +1,7 @@
 pub mod article;
+pub mod category;
+pub mod error;
+pub mod random;
+pub mod request;
 pub mod search;
+pub mod site;
@@ -0,0 +1,74 @@
+use crate::wiki::request;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+// NOTE: The following structs are only used for deserializing the json response
+#[derive(Deserialize)]
+struct RandomResponse {
+    query: RandomQuery,
+}
+
+#[derive(Deserialize)]
+struct RandomQuery {
+    random: Vec<RandomPage>,
+}
+
+#[derive(Deserialize)]
+struct RandomPage {
+    id: i32,
+    title: String,
+}
+
+/// Requests a random article from the main namespace of the wiki at base_url and returns its id
+/// and title
+pub fn random_article(
+    base_url: &str,
+    script_path: &str,
+    timeout: Duration,
+    max_retries: u32,
+    user_agent: &str,
+) -> Result<(i32, String)> {
+    let api_url = request::join_url(base_url, &format!("{}api.php", script_path))?;
+    let url = format!(
+        "{}?action=query&list=random&rnnamespace=0&rnlimit=1&format=json",
+        api_url
+    );
+
+    log::debug!("requesting a random article from '{}'", api_url);
+    let response = request::get(&url, timeout, max_retries, user_agent)?;
+    let json = response.text()?;
+    parse_random_article(&json)
+}
+
+/// Helper function that retrieves the id and the title of the article from a random response
+fn parse_random_article(json: &str) -> Result<(i32, String)> {
+    let response: RandomResponse =
+        serde_json::from_str(json).context("the response doesn't contain a random article")?;
+    let page = response
+        .query
+        .random
+        .into_iter()
+        .next()
+        .context("the response doesn't contain a random article")?;
+    Ok((page.id, page.title))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_random_article;
+
+    #[test]
+    fn random_article() {
+        assert_eq!(
+            parse_random_article(
+                r#"{"batchcomplete":"","continue":{"rncontinue":"0.1|0.1|1|0","continue":"-||"},"query":{"random":[{"id":4567,"ns":0,"title":"Linux"}]}}"#
+            )
+            .unwrap(),
+            (4567, "Linux".to_string())
+        );
+        assert!(parse_random_article(r#"{"query":{"random":[]}}"#).is_err());
+        assert!(parse_random_article("<html></html>").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::wiki::error::WikiError;
+
+use anyhow::{anyhow, Context, Result};
+use lazy_static::lazy_static;
+use reqwest::blocking::{Client, Response};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{StatusCode, Url};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a request can take by default before it's cancelled
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often a failed request is retried by default
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+/// The path of the MediaWiki scripts used by wikipedia and the other Wikimedia projects
+pub const DEFAULT_SCRIPT_PATH: &str = "w/";
+/// The User-Agent sent with every request by default. Wikimedia asks api clients to identify
+/// themselves with their name, version and a way to contact them
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "wiki-tui/",
+    env!("CARGO_PKG_VERSION"),
+    " (https://github.com/Builditluc/wiki-tui)"
+);
+
+/// How long to wait before the first retry. The delay doubles with every retry
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+/// How much time has to pass between two requests. Navigating quickly through articles and
+/// search results then can't flood the api with requests
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait when the wiki limits the requests without saying for how long
+const RATE_LIMIT_DELAY: Duration = Duration::from_secs(5);
+/// How long a rate limited request waits at most before it's retried. Longer waits fail the
+/// request instead
+const MAX_RATE_LIMIT_DELAY: Duration = Duration::from_secs(60);
+
+/// A function that is called whenever a rate limited request waits before it's retried
+type RateLimitListener = Box<dyn Fn(Duration) + Send>;
+
+lazy_static! {
+    /// When the last request was started
+    static ref LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+    /// Is told about the waits of rate limited requests, if there is one
+    static ref RATE_LIMIT_LISTENER: Mutex<Option<RateLimitListener>> = Mutex::new(None);
+}
+
+/// Sets the function that is called with the delay whenever the wiki limits the requests and a
+/// request waits before it's retried. This way, the wait can be shown to the user
+pub fn on_rate_limit(listener: impl Fn(Duration) + Send + 'static) {
+    *RATE_LIMIT_LISTENER.lock().unwrap() = Some(Box::new(listener));
+}
+
+/// Makes a get request to a given url and checks the response for errors. Requests that time out,
+/// can't connect or fail because of the server are retried up to max_retries times, waiting twice
+/// as long before every retry. Rate limited requests wait as long as the wiki asks them to before
+/// they are retried. Any other errors are returned immediately. Every request identifies itself
+/// with the given user_agent
+pub fn get(url: &str, timeout: Duration, max_retries: u32, user_agent: &str) -> Result<Response> {
+    let client = Client::builder()
+        .timeout(timeout)
+        .user_agent(user_agent)
+        .build()
+        .context("couldn't create the http client")?;
+
+    let mut retries = 0;
+    loop {
+        wait_for_turn();
+        let error = match client.get(url).send() {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let delay = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_retry_after)
+                    .unwrap_or(RATE_LIMIT_DELAY);
+                if retries >= max_retries || delay > MAX_RATE_LIMIT_DELAY {
+                    return Err(anyhow!(WikiError::RateLimited(delay.as_secs())))
+                        .with_context(|| format!("the request to '{}' failed", url));
+                }
+
+                log::warn!(
+                    "the request to '{}' was rate limited, retrying in '{}s'",
+                    url,
+                    delay.as_secs()
+                );
+                if let Some(ref listener) = *RATE_LIMIT_LISTENER.lock().unwrap() {
+                    listener(delay);
+                }
+                thread::sleep(delay);
+                retries += 1;
+                continue;
+            }
+            Ok(response) => match response.error_for_status() {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            },
+            Err(error) => error,
+        };
+
+        let is_retryable = error.is_timeout()
+            || error.is_connect()
+            || error
+                .status()
+                .is_some_and(|status| status.is_server_error());
+        if !is_retryable || retries >= max_retries {
+            return Err(error).with_context(|| format!("the request to '{}' failed", url));
+        }
+
+        let delay = RETRY_DELAY.saturating_mul(2_u32.saturating_pow(retries));
+        log::warn!(
+            "the request to '{}' failed, retrying in '{}ms': {}",
+            url,
+            delay.as_millis(),
+            error
+        );
+        thread::sleep(delay);
+        retries += 1;
+    }
+}
+
+/// Helper function that reads how long to wait from the Retry-After header of a rate limited
+/// response. Only delays in seconds are supported, dates return None
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Helper function that blocks until MIN_REQUEST_INTERVAL has passed since the last request was
+/// started. Requests from different threads are spaced out too
+fn wait_for_turn() {
+    let mut last_request = LAST_REQUEST.lock().unwrap();
+    if let Some(wait) = last_request
+        .and_then(|last_request| MIN_REQUEST_INTERVAL.checked_sub(last_request.elapsed()))
+    {
+        thread::sleep(wait);
+    }
+    *last_request = Some(Instant::now());
+}
+
+/// Joins a path onto the url of a wiki. Absolute paths replace the path of the url, relative ones
+/// are appended to it
+pub fn join_url(base_url: &str, path: &str) -> Result<String> {
+    let url = Url::parse(base_url)
+        .with_context(|| format!("'{}' isn't a valid url", base_url))?
+        .join(path)
+        .with_context(|| format!("couldn't join '{}' onto '{}'", path, base_url))?;
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        get, join_url, parse_retry_after, wait_for_turn, DEFAULT_USER_AGENT, MIN_REQUEST_INTERVAL,
+    };
+    use crate::wiki::error::WikiError;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Helper function that starts a server answering the requests with the given responses
+    fn serve(responses: &'static [&'static str]) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 1024];
+                let _ = stream.read(&mut request);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn retry_rate_limited() {
+        let url = serve(&[
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok",
+        ]);
+        let response = get(&url, Duration::from_secs(5), 1, DEFAULT_USER_AGENT).unwrap();
+        assert_eq!(response.text().unwrap(), "ok");
+
+        let url = serve(&[
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 3600\r\nContent-Length: 0\r\n\r\n",
+        ]);
+        let error = get(&url, Duration::from_secs(5), 1, DEFAULT_USER_AGENT).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<WikiError>(),
+            Some(&WikiError::RateLimited(3600))
+        );
+    }
+
+    #[test]
+    fn retry_after() {
+        assert_eq!(parse_retry_after(" 30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn timeout() {
+        // the listener accepts connections but never responds
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let start = Instant::now();
+        assert!(get(&url, Duration::from_millis(100), 1, DEFAULT_USER_AGENT).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn rate_limit() {
+        wait_for_turn();
+        let start = Instant::now();
+        wait_for_turn();
+        assert!(start.elapsed() >= MIN_REQUEST_INTERVAL);
+    }
+
+    #[test]
+    fn join_paths() {
+        assert_eq!(
+            join_url("https://en.wikipedia.org/", "w/api.php").unwrap(),
+            "https://en.wikipedia.org/w/api.php"
+        );
+        assert_eq!(
+            join_url("https://example.org/wiki/", "api.php").unwrap(),
+            "https://example.org/wiki/api.php"
+        );
+        assert_eq!(
+            join_url("https://example.org/wiki/", "/wiki/Software").unwrap(),
+            "https://example.org/wiki/Software"
+        );
+        assert!(join_url("example.org", "api.php").is_err());
+    }
+}
@@ -1,11 +1,17 @@
+use crate::wiki::error::WikiError;
+use crate::wiki::request::{
+    self, DEFAULT_MAX_RETRIES, DEFAULT_SCRIPT_PATH, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT,
+};
 use crate::wiki::search::{
-    compiled_search::Search, info::SearchInfo, metadata::SearchMetadata,
-    properties::SearchProperties, result::SearchResult, sort_order::SearchSortOrder,
+    cache::SearchCache, compiled_search::Search, info::SearchInfo, metadata::SearchMetadata,
+    mode::SearchMode, properties::SearchProperties, result::SearchResult,
+    sort_order::SearchSortOrder,
 };
 
 use anyhow::{bail, Context, Result};
-use reqwest::blocking::{get, Response};
+use reqwest::blocking::Response;
 use serde::Deserialize;
+use std::time::Duration;
 
 /// A SearchBuilder can be used to do a search with custom configuration
 pub struct SearchBuilder {
@@ -26,8 +32,22 @@ pub struct SearchBuilder {
     prop: SearchProperties,
     /// Set the sort order of returned results
     sort: SearchSortOrder,
+    /// How the query is matched
+    mode: SearchMode,
     /// The url of wikipedia
     base_url: String,
+    /// The path of the MediaWiki scripts, relative to base_url
+    script_path: String,
+    /// How long the request can take before it's cancelled
+    timeout: Duration,
+    /// How often the request is retried when it fails
+    max_retries: u32,
+    /// The User-Agent the request identifies itself with
+    user_agent: String,
+    /// Is the network unavailable? Searching fails then
+    offline: bool,
+    /// The optional cache, finished searches are stored in and retrieved from
+    cache: Option<SearchCache>,
 }
 
 // NOTE: The following structs are only used for deserializing the json response
@@ -35,7 +55,7 @@ pub struct SearchBuilder {
 #[doc(hidden)]
 struct JsonResponse {
     #[serde(rename = "continue")]
-    continue_code: JsonResponseContinue,
+    continue_code: Option<JsonResponseContinue>,
 
     query: JsonResponseQuery,
 }
@@ -130,7 +150,14 @@ impl SearchBuilder {
                 .timestamp()
                 .snippet(),
             sort: SearchSortOrder::default(),
+            mode: SearchMode::default(),
             base_url: base_url.to_string(),
+            script_path: DEFAULT_SCRIPT_PATH.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            offline: false,
+            cache: None,
         }
     }
 
@@ -172,8 +199,47 @@ impl SearchBuilder {
         sort,
         SearchSortOrder
     );
+    build_setter!(
+        /// Set how the query is matched
+        mode,
+        SearchMode
+    );
 
-    /// Begin the search. This will return either a Search or an error
+    build_setter!(
+        /// The path of the MediaWiki scripts, relative to the url of wikipedia
+        script_path,
+        String
+    );
+    build_setter!(
+        /// How long the request can take before it's cancelled
+        timeout,
+        Duration
+    );
+    build_setter!(
+        /// How often the request is retried when it fails
+        max_retries,
+        u32
+    );
+    build_setter!(
+        /// The User-Agent the request identifies itself with
+        user_agent,
+        String
+    );
+    build_setter!(
+        /// Don't use the network. Searching isn't possible then
+        offline,
+        bool
+    );
+
+    /// Use a cache for storing and retrieving the search
+    #[must_use]
+    pub fn cache(mut self, cache: SearchCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Begin the search. This will return either a Search or an error. If the search is cached,
+    /// the cached search is returned without making a request
     pub fn search(&self) -> Result<Search> {
         log::info!("search was called");
 
@@ -185,14 +251,30 @@ impl SearchBuilder {
         log::debug!("building the url");
         let url = self.build_url()?;
 
+        // the url contains everything the results depend on, so it's the key of the cache
+        if let Some(search) = self.cache.as_ref().and_then(|cache| cache.get(&url)) {
+            log::info!("found the search '{}' in the cache", url);
+            return Ok(search);
+        }
+
+        // searching always needs the network
+        if self.offline {
+            bail!(WikiError::Offline);
+        }
+
         // make the request
         log::debug!("making the request to '{}'", url);
         let response = self.make_request(&url)?;
 
         // deserialize the response and return the finished search
         log::debug!("deserializing the resposne");
-        let search = self.deserialize_response(response.text()?);
-        if search.is_ok() {
+        let search = self
+            .deserialize_response(response.text()?)
+            .context(WikiError::SearchParse);
+        if let Ok(ref search) = search {
+            if let Some(ref cache) = self.cache {
+                cache.put(&url, search);
+            }
             log::info!("search finished successfully");
         }
 
@@ -207,10 +289,11 @@ impl SearchBuilder {
         }
 
         // just build the url, very simple
+        let api_url = request::join_url(&self.base_url, &format!("{}api.php", self.script_path))?;
         Ok(format!(
-            "{}w/api.php?action=query&format=json&list=search&srsearch={}&srnamespace={}&srlimit={}&sroffset={}{}{}{}",
-            self.base_url,
-            self.query,
+            "{}?action=query&format=json&list=search&srsearch={}&srnamespace={}&srlimit={}&sroffset={}{}{}{}",
+            api_url,
+            self.mode.search_query(&self.query),
             self.namespace,
             self.limit,
             self.offset,
@@ -222,8 +305,7 @@ impl SearchBuilder {
 
     /// A helper function that makes a get request to a given url and returns its response
     fn make_request(&self, url: &str) -> Result<Response> {
-        // just do the request, nothing special here
-        Ok(get(url)?.error_for_status()?)
+        request::get(url, self.timeout, self.max_retries, &self.user_agent)
     }
 
     /// A helper function that deserializes a json string into a Search. Any errors it encounters
@@ -234,7 +316,9 @@ impl SearchBuilder {
             serde_json::from_str(&json).context("failed to deserialize the response")?;
 
         // retrieve the values of importance
-        let search_offset = deserialized_json.continue_code.offset as usize;
+        let search_offset = deserialized_json
+            .continue_code
+            .map(|continue_code| continue_code.offset as usize);
         let search_info = self.deserialize_search_info(deserialized_json.query.info.take());
         let search_results =
             self.deserialize_search_results(std::mem::take(&mut deserialized_json.query.search));
@@ -325,9 +409,11 @@ mod tests {
 
     #[test]
     fn correct_url() {
-        use super::SearchBuilder;
+        use super::{SearchBuilder, SearchMode};
         assert!(SearchBuilder::new(BASE_URL).build_url().is_err());
         assert_eq!(SearchBuilder::new(BASE_URL).query("meaning".to_string()).build_url().unwrap(), "https://en.wikipedia.org/w/api.php?action=query&format=json&list=search&srsearch=meaning&srnamespace=0&srlimit=10&sroffset=0&srinfo=totalhits|suggestion|rewrittenquery&srprop=size|wordcount|timestamp|snippet&srsort=relevance".to_string());
+        assert_eq!(SearchBuilder::new(BASE_URL).mode(SearchMode::TitlePrefix).query("meaning".to_string()).build_url().unwrap(), "https://en.wikipedia.org/w/api.php?action=query&format=json&list=search&srsearch=prefix:meaning&srnamespace=0&srlimit=10&sroffset=0&srinfo=totalhits|suggestion|rewrittenquery&srprop=size|wordcount|timestamp|snippet&srsort=relevance".to_string());
+        assert_eq!(SearchBuilder::new("https://wiki.example.org/").script_path(String::new()).query("meaning".to_string()).build_url().unwrap(), "https://wiki.example.org/api.php?action=query&format=json&list=search&srsearch=meaning&srnamespace=0&srlimit=10&sroffset=0&srinfo=totalhits|suggestion|rewrittenquery&srprop=size|wordcount|timestamp|snippet&srsort=relevance".to_string());
     }
 
     #[test]
@@ -338,6 +424,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deserialize_last_page() -> anyhow::Result<()> {
+        use super::SearchBuilder;
+        let response =
+            SEARCH_RESPONSE.replace(r#""continue":{"sroffset":2,"continue":"-||"},"#, "");
+        let search = SearchBuilder::new(BASE_URL).deserialize_response(response)?;
+        assert_eq!(search.search_offset(), None);
+
+        Ok(())
+    }
+
     #[test]
     fn deserialize_missing_fields() {
         use super::SearchBuilder;
@@ -0,0 +1,109 @@
+use crate::wiki::search::compiled_search::Search;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A cache that keeps finished searches in memory for a short time, so searching for the same
+/// query again doesn't need the network. Clones of a SearchCache share the same searches
+#[derive(Clone)]
+pub struct SearchCache {
+    /// The cached searches with the time they were stored at, keyed by their url
+    searches: Arc<Mutex<HashMap<String, (Instant, Search)>>>,
+    /// How long a cached search is valid before it has to be searched again
+    ttl: Duration,
+    /// How many searches are cached at most. When the cache is full, the oldest one is removed
+    capacity: usize,
+}
+
+impl SearchCache {
+    /// Creates a new, empty SearchCache
+    pub fn new(ttl: Duration, capacity: usize) -> Self {
+        log::debug!("creating a new instance of SearchCache");
+        SearchCache {
+            searches: Arc::new(Mutex::new(HashMap::new())),
+            ttl,
+            capacity,
+        }
+    }
+
+    /// Retrieves the cached search for a given key. If the search isn't cached or is older than
+    /// the ttl, None will be returned
+    pub fn get(&self, key: &str) -> Option<Search> {
+        let mut searches = self.searches.lock().unwrap();
+        match searches.get(key) {
+            Some((stored_at, search)) if stored_at.elapsed() < self.ttl => Some(search.clone()),
+            Some(_) => {
+                log::debug!("the cached search '{}' is stale", key);
+                searches.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Stores a search with a given key. Stale searches are removed first and if the cache is
+    /// still full, the oldest search makes room for it
+    pub fn put(&self, key: &str, search: &Search) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut searches = self.searches.lock().unwrap();
+        searches.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+        if searches.len() >= self.capacity && !searches.contains_key(key) {
+            let oldest = searches
+                .iter()
+                .min_by_key(|(_, (stored_at, _))| *stored_at)
+                .map(|(key, _)| key.to_string());
+            if let Some(oldest) = oldest {
+                searches.remove(&oldest);
+            }
+        }
+        searches.insert(key.to_string(), (Instant::now(), search.clone()));
+        log::debug!("cached the search '{}'", key);
+    }
+
+    /// Removes every cached search
+    pub fn clear(&self) {
+        log::debug!("clearing the search cache");
+        self.searches.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchCache;
+    use crate::wiki::search::{Search, SearchInfo};
+    use std::time::Duration;
+
+    fn search(offset: usize) -> Search {
+        Search::new(Some(offset), SearchInfo::new(None, None, None), Vec::new())
+    }
+
+    #[test]
+    fn cache() {
+        let cache = SearchCache::new(Duration::from_secs(60), 2);
+        assert!(cache.get("rust").is_none());
+
+        cache.put("rust", &search(1));
+        cache.put("go", &search(2));
+        assert_eq!(cache.get("rust").unwrap().search_offset(), Some(1));
+
+        // the oldest search makes room for the new one
+        cache.put("zig", &search(3));
+        assert!(cache.get("rust").is_none());
+        assert!(cache.get("go").is_some());
+
+        // clones share the same searches
+        cache.clone().clear();
+        assert!(cache.get("go").is_none());
+    }
+
+    #[test]
+    fn stale_searches() {
+        let cache = SearchCache::new(Duration::ZERO, 2);
+        cache.put("rust", &search(1));
+        assert!(cache.get("rust").is_none());
+    }
+}
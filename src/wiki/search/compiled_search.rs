@@ -4,8 +4,8 @@ use crate::wiki::search::{info::SearchInfo, result::SearchResult};
 /// used for continuing the search
 #[derive(Clone)]
 pub struct Search {
-    /// Use this offset to continue the search
-    search_offset: usize,
+    /// Use this offset to continue the search, if there are more results
+    search_offset: Option<usize>,
     /// The metada of the search
     info: SearchInfo,
     /// The results of the search
@@ -14,7 +14,7 @@ pub struct Search {
 
 impl Search {
     /// Creates a new Search with a given offset, metadata and resutls
-    pub fn new(search_offset: usize, info: SearchInfo, results: Vec<SearchResult>) -> Self {
+    pub fn new(search_offset: Option<usize>, info: SearchInfo, results: Vec<SearchResult>) -> Self {
         Search {
             search_offset,
             info,
@@ -22,9 +22,10 @@ impl Search {
         }
     }
 
-    /// The search offset required for the next search
-    pub fn search_offset(&self) -> &usize {
-        &self.search_offset
+    /// The search offset required for the next search. If there are no more results, None is
+    /// returned
+    pub fn search_offset(&self) -> Option<usize> {
+        self.search_offset
     }
 
     /// The metadata of the search
@@ -1,5 +1,7 @@
 mod builder;
+mod cache;
 mod metadata;
+mod mode;
 mod properties;
 mod sort_order;
 
@@ -12,6 +14,8 @@ pub type SearchInfo = info::SearchInfo;
 pub type SearchResult = result::SearchResult;
 
 pub type SearchBuilder = builder::SearchBuilder;
+pub type SearchCache = cache::SearchCache;
 pub type SearchMetadata = metadata::SearchMetadata;
+pub type SearchMode = mode::SearchMode;
 pub type SearchSortOrder = sort_order::SearchSortOrder;
 pub type SearchProperties = properties::SearchProperties;
@@ -0,0 +1,53 @@
+/// SearchMode can be used to configure how the query of a search is matched. The default one is a
+/// full-text search
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum SearchMode {
+    /// Search the titles and the content of the articles
+    #[default]
+    FullText,
+    /// Search for articles whose titles start with the query
+    TitlePrefix,
+    /// Search for articles containing the query exactly as it is
+    Exact,
+}
+
+impl SearchMode {
+    /// Returns the mode that follows this one, after the last one the first one follows
+    pub fn next(self) -> Self {
+        match self {
+            SearchMode::FullText => SearchMode::TitlePrefix,
+            SearchMode::TitlePrefix => SearchMode::Exact,
+            SearchMode::Exact => SearchMode::FullText,
+        }
+    }
+
+    /// Converts a query into the value of srsearch matching the way this mode wants it. Title
+    /// searches with srwhat=title are disabled on the Wikimedia wikis, so the prefix keyword is
+    /// used instead
+    pub fn search_query(&self, query: &str) -> String {
+        match self {
+            SearchMode::FullText => query.to_string(),
+            SearchMode::TitlePrefix => format!("prefix:{}", query),
+            SearchMode::Exact => format!("\"{}\"", query.trim_matches('"')),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SearchMode;
+
+    #[test]
+    fn search_queries() {
+        assert_eq!(SearchMode::FullText.search_query("rust"), "rust");
+        assert_eq!(SearchMode::TitlePrefix.search_query("rust"), "prefix:rust");
+        assert_eq!(SearchMode::Exact.search_query("rust lang"), "\"rust lang\"");
+        assert_eq!(SearchMode::Exact.search_query("\"rust\""), "\"rust\"");
+    }
+
+    #[test]
+    fn next() {
+        assert_eq!(SearchMode::default().next(), SearchMode::TitlePrefix);
+        assert_eq!(SearchMode::Exact.next(), SearchMode::FullText);
+    }
+}
@@ -0,0 +1,67 @@
+use crate::wiki::request;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::time::Duration;
+
+// NOTE: The following structs are only used for deserializing the json response
+#[derive(Deserialize)]
+struct SiteInfoResponse {
+    query: SiteInfoQuery,
+}
+
+#[derive(Deserialize)]
+struct SiteInfoQuery {
+    general: SiteInfoGeneral,
+}
+
+#[derive(Deserialize)]
+struct SiteInfoGeneral {
+    sitename: String,
+}
+
+/// Checks that the MediaWiki api of the wiki at base_url can be reached and returns the name of
+/// the wiki. Fails with an error explaining what went wrong otherwise
+pub fn check_api(
+    base_url: &str,
+    script_path: &str,
+    timeout: Duration,
+    max_retries: u32,
+    user_agent: &str,
+) -> Result<String> {
+    let api_url = request::join_url(base_url, &format!("{}api.php", script_path))?;
+    let url = format!(
+        "{}?action=query&meta=siteinfo&siprop=general&format=json",
+        api_url
+    );
+
+    log::debug!("checking the api at '{}'", api_url);
+    let response = request::get(&url, timeout, max_retries, user_agent)
+        .with_context(|| format!("couldn't reach the api at '{}'", api_url))?;
+    let json = response.text()?;
+    parse_site_name(&json).with_context(|| format!("'{}' isn't a MediaWiki api", api_url))
+}
+
+/// Helper function that retrieves the name of the wiki from a siteinfo response
+fn parse_site_name(json: &str) -> Result<String> {
+    let response: SiteInfoResponse =
+        serde_json::from_str(json).context("the response doesn't contain any site information")?;
+    Ok(response.query.general.sitename)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_site_name;
+
+    #[test]
+    fn site_name() {
+        assert_eq!(
+            parse_site_name(
+                r#"{"batchcomplete":"","query":{"general":{"mainpage":"Main Page","sitename":"Wiktionary"}}}"#
+            )
+            .unwrap(),
+            "Wiktionary"
+        );
+        assert!(parse_site_name("<html></html>").is_err());
+    }
+}